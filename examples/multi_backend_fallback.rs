@@ -1,68 +1,53 @@
 //! Multi-backend fallback example.
 //!
-//! Demonstrates trying multiple backends until one succeeds.
-//! Useful for applications that can work with different secret managers.
+//! Demonstrates reading from whichever of several backends is available.
+//! Useful for applications that can work with different secret managers,
+//! such as a laptop that backs up a remote vault with a local one.
 //!
 //! Run with: cargo run --example multi_backend_fallback --features "pass,bitwarden"
 
-use vaultmux::{factory, Backend, BackendType, Config, VaultmuxError};
-
-async fn try_backend(backend_type: BackendType) -> vaultmux::Result<Box<dyn Backend>> {
-    println!("Trying backend: {:?}...", backend_type);
-
-    let config = Config::new(backend_type);
-    let mut backend = factory::new_backend(config)?;
-
-    // Try to initialize
-    backend.init().await?;
-
-    // Try to authenticate
-    let session = backend.authenticate().await?;
-
-    // Test if we can actually use it
-    let _ = backend.list_items(&*session).await?;
-
-    println!("✓ Backend {:?} is available and working!", backend_type);
-    Ok(backend)
-}
+use std::time::Duration;
+use vaultmux::middleware::{FallbackBackend, WritePolicy};
+use vaultmux::{factory, Backend, BackendType, Config};
 
 #[tokio::main]
 async fn main() -> vaultmux::Result<()> {
     println!("=== Multi-Backend Fallback Example ===\n");
 
-    // List of backends to try, in order of preference
+    // Backends to read from, in order of preference
     let backends_to_try = vec![
-        BackendType::Pass,
         BackendType::Bitwarden,
         BackendType::OnePassword,
-        BackendType::Pass, // Fallback to mock for testing
+        BackendType::Pass,
     ];
 
-    let mut selected_backend: Option<Box<dyn Backend>> = None;
+    let mut backend = FallbackBackend::new()
+        .with_write_policy(WritePolicy::PrimaryOnly)
+        .with_failure_threshold(2)
+        .with_probe_interval(Duration::from_secs(60));
 
     for backend_type in backends_to_try {
-        match try_backend(backend_type).await {
-            Ok(backend) => {
-                selected_backend = Some(backend);
-                break;
-            }
-            Err(e) => {
-                println!("✗ Backend {:?} unavailable: {}", backend_type, e);
-                continue;
+        match factory::new_backend(Config::new(backend_type)) {
+            Ok(inner) => {
+                println!("Adding backend: {:?}", backend_type);
+                backend = backend.with_backend(inner);
             }
+            Err(e) => println!("✗ Backend {:?} unavailable: {}", backend_type, e),
         }
     }
 
-    let mut backend = selected_backend
-        .ok_or_else(|| VaultmuxError::Other(anyhow::anyhow!("No available backends found")))?;
-
-    println!("\n=== Using backend: {} ===\n", backend.name());
+    backend.init().await?;
 
-    // Now use the selected backend
+    // Succeeds if any backend authenticates; the rest are probed in the background
     let session = backend.authenticate().await?;
 
-    // Example operation: List all secrets
-    println!("Listing secrets...");
+    for (name, healthy) in backend.health() {
+        let status = if healthy { "healthy" } else { "unhealthy" };
+        println!("  {}: {}", name, status);
+    }
+
+    // Each read goes to the first backend that answers
+    println!("\nListing secrets...");
     let items = backend.list_items(&*session).await?;
     println!("Found {} secret(s)", items.len());
 
@@ -4,10 +4,20 @@
 //! must satisfy. The trait provides a unified interface for authentication,
 //! item management, and location (folder/vault) operations.
 
-use crate::{Item, Result, Session};
+use crate::watch::{self, ChangeEvent, WatchOptions};
+use crate::{
+    encoding, Capabilities, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions, Result,
+    Session, VaultmuxError,
+};
 use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Maximum number of requests the batch operations keep in flight.
+pub(crate) const BATCH_CONCURRENCY: usize = 8;
+
 /// Backend represents a secret storage backend.
 ///
 /// All implementations must be `Send + Sync` to support concurrent access
@@ -49,6 +59,17 @@ pub trait Backend: Send + Sync {
     /// Returns the backend name (e.g., "bitwarden", "pass", "awssecrets").
     fn name(&self) -> &str;
 
+    /// Describes the optional features this backend supports.
+    ///
+    /// Check this before calling optional operations (locations, versioning,
+    /// tags) to fail early with a clear message instead of handling
+    /// [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported).
+    ///
+    /// The default reports no optional features.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     // ========================================================================
     // Lifecycle
     // ========================================================================
@@ -149,7 +170,8 @@ pub trait Backend: Send + Sync {
 
     /// Lists all items in the vault.
     ///
-    /// Note: For large vaults, this may be slow. Consider using location-based
+    /// Note: For large vaults, this may be slow. Consider
+    /// [`list_items_stream()`](Backend::list_items_stream), or location-based
     /// filtering with `list_items_in_location()` if available.
     ///
     /// # Errors
@@ -158,6 +180,53 @@ pub trait Backend: Send + Sync {
     ///   Session is no longer valid
     async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>>;
 
+    /// Streams items, filtered and limited by `options`.
+    ///
+    /// - AWS: Fetches pages as the stream is polled, with the prefix as a
+    ///   `name` filter
+    /// - GCP: Fetches pages as the stream is polled, with the prefix in the
+    ///   list `filter`
+    /// - Bitwarden: A single `bw list items --search <prefix>`
+    /// - Other backends: Filters the result of [`list_items()`](Backend::list_items)
+    ///
+    /// Stopping early (or setting [`limit`](ListOptions::limit)) avoids
+    /// fetching the remaining pages.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::TryStreamExt;
+    /// use vaultmux::{factory, Backend, BackendType, Config, ListOptions};
+    ///
+    /// # async fn example() -> vaultmux::Result<()> {
+    /// let mut backend = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+    /// backend.init().await?;
+    /// let session = backend.authenticate().await?;
+    ///
+    /// let options = ListOptions::new().with_prefix("prod/").with_limit(50);
+    /// let mut items = backend.list_items_stream(&options, &*session);
+    /// while let Some(item) = items.try_next().await? {
+    ///     println!("{}", item.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors are yielded as stream items; the stream ends after the first one.
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        let items = futures::stream::once(self.list_items(session))
+            .map_ok(|items| futures::stream::iter(items.into_iter().map(Ok)))
+            .try_flatten();
+
+        options.apply(items)
+    }
+
     // ========================================================================
     // Mutations
     // ========================================================================
@@ -165,7 +234,8 @@ pub trait Backend: Send + Sync {
     /// Creates a new item.
     ///
     /// The item type is always [`ItemType::SecureNote`](crate::ItemType::SecureNote)
-    /// for simplicity. The `content` is stored in the notes field.
+    /// for simplicity. The `content` is stored in the notes field. Use
+    /// [`create_item_full()`](Backend::create_item_full) for structured items.
     ///
     /// # Errors
     ///
@@ -191,6 +261,9 @@ pub trait Backend: Send + Sync {
 
     /// Deletes an item.
     ///
+    /// Whether the item can be recovered afterwards is backend-specific; use
+    /// [`delete_item_with()`](Backend::delete_item_with) to choose.
+    ///
     /// # Errors
     ///
     /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
@@ -199,6 +272,583 @@ pub trait Backend: Send + Sync {
     ///   Session is no longer valid
     async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()>;
 
+    // ========================================================================
+    // Batch Operations
+    // ========================================================================
+
+    /// Retrieves the notes of several items.
+    ///
+    /// Results are returned in the order of `names`, one per name, so a
+    /// missing item doesn't fail the whole batch.
+    ///
+    /// - AWS: `BatchGetSecretValue`, 20 secrets per call
+    /// - Bitwarden: A single `bw list items`
+    /// - Other backends: Up to 8 concurrent [`get_notes()`](Backend::get_notes) calls
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if the batch as a whole fails (for example
+    /// [`VaultmuxError::NotAuthenticated`](crate::VaultmuxError::NotAuthenticated)).
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        // Futures don't start until polled, so only BATCH_CONCURRENCY run at once
+        let lookups: Vec<_> = names
+            .iter()
+            .map(|name| self.get_notes(name, session))
+            .collect();
+
+        Ok(futures::stream::iter(lookups)
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await)
+    }
+
+    /// Creates or updates several items from `(name, content)` pairs.
+    ///
+    /// Results are returned in the order of `entries`. AWS writes up to 8
+    /// secrets concurrently; other backends write one item at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if the batch as a whole fails.
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(entries.len());
+        for (name, content) in entries {
+            let result = match self.item_exists(name, session).await {
+                Ok(true) => self.update_item(name, content, session).await,
+                Ok(false) => self.create_item(name, content, session).await,
+                Err(e) => Err(e),
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Deletes several items.
+    ///
+    /// Results are returned in the order of `names`. AWS deletes up to 8
+    /// secrets concurrently; other backends delete one item at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if the batch as a whole fails.
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(names.len());
+        for name in names {
+            results.push(self.delete_item(name, session).await);
+        }
+        Ok(results)
+    }
+
+    // ========================================================================
+    // Structured Items
+    // ========================================================================
+
+    /// Creates a new item from a complete [`Item`], honouring its
+    /// [`item_type`](Item::item_type) and [`fields`](Item::fields).
+    ///
+    /// Backends with native item types (Bitwarden, 1Password) write logins,
+    /// cards, identities and SSH keys with their native fields. The default
+    /// implementation stores the item as a single value using
+    /// [`Item::encode_content`], which [`get_item()`](Backend::get_item)
+    /// decodes back into type and fields.
+    ///
+    /// Non-empty [`tags`](Item::tags) are written with
+    /// [`set_tags()`](Backend::set_tags).
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::AlreadyExists`](crate::VaultmuxError::AlreadyExists):
+    ///   An item with this name already exists
+    /// - [`VaultmuxError::SessionExpired`](crate::VaultmuxError::SessionExpired):
+    ///   Session is no longer valid
+    /// - [`VaultmuxError::InvalidItemName`](crate::VaultmuxError::InvalidItemName):
+    ///   Item name contains invalid characters
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Item has tags but the backend does not support them (the value
+    ///   has already been written)
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        let content = item.encode_content()?;
+        self.create_item(&item.name, &content, session).await?;
+        if !item.tags.is_empty() {
+            self.set_tags(&item.name, &item.tags, session).await?;
+        }
+        Ok(())
+    }
+
+    /// Replaces an existing item's type, fields and notes from a complete [`Item`].
+    ///
+    /// Non-empty [`tags`](Item::tags) replace the item's tags; an item with
+    /// no tags leaves existing tags untouched.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item does not exist
+    /// - [`VaultmuxError::SessionExpired`](crate::VaultmuxError::SessionExpired):
+    ///   Session is no longer valid
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        let content = item.encode_content()?;
+        self.update_item(&item.name, &content, session).await?;
+        if !item.tags.is_empty() {
+            self.set_tags(&item.name, &item.tags, session).await?;
+        }
+        Ok(())
+    }
+
+    // ========================================================================
+    // Conditional Writes
+    // ========================================================================
+
+    /// Updates an item only if it is still at `expected_revision`.
+    ///
+    /// Pass the [`revision`](Item::revision) from an earlier
+    /// [`get_item()`](Backend::get_item). If the item has changed since, the
+    /// update is rejected with [`VaultmuxError::Conflict`](crate::VaultmuxError::Conflict)
+    /// and the caller should re-read and retry.
+    ///
+    /// The default implementation re-reads the item and compares revisions
+    /// before writing ([`CompareAndSwap::BestEffort`](crate::CompareAndSwap::BestEffort)).
+    /// See [`Capabilities::compare_and_swap`](crate::Capabilities::compare_and_swap).
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::Conflict`](crate::VaultmuxError::Conflict):
+    ///   Item changed since `expected_revision`
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item does not exist, or (AWS) has no version `expected_revision`
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend does not track revisions
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let current = self.get_item(name, session).await?;
+        match current.revision.as_deref() {
+            None => Err(VaultmuxError::NotSupported(format!(
+                "{} does not track item revisions",
+                self.name()
+            ))),
+            Some(revision) if revision != expected_revision => {
+                Err(VaultmuxError::Conflict(format!(
+                    "{} is at revision {}, expected {}",
+                    name, revision, expected_revision
+                )))
+            }
+            Some(_) => self.update_item(name, content, session).await,
+        }
+    }
+
+    // ========================================================================
+    // Binary Values
+    // ========================================================================
+
+    /// Retrieves an item's value as raw bytes.
+    ///
+    /// Values written with [`put_bytes()`](Backend::put_bytes) are returned
+    /// exactly as stored. Plain text values are returned as their UTF-8 bytes.
+    ///
+    /// The default implementation decodes the marked base64 form produced by
+    /// [`encoding::encode_binary`](crate::encoding::encode_binary). Backends
+    /// with native binary storage (AWS, GCP, pass) override this.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item does not exist
+    /// - [`VaultmuxError::SessionExpired`](crate::VaultmuxError::SessionExpired):
+    ///   Session is no longer valid
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        let notes = self.get_notes(name, session).await?;
+        encoding::decode_value(&notes)
+    }
+
+    /// Stores raw bytes, creating the item or replacing its current value.
+    ///
+    /// Callers pass the bytes as-is; any encoding the backend needs is
+    /// applied internally and undone by [`get_bytes()`](Backend::get_bytes).
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::SessionExpired`](crate::VaultmuxError::SessionExpired):
+    ///   Session is no longer valid
+    /// - [`VaultmuxError::InvalidItemName`](crate::VaultmuxError::InvalidItemName):
+    ///   Item name contains invalid characters
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        let encoded = encoding::encode_binary(data);
+        if self.item_exists(name, session).await? {
+            self.update_item(name, &encoded, session).await
+        } else {
+            self.create_item(name, &encoded, session).await
+        }
+    }
+
+    // ========================================================================
+    // Versioning (Optional)
+    // ========================================================================
+
+    /// Lists the versions of an item, newest first.
+    ///
+    /// - AWS: Secret version IDs with their staging labels
+    /// - GCP: Secret versions with their state
+    /// - Azure: Secret versions
+    /// - pass: Commits touching the entry (`pass git log`)
+    /// - Other backends: Return [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported)
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item does not exist
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend does not keep version history
+    async fn list_versions(&self, _name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        Err(VaultmuxError::NotSupported(format!(
+            "{} does not keep version history",
+            self.name()
+        )))
+    }
+
+    /// Retrieves the stored value of a specific version.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item or version does not exist
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend does not keep version history
+    async fn get_version(
+        &self,
+        _name: &str,
+        _version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<String> {
+        Err(VaultmuxError::NotSupported(format!(
+            "{} does not keep version history",
+            self.name()
+        )))
+    }
+
+    /// Disables a version so it can no longer be read.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item or version does not exist
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend cannot disable individual versions
+    async fn disable_version(
+        &mut self,
+        _name: &str,
+        _version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        Err(VaultmuxError::NotSupported(format!(
+            "{} cannot disable versions",
+            self.name()
+        )))
+    }
+
+    /// Irreversibly destroys a version's value.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item or version does not exist
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend cannot destroy individual versions
+    async fn destroy_version(
+        &mut self,
+        _name: &str,
+        _version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        Err(VaultmuxError::NotSupported(format!(
+            "{} cannot destroy versions",
+            self.name()
+        )))
+    }
+
+    /// Makes a previous version's value current again.
+    ///
+    /// The default implementation reads the version with
+    /// [`get_version()`](Backend::get_version) and writes it back as a new
+    /// version. AWS instead moves the `AWSCURRENT` staging label.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item or version does not exist
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend does not keep version history
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let value = self.get_version(name, version_id, session).await?;
+        self.update_item(name, &value, session).await
+    }
+
+    // ========================================================================
+    // Tags (Optional)
+    // ========================================================================
+
+    /// Retrieves an item's metadata tags.
+    ///
+    /// The default implementation returns [`Item::tags`] from
+    /// [`get_item()`](Backend::get_item), which is empty on backends without
+    /// tag support.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item does not exist
+    /// - [`VaultmuxError::SessionExpired`](crate::VaultmuxError::SessionExpired):
+    ///   Session is no longer valid
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        Ok(self.get_item(name, session).await?.tags)
+    }
+
+    /// Replaces an item's metadata tags.
+    ///
+    /// - AWS: Secret tags
+    /// - GCP: Secret labels (keys and values must be lowercase)
+    /// - Azure: Secret tags
+    /// - 1Password: Item tags, stored as `key=value`
+    /// - Bitwarden: Custom fields named `tag:<key>`
+    /// - Other backends: Return [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported)
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item does not exist
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend does not support tags
+    async fn set_tags(
+        &mut self,
+        _name: &str,
+        _tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        Err(VaultmuxError::NotSupported(format!(
+            "{} does not support tags",
+            self.name()
+        )))
+    }
+
+    /// Lists items whose tag `key` equals `value`.
+    ///
+    /// Returned items have their [`tags`](Item::tags) populated. Backends
+    /// that can filter server-side (AWS, GCP, 1Password) override this; the
+    /// default implementation fetches the tags of every item.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::SessionExpired`](crate::VaultmuxError::SessionExpired):
+    ///   Session is no longer valid
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        let mut matches = Vec::new();
+        for mut item in self.list_items(session).await? {
+            if item.tags.is_empty() {
+                item.tags = self.get_tags(&item.name, session).await?;
+            }
+            if item.tags.get(key).map(String::as_str) == Some(value) {
+                matches.push(item);
+            }
+        }
+        Ok(matches)
+    }
+
+    // ========================================================================
+    // Rename and Move
+    // ========================================================================
+
+    /// Renames an item.
+    ///
+    /// - pass: `pass mv`
+    /// - Bitwarden: Edits the item's name
+    /// - 1Password: Edits the item's title
+    /// - Other backends: Copy to the new name, then delete the old one
+    ///
+    /// The emulated copy keeps type, fields and the tags the backend can
+    /// read back, but not version history. If deleting the old item fails,
+    /// the copy is deleted again so the item doesn't exist under both names.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   `old_name` does not exist
+    /// - [`VaultmuxError::AlreadyExists`](crate::VaultmuxError::AlreadyExists):
+    ///   `new_name` is already taken
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        copy_and_delete(self, old_name, new_name, session, |_| {}).await
+    }
+
+    /// Moves an item to another location (folder, vault, directory).
+    ///
+    /// - Bitwarden: Sets the item's folder (`location` is the folder name)
+    /// - 1Password: `op item move` to another vault
+    /// - pass: Moves the entry into directory `location`, keeping its
+    ///   last path segment (`a/b/key` to `location/key`)
+    /// - Other backends: Return [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported)
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item or location does not exist
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend does not support locations
+    async fn move_item(
+        &mut self,
+        _name: &str,
+        _location: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        Err(VaultmuxError::NotSupported(format!(
+            "{} does not support locations",
+            self.name()
+        )))
+    }
+
+    // ========================================================================
+    // Soft Delete (Optional)
+    // ========================================================================
+
+    /// Deletes an item, keeping it recoverable unless `options.force` is set.
+    ///
+    /// - AWS: Schedules deletion after the recovery window (7-30 days)
+    /// - Azure: Deletes the secret; on vaults with soft-delete enabled it
+    ///   stays recoverable through the Azure portal or CLI
+    /// - Bitwarden: Moves the item to the trash
+    /// - 1Password: Archives the item
+    /// - Other backends: Only `force` deletes are supported
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   Item does not exist
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend cannot keep deleted items (retry with `force`)
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        if options.force {
+            return self.delete_item(name, session).await;
+        }
+
+        Err(VaultmuxError::NotSupported(format!(
+            "{} cannot keep deleted items; use force to delete permanently",
+            self.name()
+        )))
+    }
+
+    /// Lists deleted items that can still be restored.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend cannot list deleted items
+    async fn list_deleted_items(&self, _session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        Err(VaultmuxError::NotSupported(format!(
+            "{} cannot list deleted items",
+            self.name()
+        )))
+    }
+
+    /// Restores a deleted item.
+    ///
+    /// # Errors
+    ///
+    /// - [`VaultmuxError::NotFound`](crate::VaultmuxError::NotFound):
+    ///   No deleted item with that name
+    /// - [`VaultmuxError::AlreadyExists`](crate::VaultmuxError::AlreadyExists):
+    ///   An item with the same name was created since
+    /// - [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported):
+    ///   Backend cannot restore deleted items
+    async fn restore_item(&mut self, _name: &str, _session: &dyn Session) -> Result<()> {
+        Err(VaultmuxError::NotSupported(format!(
+            "{} cannot restore deleted items",
+            self.name()
+        )))
+    }
+
+    // ========================================================================
+    // Watching
+    // ========================================================================
+
+    /// Streams changes to the watched items.
+    ///
+    /// The default implementation polls every
+    /// [`interval`](WatchOptions::interval): named items with
+    /// [`get_item()`](Backend::get_item), prefixes with a single
+    /// [`list_items_stream()`](Backend::list_items_stream). Items are compared
+    /// by revision or modification time where the listing reports them,
+    /// falling back to reading each item's value. The first poll only
+    /// records the current state, so events start with the first change.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use vaultmux::{factory, Backend, BackendType, ChangeKind, Config, WatchOptions};
+    ///
+    /// # async fn example() -> vaultmux::Result<()> {
+    /// let mut backend = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+    /// backend.init().await?;
+    /// let session = backend.authenticate().await?;
+    ///
+    /// let options = WatchOptions::names(["db/password"]);
+    /// let mut changes = backend.watch(&options, &*session);
+    /// while let Some(change) = changes.next().await {
+    ///     match change {
+    ///         Ok(change) if change.kind == ChangeKind::Updated => {
+    ///             let password = backend.get_notes(&change.name, &*session).await?;
+    ///             // reconnect with the rotated password
+    ///         }
+    ///         Ok(_) => {}
+    ///         Err(e) => eprintln!("watch poll failed: {}", e),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A failed poll is yielded as an error item; the stream keeps polling
+    /// and never ends on its own.
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        watch::poll(self, options, session)
+    }
+
     // ========================================================================
     // Location Management (Optional)
     // ========================================================================
@@ -257,3 +907,290 @@ pub trait Backend: Send + Sync {
         session: &dyn Session,
     ) -> Result<Vec<Item>>;
 }
+
+/// Renames an item by copying it to `new_name` and deleting the original.
+///
+/// `prepare` adjusts the copy before it is created. If the original can't
+/// be deleted, the copy is removed again so the item isn't duplicated.
+pub(crate) async fn copy_and_delete<B: Backend + ?Sized>(
+    backend: &mut B,
+    old_name: &str,
+    new_name: &str,
+    session: &dyn Session,
+    prepare: impl FnOnce(&mut Item) + Send,
+) -> Result<()> {
+    let mut item = backend.get_item(old_name, session).await?;
+    if backend.item_exists(new_name, session).await? {
+        return Err(VaultmuxError::AlreadyExists(new_name.to_string()));
+    }
+
+    item.name = new_name.to_string();
+    prepare(&mut item);
+    backend.create_item_full(&item, session).await?;
+
+    if let Err(e) = backend.delete_item(old_name, session).await {
+        if let Err(rollback) = backend.delete_item(new_name, session).await {
+            return Err(VaultmuxError::Other(anyhow::anyhow!(
+                "Failed to delete {} after copying it to {} ({}); removing the copy also failed: {}",
+                old_name,
+                new_name,
+                e,
+                rollback
+            )));
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Forwards every call to the boxed backend, so wrappers generic over
+/// [`Backend`] accept the `Box<dyn Backend>` returned by
+/// [`factory::new_backend`](crate::factory::new_backend).
+#[async_trait]
+impl<T: Backend + ?Sized> Backend for Box<T> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        (**self).capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        (**self).init().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        (**self).close().await
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        (**self).is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        (**self).authenticate().await
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        (**self).sync(session).await
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        (**self).get_item(name, session).await
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        (**self).get_notes(name, session).await
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        (**self).item_exists(name, session).await
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        (**self).list_items(session).await
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        (**self).list_items_stream(options, session)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self).create_item(name, content, session).await
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self).update_item(name, content, session).await
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        (**self).delete_item(name, session).await
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        (**self).get_many(names, session).await
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        (**self).put_many(entries, session).await
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        (**self).delete_many(names, session).await
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        (**self).create_item_full(item, session).await
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        (**self).update_item_full(item, session).await
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self)
+            .update_item_if(name, content, expected_revision, session)
+            .await
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        (**self).get_bytes(name, session).await
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        (**self).put_bytes(name, data, session).await
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        (**self).list_versions(name, session).await
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        (**self).get_version(name, version_id, session).await
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self).disable_version(name, version_id, session).await
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self).destroy_version(name, version_id, session).await
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self).rollback(name, version_id, session).await
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        (**self).get_tags(name, session).await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self).set_tags(name, tags, session).await
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        (**self).list_items_by_tag(key, value, session).await
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self).rename_item(old_name, new_name, session).await
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        (**self).move_item(name, location, session).await
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        (**self).delete_item_with(name, options, session).await
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        (**self).list_deleted_items(session).await
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        (**self).restore_item(name, session).await
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        (**self).watch(options, session)
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        (**self).list_locations(session).await
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        (**self).location_exists(name, session).await
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        (**self).create_location(name, session).await
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        (**self)
+            .list_items_in_location(loc_type, loc_value, session)
+            .await
+    }
+}
@@ -1,12 +1,79 @@
 //! AWS Secrets Manager backend implementation.
 
+use crate::backend::BATCH_CONCURRENCY;
 use crate::backends::aws::AWSSession;
+use crate::encoding::{decode_value, encode_binary};
+use crate::options::paginate;
+use crate::telemetry;
 use crate::validation::validate_item_name;
-use crate::{Backend, Config, Item, ItemType, Result, Session, VaultmuxError};
+use crate::{
+    Backend, Capabilities, CompareAndSwap, Config, DeleteOptions, DeletedItem, Item, ItemType,
+    ItemVersion, ListOptions, NameCharset, Result, Session, VaultmuxError, VersionState,
+};
 use async_trait::async_trait;
+use aws_sdk_secretsmanager::operation::RequestId;
+use aws_sdk_secretsmanager::primitives::Blob;
+use aws_sdk_secretsmanager::types::{Filter, FilterNameStringType, SecretListEntry, Tag};
 use aws_sdk_secretsmanager::Client;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Staging label AWS attaches to the current version of a secret.
+const AWS_CURRENT: &str = "AWSCURRENT";
+
+/// Staging label for versions written by `update_item_if` until they
+/// become current.
+const VAULTMUX_PENDING: &str = "VAULTMUXPENDING";
+
+/// Maximum number of secrets per `ListSecrets` page.
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Maximum number of secrets per `BatchGetSecretValue` call.
+const BATCH_GET_LIMIT: usize = 20;
+
+/// Maximum size of a secret value (64 KiB).
+const MAX_SECRET_SIZE: usize = 65536;
+
+/// Error codes AWS returns when a request is rate limited.
+const THROTTLING_CODES: &[&str] = &[
+    "ThrottlingException",
+    "TooManyRequestsException",
+    "RequestLimitExceeded",
+];
+
+/// Records the AWS request ID of a response or error on the current
+/// tracing span.
+trait RecordRequestId: Sized {
+    fn record_request_id(self) -> Self;
+}
+
+impl<T: RequestId> RecordRequestId for T {
+    fn record_request_id(self) -> Self {
+        telemetry::record_request_id(self.request_id());
+        self
+    }
+}
+
+/// Converts an SDK error, keeping rate limiting distinguishable.
+///
+/// The SDK's `Display` output often omits the error code, so the code is
+/// looked up in the `Debug` output instead.
+fn sdk_error<E: std::fmt::Debug + std::fmt::Display>(context: &str, err: E) -> VaultmuxError {
+    let message = format!("{}: {}", context, err);
+    let detail = format!("{:?}", err);
+    if THROTTLING_CODES.iter().any(|code| detail.contains(code)) {
+        VaultmuxError::Throttled(message)
+    } else {
+        VaultmuxError::Other(anyhow::anyhow!(message))
+    }
+}
+
+/// Recovery window AWS allows for scheduled deletions, in days.
+const MIN_RECOVERY_DAYS: i64 = 7;
+const MAX_RECOVERY_DAYS: i64 = 30;
+
 /// AWS Secrets Manager backend.
 ///
 /// Integrates with AWS Secrets Manager using the official AWS SDK.
@@ -50,6 +117,247 @@ impl AWSBackend {
             format!("{}{}", self.prefix, name)
         }
     }
+
+    /// Converts AWS tags into a key/value map.
+    fn tag_map(tags: &[Tag]) -> HashMap<String, String> {
+        tags.iter()
+            .filter_map(|t| Some((t.key()?.to_string(), t.value().unwrap_or("").to_string())))
+            .collect()
+    }
+
+    /// Writes a secret value, creating the secret if it doesn't exist.
+    ///
+    /// Takes `&self` so that `put_many` can run several writes at once.
+    async fn put_value(&self, name: &str, content: &str) -> Result<()> {
+        validate_item_name(name)?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let secret_name = self.secret_name(name);
+
+        let updated = client
+            .put_secret_value()
+            .secret_id(&secret_name)
+            .secret_string(content)
+            .send()
+            .await
+            .record_request_id();
+
+        match updated {
+            Ok(_) => Ok(()),
+            Err(e) if format!("{:?}", e).contains("ResourceNotFoundException") => {
+                client
+                    .create_secret()
+                    .name(&secret_name)
+                    .secret_string(content)
+                    .send()
+                    .await
+                    .record_request_id()
+                    .map_err(|e| sdk_error("Failed to create secret", e))?;
+                Ok(())
+            }
+            Err(e) => Err(sdk_error("Failed to update secret", e)),
+        }
+    }
+
+    /// Permanently deletes a secret.
+    ///
+    /// Takes `&self` so that `delete_many` can run several deletes at once.
+    async fn delete_secret(&self, name: &str) -> Result<()> {
+        validate_item_name(name)?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        client
+            .delete_secret()
+            .secret_id(self.secret_name(name))
+            .force_delete_without_recovery(true)
+            .send()
+            .await
+            .record_request_id()
+            .map_err(|e| {
+                let error_str = format!("{:?}", e);
+                if error_str.contains("ResourceNotFoundException") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    sdk_error("Failed to delete secret", e)
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// Lists secrets under the prefix, optionally narrowed by server-side filters.
+    async fn list_secrets(&self, filters: Vec<Filter>) -> Result<Vec<Item>> {
+        let entries = self.list_secret_entries(filters, false).await?;
+        Ok(entries
+            .iter()
+            .map(|secret| self.entry_item(secret))
+            .collect())
+    }
+
+    /// Fetches every page of `ListSecrets` and keeps the secrets under the prefix.
+    ///
+    /// With `include_planned_deletion`, secrets scheduled for deletion are
+    /// returned as well.
+    async fn list_secret_entries(
+        &self,
+        filters: Vec<Filter>,
+        include_planned_deletion: bool,
+    ) -> Result<Vec<SecretListEntry>> {
+        let mut entries = Vec::new();
+        let mut next_token: Option<String> = None;
+
+        loop {
+            let (page, token) = self
+                .list_secrets_page(
+                    &self.prefix,
+                    &filters,
+                    include_planned_deletion,
+                    None,
+                    next_token,
+                )
+                .await?;
+            entries.extend(page);
+
+            // Check for more results
+            next_token = token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Fetches one page of `ListSecrets`, keeping the secrets whose full name
+    /// starts with `name_prefix`.
+    ///
+    /// The prefix is also sent as a `name` filter, which AWS matches
+    /// case-insensitively, so it is checked again here.
+    async fn list_secrets_page(
+        &self,
+        name_prefix: &str,
+        filters: &[Filter],
+        include_planned_deletion: bool,
+        page_size: Option<usize>,
+        next_token: Option<String>,
+    ) -> Result<(Vec<SecretListEntry>, Option<String>)> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let mut filters = filters.to_vec();
+        if !name_prefix.is_empty() {
+            filters.push(
+                Filter::builder()
+                    .key(FilterNameStringType::Name)
+                    .values(name_prefix)
+                    .build(),
+            );
+        }
+
+        let mut request = client
+            .list_secrets()
+            .include_planned_deletion(include_planned_deletion);
+
+        if !filters.is_empty() {
+            request = request.set_filters(Some(filters));
+        }
+
+        if let Some(page_size) = page_size {
+            request = request.max_results(page_size.clamp(1, MAX_PAGE_SIZE) as i32);
+        }
+
+        if let Some(token) = next_token {
+            request = request.next_token(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .record_request_id()
+            .map_err(|e| sdk_error("Failed to list secrets", e))?;
+
+        let entries = response
+            .secret_list()
+            .iter()
+            .filter(|secret| {
+                secret
+                    .name()
+                    .is_some_and(|full_name| full_name.starts_with(name_prefix))
+            })
+            .cloned()
+            .collect();
+
+        Ok((entries, response.next_token().map(|s| s.to_string())))
+    }
+
+    /// Converts a listed secret into an item, stripping the prefix from its name.
+    fn entry_item(&self, secret: &SecretListEntry) -> Item {
+        let full_name = secret.name().unwrap_or("");
+        let name = full_name.strip_prefix(&self.prefix).unwrap_or(full_name);
+
+        Item {
+            id: secret.arn().unwrap_or("").to_string(),
+            name: name.to_string(),
+            item_type: ItemType::SecureNote,
+            notes: None, // Don't fetch values for list
+            fields: None,
+            tags: Self::tag_map(secret.tags()),
+            location: None,
+            created: secret
+                .created_date()
+                .and_then(|d| chrono::DateTime::from_timestamp(d.secs(), d.subsec_nanos())),
+            modified: secret
+                .last_changed_date()
+                .and_then(|d| chrono::DateTime::from_timestamp(d.secs(), d.subsec_nanos())),
+            revision: None,
+        }
+    }
+
+    /// Returns the staging labels of each version of secret `name`.
+    async fn version_stages(&self, name: &str) -> Result<HashMap<String, Vec<String>>> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let response = client
+            .describe_secret()
+            .secret_id(self.secret_name(name))
+            .send()
+            .await
+            .record_request_id()
+            .map_err(|e| {
+                let error_str = format!("{:?}", e);
+                if error_str.contains("ResourceNotFoundException") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    sdk_error("AWS error", e)
+                }
+            })?;
+
+        Ok(response
+            .version_ids_to_stages()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+/// Returns the version holding `AWSCURRENT`.
+fn current_version(stages: &HashMap<String, Vec<String>>) -> Option<&str> {
+    stages
+        .iter()
+        .find(|(_, labels)| labels.iter().any(|l| l == AWS_CURRENT))
+        .map(|(id, _)| id.as_str())
 }
 
 #[async_trait]
@@ -58,6 +366,23 @@ impl Backend for AWSBackend {
         "awssecrets"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            versioning: true,
+            native_binary: true,
+            tags: true,
+            read_tags: true,
+            soft_delete: true,
+            max_value_size: Some(MAX_SECRET_SIZE),
+            compare_and_swap: CompareAndSwap::Atomic,
+            name_charset: NameCharset {
+                extra_chars: Some("/_+=.@-"),
+                max_length: 512,
+            },
+            ..Capabilities::default()
+        }
+    }
+
     async fn init(&mut self) -> Result<()> {
         // Set up AWS SDK config
         let mut config_loader = aws_config::defaults(aws_config::BehaviorVersion::latest())
@@ -122,12 +447,13 @@ impl Backend for AWSBackend {
             .secret_id(&secret_name)
             .send()
             .await
+            .record_request_id()
             .map_err(|e| {
                 let error_str = format!("{:?}", e);
                 if error_str.contains("ResourceNotFoundException") {
                     VaultmuxError::NotFound(name.to_string())
                 } else {
-                    VaultmuxError::Other(anyhow::anyhow!("AWS error: {}", e))
+                    sdk_error("AWS error", e)
                 }
             })?;
 
@@ -137,20 +463,28 @@ impl Backend for AWSBackend {
             .secret_id(&secret_name)
             .send()
             .await
-            .map_err(|e| {
-                VaultmuxError::Other(anyhow::anyhow!("Failed to get secret value: {}", e))
-            })?;
-
-        let secret_string = value_response
-            .secret_string()
-            .ok_or_else(|| VaultmuxError::Other(anyhow::anyhow!("Secret has no string value")))?;
+            .record_request_id()
+            .map_err(|e| sdk_error("Failed to get secret value", e))?;
+
+        // Binary secrets are surfaced in their marked base64 form
+        let secret_string = match (
+            value_response.secret_string(),
+            value_response.secret_binary(),
+        ) {
+            (Some(s), _) => s.to_string(),
+            (None, Some(blob)) => encode_binary(blob.as_ref()),
+            (None, None) => {
+                return Err(VaultmuxError::Other(anyhow::anyhow!("Secret has no value")));
+            }
+        };
 
-        Ok(Item {
+        let mut item = Item {
             id: response.arn().unwrap_or("").to_string(),
             name: name.to_string(),
             item_type: ItemType::SecureNote,
-            notes: Some(secret_string.to_string()),
+            notes: None,
             fields: None,
+            tags: Self::tag_map(response.tags()),
             location: None,
             created: response
                 .created_date()
@@ -158,13 +492,20 @@ impl Backend for AWSBackend {
             modified: response
                 .last_changed_date()
                 .and_then(|d| chrono::DateTime::from_timestamp(d.secs(), d.subsec_nanos())),
-        })
+            revision: value_response.version_id().map(String::from),
+        };
+
+        // Restores type and fields of items written with create_item_full
+        item.decode_content(secret_string);
+
+        Ok(item)
     }
 
     async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        // Structured items keep their data in fields, so missing notes
+        // are empty rather than a missing item
         let item = self.get_item(name, session).await?;
-        item.notes
-            .ok_or_else(|| VaultmuxError::NotFound(format!("{} has no value", name)))
+        Ok(item.notes.unwrap_or_default())
     }
 
     async fn item_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
@@ -182,6 +523,7 @@ impl Backend for AWSBackend {
             .secret_id(&secret_name)
             .send()
             .await
+            .record_request_id()
         {
             Ok(_) => Ok(true),
             Err(e) => {
@@ -190,80 +532,454 @@ impl Backend for AWSBackend {
                 if error_str.contains("ResourceNotFoundException") {
                     Ok(false)
                 } else {
-                    Err(VaultmuxError::Other(anyhow::anyhow!("AWS error: {:?}", e)))
+                    Err(sdk_error("AWS error", e))
                 }
             }
         }
     }
 
     async fn list_items(&self, _session: &dyn Session) -> Result<Vec<Item>> {
+        self.list_secrets(Vec::new()).await
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        _session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        let name_prefix = format!("{}{}", self.prefix, options.prefix.as_deref().unwrap_or(""));
+
+        let items = paginate(move |next_token| {
+            let name_prefix = name_prefix.clone();
+            async move {
+                let (entries, next_token) = self
+                    .list_secrets_page(&name_prefix, &[], false, options.page_size, next_token)
+                    .await?;
+                let items = entries
+                    .iter()
+                    .map(|secret| self.entry_item(secret))
+                    .collect();
+                Ok((items, next_token))
+            }
+        });
+
+        options.apply(items)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
         let client = self
             .client
             .as_ref()
             .ok_or(VaultmuxError::NotAuthenticated)?;
 
-        let mut items = Vec::new();
-        let mut next_token: Option<String> = None;
+        // Check if exists
+        if self.item_exists(name, _session).await? {
+            return Err(VaultmuxError::AlreadyExists(name.to_string()));
+        }
 
-        loop {
-            let mut request = client.list_secrets();
+        let secret_name = self.secret_name(name);
 
-            if let Some(token) = next_token {
-                request = request.next_token(token);
-            }
+        client
+            .create_secret()
+            .name(&secret_name)
+            .secret_string(content)
+            .send()
+            .await
+            .record_request_id()
+            .map_err(|e| sdk_error("Failed to create secret", e))?;
 
-            let response = request.send().await.map_err(|e| {
-                VaultmuxError::Other(anyhow::anyhow!("Failed to list secrets: {}", e))
-            })?;
+        Ok(())
+    }
 
-            for secret in response.secret_list() {
-                let Some(full_name) = secret.name() else {
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        // Check if exists
+        if !self.item_exists(name, _session).await? {
+            return Err(VaultmuxError::NotFound(name.to_string()));
+        }
+
+        let secret_name = self.secret_name(name);
+
+        client
+            .put_secret_value()
+            .secret_id(&secret_name)
+            .secret_string(content)
+            .send()
+            .await
+            .record_request_id()
+            .map_err(|e| sdk_error("Failed to update secret", e))?;
+
+        Ok(())
+    }
+
+    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        self.delete_secret(name).await
+    }
+
+    async fn get_many(
+        &self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<String>>> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let mut valid: Vec<&str> = names
+            .iter()
+            .copied()
+            .filter(|name| validate_item_name(name).is_ok())
+            .collect();
+        valid.sort_unstable();
+        valid.dedup();
+
+        // Values by secret name, and error messages for secrets that failed
+        // for reasons other than not existing
+        let mut values: HashMap<String, String> = HashMap::new();
+        let mut errors: HashMap<String, String> = HashMap::new();
+
+        for chunk in valid.chunks(BATCH_GET_LIMIT) {
+            let secret_ids = chunk.iter().map(|name| self.secret_name(name)).collect();
+
+            let response = client
+                .batch_get_secret_value()
+                .set_secret_id_list(Some(secret_ids))
+                .send()
+                .await
+                .record_request_id()
+                .map_err(|e| sdk_error("Failed to get secret values", e))?;
+
+            for entry in response.secret_values() {
+                let Some(secret_name) = entry.name() else {
                     continue;
                 };
 
-                // Filter by prefix and strip it from the returned name
-                if !self.prefix.is_empty() && !full_name.starts_with(&self.prefix) {
+                // Binary secrets are surfaced in their marked base64 form
+                let value = match (entry.secret_string(), entry.secret_binary()) {
+                    (Some(s), _) => s.to_string(),
+                    (None, Some(blob)) => encode_binary(blob.as_ref()),
+                    (None, None) => continue,
+                };
+                values.insert(secret_name.to_string(), value);
+            }
+
+            for error in response.errors() {
+                let Some(secret_name) = error.secret_id() else {
                     continue;
+                };
+
+                if error.error_code() != Some("ResourceNotFoundException") {
+                    errors.insert(
+                        secret_name.to_string(),
+                        error.message().unwrap_or_default().to_string(),
+                    );
                 }
+            }
+        }
+
+        Ok(names
+            .iter()
+            .map(|name| {
+                validate_item_name(name)?;
+
+                let secret_name = self.secret_name(name);
+                if let Some(value) = values.get(&secret_name) {
+                    // Same decoding as get_item, so structured items yield their notes
+                    let mut item = Item::new_secure_note(*name, "");
+                    item.decode_content(value.clone());
+                    return Ok(item.notes.unwrap_or_default());
+                }
+                match errors.get(&secret_name) {
+                    Some(message) => Err(VaultmuxError::Other(anyhow::anyhow!(
+                        "Failed to get secret value: {}",
+                        message
+                    ))),
+                    None => Err(VaultmuxError::NotFound(name.to_string())),
+                }
+            })
+            .collect())
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let writes: Vec<_> = entries
+            .iter()
+            .map(|(name, content)| self.put_value(name, content))
+            .collect();
+
+        Ok(futures::stream::iter(writes)
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await)
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let deletes: Vec<_> = names.iter().map(|name| self.delete_secret(name)).collect();
 
-                let name = if !self.prefix.is_empty() {
-                    full_name.strip_prefix(&self.prefix).unwrap()
+        Ok(futures::stream::iter(deletes)
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await)
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let secret_name = self.secret_name(name);
+        let conflict = || {
+            VaultmuxError::Conflict(format!(
+                "{} is no longer at revision {}",
+                name, expected_revision
+            ))
+        };
+
+        // Check first, so a stale revision doesn't leave a staged version behind
+        let stages = self.version_stages(name).await?;
+        if !stages.contains_key(expected_revision) {
+            return Err(VaultmuxError::NotFound(format!(
+                "{} revision {}",
+                name, expected_revision
+            )));
+        }
+        if current_version(&stages) != Some(expected_revision) {
+            return Err(conflict());
+        }
+
+        // Stage the new value without making it current
+        let put = client
+            .put_secret_value()
+            .secret_id(&secret_name)
+            .secret_string(content)
+            .version_stages(VAULTMUX_PENDING)
+            .send()
+            .await
+            .record_request_id()
+            .map_err(|e| {
+                let error_str = format!("{:?}", e);
+                if error_str.contains("ResourceNotFoundException") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    sdk_error("Failed to update secret", e)
+                }
+            })?;
+
+        let new_version = put
+            .version_id()
+            .ok_or_else(|| VaultmuxError::Other(anyhow::anyhow!("AWS returned no version ID")))?;
+
+        // AWS refuses to move AWSCURRENT off a version that doesn't hold it,
+        // which makes this the compare-and-swap
+        let promoted = client
+            .update_secret_version_stage()
+            .secret_id(&secret_name)
+            .version_stage(AWS_CURRENT)
+            .move_to_version_id(new_version)
+            .remove_from_version_id(expected_revision)
+            .send()
+            .await
+            .record_request_id();
+
+        // Best-effort cleanup. If the swap lost, the staged version is left
+        // without labels, which AWS treats as deprecated and removes.
+        let _ = client
+            .update_secret_version_stage()
+            .secret_id(&secret_name)
+            .version_stage(VAULTMUX_PENDING)
+            .remove_from_version_id(new_version)
+            .send()
+            .await
+            .record_request_id();
+
+        if let Err(e) = promoted {
+            // Only a concurrent write moving AWSCURRENT is a conflict
+            let moved = self
+                .version_stages(name)
+                .await
+                .is_ok_and(|stages| current_version(&stages) != Some(expected_revision));
+            return Err(if moved {
+                conflict()
+            } else {
+                sdk_error("Failed to update secret", e)
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn get_bytes(&self, name: &str, _session: &dyn Session) -> Result<Vec<u8>> {
+        validate_item_name(name)?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let secret_name = self.secret_name(name);
+
+        let value_response = client
+            .get_secret_value()
+            .secret_id(&secret_name)
+            .send()
+            .await
+            .record_request_id()
+            .map_err(|e| {
+                let error_str = format!("{:?}", e);
+                if error_str.contains("ResourceNotFoundException") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    sdk_error("Failed to get secret value", e)
+                }
+            })?;
+
+        if let Some(blob) = value_response.secret_binary() {
+            return Ok(blob.as_ref().to_vec());
+        }
+
+        let secret_string = value_response
+            .secret_string()
+            .ok_or_else(|| VaultmuxError::NotFound(format!("{} has no value", name)))?;
+
+        decode_value(secret_string)
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+
+        let exists = self.item_exists(name, session).await?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let secret_name = self.secret_name(name);
+
+        // Stored as SecretBinary so other SDK consumers see the raw bytes
+        if exists {
+            client
+                .put_secret_value()
+                .secret_id(&secret_name)
+                .secret_binary(Blob::new(data))
+                .send()
+                .await
+                .record_request_id()
+                .map_err(|e| sdk_error("Failed to update secret", e))?;
+        } else {
+            client
+                .create_secret()
+                .name(&secret_name)
+                .secret_binary(Blob::new(data))
+                .send()
+                .await
+                .record_request_id()
+                .map_err(|e| sdk_error("Failed to create secret", e))?;
+        }
+
+        Ok(())
+    }
+
+    async fn list_versions(&self, name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        validate_item_name(name)?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let secret_name = self.secret_name(name);
+
+        let mut versions = Vec::new();
+        let mut next_token: Option<String> = None;
+
+        loop {
+            let mut request = client
+                .list_secret_version_ids()
+                .secret_id(&secret_name)
+                .include_deprecated(true);
+
+            if let Some(token) = next_token {
+                request = request.next_token(token);
+            }
+
+            let response = request.send().await.record_request_id().map_err(|e| {
+                let error_str = format!("{:?}", e);
+                if error_str.contains("ResourceNotFoundException") {
+                    VaultmuxError::NotFound(name.to_string())
                 } else {
-                    full_name
+                    sdk_error("Failed to list versions", e)
+                }
+            })?;
+
+            for entry in response.versions() {
+                let Some(version_id) = entry.version_id() else {
+                    continue;
                 };
 
-                items.push(Item {
-                    id: secret.arn().unwrap_or("").to_string(),
-                    name: name.to_string(),
-                    item_type: ItemType::SecureNote,
-                    notes: None, // Don't fetch values for list
-                    fields: None,
-                    location: None,
-                    created: secret
+                let stages = entry.version_stages().to_vec();
+                versions.push(ItemVersion {
+                    id: version_id.to_string(),
+                    created: entry
                         .created_date()
                         .and_then(|d| chrono::DateTime::from_timestamp(d.secs(), d.subsec_nanos())),
-                    modified: secret
-                        .last_changed_date()
-                        .and_then(|d| chrono::DateTime::from_timestamp(d.secs(), d.subsec_nanos())),
+                    current: stages.iter().any(|s| s == AWS_CURRENT),
+                    stages,
+                    state: VersionState::Enabled,
                 });
             }
 
-            // Check for more results
             next_token = response.next_token().map(|s| s.to_string());
             if next_token.is_none() {
                 break;
             }
         }
 
-        Ok(items)
+        versions.sort_by_key(|v| std::cmp::Reverse(v.created));
+        Ok(versions)
     }
 
-    async fn create_item(
-        &mut self,
+    async fn get_version(
+        &self,
         name: &str,
-        content: &str,
+        version_id: &str,
         _session: &dyn Session,
-    ) -> Result<()> {
+    ) -> Result<String> {
         validate_item_name(name)?;
 
         let client = self
@@ -271,28 +987,41 @@ impl Backend for AWSBackend {
             .as_ref()
             .ok_or(VaultmuxError::NotAuthenticated)?;
 
-        // Check if exists
-        if self.item_exists(name, _session).await? {
-            return Err(VaultmuxError::AlreadyExists(name.to_string()));
-        }
-
         let secret_name = self.secret_name(name);
 
-        client
-            .create_secret()
-            .name(&secret_name)
-            .secret_string(content)
+        let value_response = client
+            .get_secret_value()
+            .secret_id(&secret_name)
+            .version_id(version_id)
             .send()
             .await
-            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to create secret: {}", e)))?;
+            .record_request_id()
+            .map_err(|e| {
+                let error_str = format!("{:?}", e);
+                if error_str.contains("ResourceNotFoundException") {
+                    VaultmuxError::NotFound(format!("{} version {}", name, version_id))
+                } else {
+                    sdk_error("Failed to get secret version", e)
+                }
+            })?;
 
-        Ok(())
+        match (
+            value_response.secret_string(),
+            value_response.secret_binary(),
+        ) {
+            (Some(s), _) => Ok(s.to_string()),
+            (None, Some(blob)) => Ok(encode_binary(blob.as_ref())),
+            (None, None) => Err(VaultmuxError::NotFound(format!(
+                "{} version {} has no value",
+                name, version_id
+            ))),
+        }
     }
 
-    async fn update_item(
+    async fn rollback(
         &mut self,
         name: &str,
-        content: &str,
+        version_id: &str,
         _session: &dyn Session,
     ) -> Result<()> {
         validate_item_name(name)?;
@@ -302,25 +1031,47 @@ impl Backend for AWSBackend {
             .as_ref()
             .ok_or(VaultmuxError::NotAuthenticated)?;
 
-        // Check if exists
-        if !self.item_exists(name, _session).await? {
-            return Err(VaultmuxError::NotFound(name.to_string()));
+        let secret_name = self.secret_name(name);
+
+        let stages = self.version_stages(name).await?;
+        if !stages.contains_key(version_id) {
+            return Err(VaultmuxError::NotFound(format!(
+                "{} version {}",
+                name, version_id
+            )));
         }
 
-        let secret_name = self.secret_name(name);
+        let current = current_version(&stages);
+        if current == Some(version_id) {
+            return Ok(());
+        }
 
-        client
-            .put_secret_value()
+        // Moving AWSCURRENT makes the old version current without a new write;
+        // AWS moves AWSPREVIOUS to the version that loses AWSCURRENT.
+        let mut request = client
+            .update_secret_version_stage()
             .secret_id(&secret_name)
-            .secret_string(content)
+            .version_stage(AWS_CURRENT)
+            .move_to_version_id(version_id);
+
+        if let Some(current) = current {
+            request = request.remove_from_version_id(current);
+        }
+
+        request
             .send()
             .await
-            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to update secret: {}", e)))?;
+            .record_request_id()
+            .map_err(|e| sdk_error("Failed to roll back secret", e))?;
 
         Ok(())
     }
 
-    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+    async fn get_tags(
+        &self,
+        name: &str,
+        _session: &dyn Session,
+    ) -> Result<HashMap<String, String>> {
         validate_item_name(name)?;
 
         let client = self
@@ -328,19 +1079,176 @@ impl Backend for AWSBackend {
             .as_ref()
             .ok_or(VaultmuxError::NotAuthenticated)?;
 
+        // DescribeSecret returns the tags without fetching the value
+        let response = client
+            .describe_secret()
+            .secret_id(self.secret_name(name))
+            .send()
+            .await
+            .record_request_id()
+            .map_err(|e| {
+                let error_str = format!("{:?}", e);
+                if error_str.contains("ResourceNotFoundException") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    sdk_error("AWS error", e)
+                }
+            })?;
+
+        Ok(Self::tag_map(response.tags()))
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let current = self.get_tags(name, session).await?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
         let secret_name = self.secret_name(name);
 
+        // TagResource only adds or overwrites, so drop removed keys first
+        let removed: Vec<String> = current
+            .keys()
+            .filter(|k| !tags.contains_key(*k))
+            .cloned()
+            .collect();
+        if !removed.is_empty() {
+            client
+                .untag_resource()
+                .secret_id(&secret_name)
+                .set_tag_keys(Some(removed))
+                .send()
+                .await
+                .record_request_id()
+                .map_err(|e| sdk_error("Failed to remove tags", e))?;
+        }
+
+        if !tags.is_empty() {
+            let tags = tags
+                .iter()
+                .map(|(k, v)| Tag::builder().key(k).value(v).build())
+                .collect();
+            client
+                .tag_resource()
+                .secret_id(&secret_name)
+                .set_tags(Some(tags))
+                .send()
+                .await
+                .record_request_id()
+                .map_err(|e| sdk_error("Failed to set tags", e))?;
+        }
+
+        Ok(())
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        _session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        let filters = vec![
+            Filter::builder()
+                .key(FilterNameStringType::TagKey)
+                .values(key)
+                .build(),
+            Filter::builder()
+                .key(FilterNameStringType::TagValue)
+                .values(value)
+                .build(),
+        ];
+
+        // The two filters match independently, so check the pair here
+        let mut items = self.list_secrets(filters).await?;
+        items.retain(|item| item.tags.get(key).map(String::as_str) == Some(value));
+        Ok(items)
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        if options.force {
+            return self.delete_item(name, session).await;
+        }
+
+        validate_item_name(name)?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        let mut request = client.delete_secret().secret_id(self.secret_name(name));
+
+        // Without a window AWS applies its default of 30 days
+        if let Some(window) = options.recovery_window {
+            let days = window.as_secs().div_ceil(86400) as i64;
+            request =
+                request.recovery_window_in_days(days.clamp(MIN_RECOVERY_DAYS, MAX_RECOVERY_DAYS));
+        }
+
+        request.send().await.record_request_id().map_err(|e| {
+            let error_str = format!("{:?}", e);
+            if error_str.contains("ResourceNotFoundException") {
+                VaultmuxError::NotFound(name.to_string())
+            } else {
+                sdk_error("Failed to delete secret", e)
+            }
+        })?;
+
+        Ok(())
+    }
+
+    async fn list_deleted_items(&self, _session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        let entries = self.list_secret_entries(Vec::new(), true).await?;
+
+        // Deleted secrets don't report their recovery window, so the purge
+        // date is unknown
+        Ok(entries
+            .iter()
+            .filter_map(|secret| {
+                let deleted = secret.deleted_date()?;
+                Some(DeletedItem {
+                    item: self.entry_item(secret),
+                    deleted: chrono::DateTime::from_timestamp(
+                        deleted.secs(),
+                        deleted.subsec_nanos(),
+                    ),
+                    purge_after: None,
+                })
+            })
+            .collect())
+    }
+
+    async fn restore_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
         client
-            .delete_secret()
-            .secret_id(&secret_name)
-            .force_delete_without_recovery(true)
+            .restore_secret()
+            .secret_id(self.secret_name(name))
             .send()
             .await
+            .record_request_id()
             .map_err(|e| {
-                if e.to_string().contains("ResourceNotFoundException") {
+                let error_str = format!("{:?}", e);
+                if error_str.contains("ResourceNotFoundException") {
                     VaultmuxError::NotFound(name.to_string())
                 } else {
-                    VaultmuxError::Other(anyhow::anyhow!("Failed to delete secret: {}", e))
+                    sdk_error("Failed to restore secret", e)
                 }
             })?;
 
@@ -2,18 +2,60 @@
 
 use crate::backends::azure::AzureSession;
 use crate::validation::validate_item_name;
-use crate::{Backend, Config, Item, ItemType, Result, Session, VaultmuxError};
+use crate::{
+    Backend, Capabilities, CompareAndSwap, Config, DeleteOptions, DeletedItem, Item, ItemType,
+    ItemVersion, NameCharset, Result, Session, VaultmuxError, VersionState,
+};
 use async_trait::async_trait;
+use azure_core::auth::TokenCredential;
+use azure_core::{headers, Method, Request, StatusCode, Url};
 use azure_identity::DefaultAzureCredential;
 use azure_security_keyvault::SecretClient;
 use futures::StreamExt;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Maximum size of a secret value (25 KiB).
+const MAX_SECRET_SIZE: usize = 25600;
+
+/// Key Vault REST API version for requests the SDK doesn't cover.
+const API_VERSION: &str = "7.4";
+
+/// Secret properties the SDK drops from its responses.
+#[derive(Debug, Deserialize)]
+struct SecretProperties {
+    #[serde(default)]
+    tags: Option<HashMap<String, String>>,
+}
+
+/// One page of `GET /deletedsecrets`.
+#[derive(Debug, Deserialize)]
+struct DeletedSecretPage {
+    #[serde(default)]
+    value: Vec<DeletedSecret>,
+    #[serde(rename = "nextLink")]
+    next_link: Option<String>,
+}
+
+/// A soft-deleted secret. Dates are Unix timestamps in seconds.
+#[derive(Debug, Deserialize)]
+struct DeletedSecret {
+    id: String,
+    #[serde(default)]
+    tags: Option<HashMap<String, String>>,
+    #[serde(rename = "deletedDate")]
+    deleted_date: Option<i64>,
+    #[serde(rename = "scheduledPurgeDate")]
+    scheduled_purge_date: Option<i64>,
+}
+
 /// Azure Key Vault backend.
 ///
 /// Integrates with Azure Key Vault using the official Microsoft SDK.
 pub struct AzureBackend {
     client: Option<SecretClient>,
+    credential: Option<Arc<DefaultAzureCredential>>,
     vault_url: String,
     prefix: String,
 }
@@ -33,6 +75,7 @@ impl AzureBackend {
 
         Self {
             client: None,
+            credential: None,
             vault_url,
             prefix,
         }
@@ -51,6 +94,104 @@ impl AzureBackend {
     fn client(&self) -> Result<&SecretClient> {
         self.client.as_ref().ok_or(VaultmuxError::NotAuthenticated)
     }
+
+    /// Builds a REST API URL for a path under the vault.
+    fn rest_url(&self, path: &str) -> Result<Url> {
+        let mut url = Url::parse(&self.vault_url)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Invalid vault URL: {}", e)))?;
+        url.set_path(path);
+        url.query_pairs_mut()
+            .append_pair("api-version", API_VERSION);
+        Ok(url)
+    }
+
+    /// Sends a request to the Key Vault REST API.
+    ///
+    /// Used for calls the SDK doesn't cover. A 404 is reported as `NotFound`
+    /// for `name`.
+    async fn rest(&self, method: Method, url: Url, name: &str) -> Result<azure_core::Response> {
+        let credential = self
+            .credential
+            .as_ref()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+
+        // Tokens are scoped to the Key Vault service, e.g. https://vault.azure.net
+        let scope = match url.host_str().and_then(|host| host.split_once('.')) {
+            Some((_, service)) => format!("{}://{}/.default", url.scheme(), service),
+            None => {
+                return Err(VaultmuxError::Other(anyhow::anyhow!(
+                    "Invalid vault URL: {}",
+                    self.vault_url
+                )))
+            }
+        };
+
+        let token = credential.get_token(&[&scope]).await.map_err(|e| {
+            VaultmuxError::Other(anyhow::anyhow!("Failed to get Azure token: {}", e))
+        })?;
+
+        let mut request = Request::new(url, method);
+        request.insert_header(
+            headers::AUTHORIZATION,
+            format!("Bearer {}", token.token.secret()),
+        );
+
+        let response = azure_core::new_http_client()
+            .execute_request(&request)
+            .await
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Azure error: {}", e)))?;
+
+        match response.status() {
+            StatusCode::NotFound => Err(VaultmuxError::NotFound(name.to_string())),
+            status if !status.is_success() => Err(VaultmuxError::Other(anyhow::anyhow!(
+                "Azure error: HTTP {}",
+                status
+            ))),
+            _ => Ok(response),
+        }
+    }
+
+    /// Fetches the current version's properties with a direct REST call.
+    ///
+    /// The SDK's `get` returns only the value and attributes, so tags are
+    /// read from the raw response.
+    async fn get_properties(&self, name: &str) -> Result<SecretProperties> {
+        let url = self.rest_url(&format!("secrets/{}", self.secret_name(name)))?;
+
+        self.rest(Method::Get, url, name)
+            .await?
+            .json()
+            .await
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse secret: {}", e)))
+    }
+
+    /// Converts a deleted secret into a [`DeletedItem`], skipping secrets
+    /// outside this backend's prefix.
+    fn deleted_item(&self, secret: DeletedSecret) -> Option<DeletedItem> {
+        let full_name = secret.id.rsplit('/').next()?;
+        let name = full_name.strip_prefix(&self.prefix)?.to_string();
+
+        Some(DeletedItem {
+            item: Item {
+                id: secret.id.clone(),
+                name,
+                item_type: ItemType::SecureNote,
+                notes: None,
+                fields: None,
+                tags: secret.tags.unwrap_or_default(),
+                location: None,
+                created: None,
+                modified: None,
+                revision: None,
+            },
+            deleted: secret
+                .deleted_date
+                .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)),
+            purge_after: secret
+                .scheduled_purge_date
+                .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)),
+        })
+    }
 }
 
 #[async_trait]
@@ -59,6 +200,23 @@ impl Backend for AzureBackend {
         "azurekeyvault"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            versioning: true,
+            disable_versions: true,
+            tags: true,
+            read_tags: true,
+            max_value_size: Some(MAX_SECRET_SIZE),
+            compare_and_swap: CompareAndSwap::BestEffort,
+            soft_delete: true,
+            name_charset: NameCharset {
+                extra_chars: Some("-"),
+                max_length: 127,
+            },
+            ..Capabilities::default()
+        }
+    }
+
     async fn init(&mut self) -> Result<()> {
         if self.vault_url.is_empty() {
             return Err(VaultmuxError::Other(anyhow::anyhow!(
@@ -72,15 +230,19 @@ impl Backend for AzureBackend {
         )?);
 
         // Create Secret client
-        self.client = Some(SecretClient::new(&self.vault_url, credential).map_err(|e| {
-            VaultmuxError::Other(anyhow::anyhow!("Failed to create Secret client: {}", e))
-        })?);
+        self.client = Some(
+            SecretClient::new(&self.vault_url, credential.clone()).map_err(|e| {
+                VaultmuxError::Other(anyhow::anyhow!("Failed to create Secret client: {}", e))
+            })?,
+        );
+        self.credential = Some(credential);
 
         Ok(())
     }
 
     async fn close(&mut self) -> Result<()> {
         self.client = None;
+        self.credential = None;
         Ok(())
     }
 
@@ -120,22 +282,33 @@ impl Backend for AzureBackend {
         let modified =
             chrono::DateTime::from_timestamp(secret.attributes.updated_on.unix_timestamp(), 0);
 
-        Ok(Item {
+        // The secret ID ends with the current version
+        let revision = secret.id.rsplit('/').next().map(String::from);
+
+        let mut item = Item {
             id: secret.id,
             name: name.to_string(),
             item_type: ItemType::SecureNote,
-            notes: Some(secret.value),
+            notes: None,
             fields: None,
+            tags: HashMap::new(),
             location: None,
             created,
             modified,
-        })
+            revision,
+        };
+
+        // Restores type and fields of items written with create_item_full
+        item.decode_content(secret.value);
+
+        Ok(item)
     }
 
     async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        // Structured items keep their data in fields, so missing notes
+        // are empty rather than a missing item
         let item = self.get_item(name, session).await?;
-        item.notes
-            .ok_or_else(|| VaultmuxError::NotFound(format!("{} has no value", name)))
+        Ok(item.notes.unwrap_or_default())
     }
 
     async fn item_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
@@ -181,9 +354,11 @@ impl Backend for AzureBackend {
                                     item_type: ItemType::SecureNote,
                                     notes: None,
                                     fields: None,
+                                    tags: HashMap::new(),
                                     location: None,
                                     created: None,
                                     modified: None,
+                                    revision: None,
                                 });
                             }
                         }
@@ -233,20 +408,30 @@ impl Backend for AzureBackend {
     ) -> Result<()> {
         validate_item_name(name)?;
 
-        if !self.item_exists(name, _session).await? {
-            return Err(VaultmuxError::NotFound(name.to_string()));
-        }
+        // Tags belong to a version, so read them before starting a new one
+        let tags = self.get_properties(name).await?.tags.unwrap_or_default();
 
         let client = self.client()?;
         let secret_name = self.secret_name(name);
 
         // Azure creates a new version when setting an existing secret
         client
-            .set(secret_name, content)
+            .set(secret_name.clone(), content)
             .into_future()
             .await
             .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to update secret: {}", e)))?;
 
+        if !tags.is_empty() {
+            client
+                .update(secret_name)
+                .tags(tags)
+                .into_future()
+                .await
+                .map_err(|e| {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to carry over tags: {}", e))
+                })?;
+        }
+
         Ok(())
     }
 
@@ -271,6 +456,225 @@ impl Backend for AzureBackend {
         Ok(())
     }
 
+    async fn list_versions(&self, name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        validate_item_name(name)?;
+
+        let client = self.client()?;
+        let secret_name = self.secret_name(name);
+
+        // The unversioned GET resolves to the current version
+        let current = client
+            .get(secret_name.clone())
+            .into_future()
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("SecretNotFound") || e.to_string().contains("404") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Azure error: {}", e))
+                }
+            })?;
+        let current_id = current
+            .id
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        let mut versions = Vec::new();
+        let mut pages = client.get_versions(secret_name).into_stream();
+
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| {
+                VaultmuxError::Other(anyhow::anyhow!("Failed to list versions: {}", e))
+            })?;
+
+            for version in page.value {
+                let Some(id) = version.id.rsplit('/').next() else {
+                    continue;
+                };
+
+                versions.push(ItemVersion {
+                    id: id.to_string(),
+                    created: chrono::DateTime::from_timestamp(
+                        version.attributes.created.unix_timestamp(),
+                        0,
+                    ),
+                    stages: Vec::new(),
+                    current: id == current_id,
+                    state: if version.attributes.enabled {
+                        VersionState::Enabled
+                    } else {
+                        VersionState::Disabled
+                    },
+                });
+            }
+        }
+
+        // Azure version ids are opaque, so order by creation time
+        versions.sort_by_key(|v| std::cmp::Reverse(v.created));
+
+        Ok(versions)
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<String> {
+        validate_item_name(name)?;
+
+        let client = self.client()?;
+        let secret_name = self.secret_name(name);
+
+        let secret = client
+            .get(secret_name)
+            .version(version_id)
+            .into_future()
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("SecretNotFound") || e.to_string().contains("404") {
+                    VaultmuxError::NotFound(format!("{} version {}", name, version_id))
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Azure error: {}", e))
+                }
+            })?;
+
+        Ok(secret.value)
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
+        let client = self.client()?;
+        let secret_name = self.secret_name(name);
+
+        client
+            .update(secret_name)
+            .version(version_id)
+            .enabled(false)
+            .into_future()
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("SecretNotFound") || e.to_string().contains("404") {
+                    VaultmuxError::NotFound(format!("{} version {}", name, version_id))
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to disable version: {}", e))
+                }
+            })?;
+
+        Ok(())
+    }
+
+    async fn get_tags(
+        &self,
+        name: &str,
+        _session: &dyn Session,
+    ) -> Result<HashMap<String, String>> {
+        validate_item_name(name)?;
+
+        Ok(self.get_properties(name).await?.tags.unwrap_or_default())
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
+        let client = self.client()?;
+        let secret_name = self.secret_name(name);
+
+        // Tags belong to the current version; update_item copies them to
+        // each new version
+        client
+            .update(secret_name)
+            .tags(tags.clone())
+            .into_future()
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("SecretNotFound") || e.to_string().contains("404") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to set tags: {}", e))
+                }
+            })?;
+
+        Ok(())
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        // The SDK has no purge, and the retention period is a vault setting,
+        // so only the vault's own soft-delete behaviour is available
+        if options.force {
+            return Err(VaultmuxError::NotSupported(
+                "Azure Key Vault secrets cannot be purged with this SDK version".to_string(),
+            ));
+        }
+
+        self.delete_item(name, session).await
+    }
+
+    async fn list_deleted_items(&self, _session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        let mut url = self.rest_url("deletedsecrets")?;
+        let mut items = Vec::new();
+
+        loop {
+            let page: DeletedSecretPage = self
+                .rest(Method::Get, url, "deletedsecrets")
+                .await?
+                .json()
+                .await
+                .map_err(|e| {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to parse deleted secrets: {}", e))
+                })?;
+
+            items.extend(
+                page.value
+                    .into_iter()
+                    .filter_map(|secret| self.deleted_item(secret)),
+            );
+
+            match page.next_link {
+                Some(next) => {
+                    url = Url::parse(&next).map_err(|e| {
+                        VaultmuxError::Other(anyhow::anyhow!("Invalid next link: {}", e))
+                    })?;
+                }
+                None => break,
+            }
+        }
+
+        Ok(items)
+    }
+
+    async fn restore_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+
+        let url = self.rest_url(&format!(
+            "deletedsecrets/{}/recover",
+            self.secret_name(name)
+        ))?;
+        // Recovery finishes in the background; the secret is readable again
+        // a few seconds after this returns
+        self.rest(Method::Post, url, name).await?;
+
+        Ok(())
+    }
+
     async fn list_locations(&self, _session: &dyn Session) -> Result<Vec<String>> {
         Err(VaultmuxError::NotSupported(
             "Azure Key Vault does not support locations (secrets are vault-scoped)".to_string(),
@@ -324,4 +728,48 @@ mod tests {
 
         assert_eq!(backend.secret_name("api-key"), "api-key");
     }
+
+    #[test]
+    fn test_deleted_item_from_page() {
+        let config = Config::new(crate::BackendType::AzureKeyVault)
+            .with_option("vault_url", "https://myvault.vault.azure.net")
+            .with_option("prefix", "app-");
+        let backend = AzureBackend::new(config);
+
+        let page: DeletedSecretPage = serde_json::from_str(
+            r#"{
+                "value": [
+                    {
+                        "id": "https://myvault.vault.azure.net/secrets/app-api-key",
+                        "recoveryId": "https://myvault.vault.azure.net/deletedsecrets/app-api-key",
+                        "deletedDate": 1700000000,
+                        "scheduledPurgeDate": 1707776000,
+                        "tags": {"env": "prod"}
+                    },
+                    {
+                        "id": "https://myvault.vault.azure.net/secrets/other-key",
+                        "deletedDate": 1700000000
+                    }
+                ],
+                "nextLink": null
+            }"#,
+        )
+        .unwrap();
+
+        let items: Vec<_> = page
+            .value
+            .into_iter()
+            .filter_map(|secret| backend.deleted_item(secret))
+            .collect();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item.name, "api-key");
+        assert_eq!(
+            items[0].item.tags.get("env").map(String::as_str),
+            Some("prod")
+        );
+        assert_eq!(items[0].deleted.unwrap().timestamp(), 1_700_000_000);
+        assert_eq!(items[0].purge_after.unwrap().timestamp(), 1_707_776_000);
+        assert!(page.next_link.is_none());
+    }
 }
@@ -1,16 +1,28 @@
 //! Bitwarden backend implementation.
 
 use crate::backends::bitwarden::BitwardenSession;
-use crate::cli::{check_command_exists, run_command, StatusCache};
+use crate::cli::{check_command_exists, run_command, run_command_with_stdin, StatusCache};
 use crate::session::SessionCache;
 use crate::validation::{validate_item_name, validate_location_name};
-use crate::{Backend, Config, Item, ItemType, Result, Session, VaultmuxError};
+use crate::{
+    Backend, Capabilities, CompareAndSwap, Config, DeleteOptions, DeletedItem, Item, ItemType,
+    ListOptions, Result, Session, VaultmuxError,
+};
 use async_trait::async_trait;
 use base64::Engine;
 use chrono::Utc;
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 
+/// Maximum length of an item's notes after encryption.
+const MAX_NOTES_SIZE: usize = 10000;
+
+/// Days Bitwarden keeps items in the trash before deleting them for good.
+const TRASH_RETENTION_DAYS: i64 = 30;
+
 /// Bitwarden CLI backend.
 ///
 /// Integrates with the `bw` command-line tool for Bitwarden vault management.
@@ -44,6 +56,179 @@ impl BitwardenBackend {
         }
     }
 
+    /// Builds a `bw` item template from a structured item.
+    ///
+    /// Known fields for the item type go into the type-specific object
+    /// (`login`, `card`, `identity`, `sshKey`); the rest become custom fields.
+    fn item_template(&self, item: &Item) -> serde_json::Value {
+        let mut remaining: BTreeMap<String, String> = item
+            .fields
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+        let mut template = serde_json::json!({
+            "type": item_type_code(item.item_type),
+            "name": self.prefixed_name(&item.name),
+            "notes": item.notes,
+        });
+
+        match item.item_type {
+            ItemType::SecureNote => {
+                template["secureNote"] = serde_json::json!({ "type": 0 });
+            }
+            ItemType::Login => {
+                let mut login = take_fields(&mut remaining, LOGIN_FIELDS);
+                if let Some(uri) = remaining.remove("uri") {
+                    login.insert("uris".to_string(), serde_json::json!([{ "uri": uri }]));
+                }
+                template["login"] = serde_json::Value::Object(login);
+            }
+            ItemType::Card => {
+                template["card"] =
+                    serde_json::Value::Object(take_fields(&mut remaining, CARD_FIELDS));
+            }
+            ItemType::Identity => {
+                template["identity"] =
+                    serde_json::Value::Object(take_fields(&mut remaining, IDENTITY_FIELDS));
+            }
+            ItemType::SSHKey => {
+                template["sshKey"] =
+                    serde_json::Value::Object(take_fields(&mut remaining, SSH_KEY_FIELDS));
+            }
+        }
+
+        let mut custom: Vec<serde_json::Value> = remaining
+            .into_iter()
+            .map(|(name, value)| serde_json::json!({ "name": name, "value": value, "type": 0 }))
+            .collect();
+        custom.extend(tag_fields(&item.tags));
+
+        if !custom.is_empty() {
+            template["fields"] = serde_json::Value::Array(custom);
+        }
+
+        template
+    }
+
+    /// Fetches an item as raw JSON.
+    ///
+    /// Editing the raw item keeps properties vaultmux doesn't model intact.
+    async fn get_raw_item(&self, name: &str, session: &dyn Session) -> Result<serde_json::Value> {
+        let full_name = self.prefixed_name(name);
+
+        let output = run_command(
+            "bw",
+            &["get", "item", &full_name],
+            &[("BW_SESSION", session.token())],
+        )
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("Not found") {
+                VaultmuxError::NotFound(name.to_string())
+            } else {
+                e
+            }
+        })?;
+
+        serde_json::from_str(&output)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse item: {}", e)))
+    }
+
+    /// Saves a raw item fetched with [`get_raw_item`](Self::get_raw_item).
+    async fn edit_raw_item(&self, raw: &serde_json::Value, session: &dyn Session) -> Result<()> {
+        let id = raw["id"].as_str().unwrap_or_default();
+        let encoded = base64::engine::general_purpose::STANDARD.encode(raw.to_string().as_bytes());
+
+        // bw reads the encoded item from stdin when it isn't an argument,
+        // which keeps secret values out of argv
+        run_command_with_stdin(
+            "bw",
+            &["edit", "item", id],
+            &[("BW_SESSION", session.token())],
+            &encoded,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lists items under the prefix, narrowed by `bw list items --search`.
+    async fn search_items(&self, search: Option<&str>, session: &dyn Session) -> Result<Vec<Item>> {
+        let mut args = vec!["list", "items"];
+        if let Some(search) = search {
+            args.extend(["--search", search]);
+        }
+
+        let output = run_command("bw", &args, &[("BW_SESSION", session.token())]).await?;
+
+        let bw_items: Vec<BitwardenItem> = serde_json::from_str(&output)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse items: {}", e)))?;
+
+        // Filter by prefix and convert
+        let mut items = Vec::new();
+        for bw_item in bw_items {
+            // Check if name matches our prefix
+            let matches_prefix = if self.prefix.is_empty() {
+                true
+            } else {
+                bw_item.name.starts_with(&format!("{}/", self.prefix))
+            };
+
+            if matches_prefix {
+                let item_type = item_type_from_code(bw_item.item_type);
+                let tags = bw_item.item_tags();
+
+                // Strip prefix from name
+                let name = if self.prefix.is_empty() {
+                    bw_item.name.clone()
+                } else {
+                    bw_item
+                        .name
+                        .strip_prefix(&format!("{}/", self.prefix))
+                        .unwrap_or(&bw_item.name)
+                        .to_string()
+                };
+
+                items.push(Item {
+                    id: bw_item.id,
+                    name,
+                    item_type,
+                    notes: None, // Don't fetch notes for all items
+                    fields: None,
+                    tags,
+                    location: bw_item.folder_id,
+                    created: None,
+                    modified: None,
+                    revision: bw_item.revision_date,
+                });
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Lists trashed items under the prefix.
+    async fn list_trash(&self, session: &dyn Session) -> Result<Vec<BitwardenItem>> {
+        let output = run_command(
+            "bw",
+            &["list", "items", "--trash"],
+            &[("BW_SESSION", session.token())],
+        )
+        .await?;
+
+        let bw_items: Vec<BitwardenItem> = serde_json::from_str(&output)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse items: {}", e)))?;
+
+        Ok(bw_items
+            .into_iter()
+            .filter(|bw_item| {
+                self.prefix.is_empty() || bw_item.name.starts_with(&format!("{}/", self.prefix))
+            })
+            .collect())
+    }
+
     /// Checks vault lock status.
     async fn check_lock_status(&self) -> Result<bool> {
         let output = run_command("bw", &["status"], &[]).await?;
@@ -55,6 +240,121 @@ impl BitwardenBackend {
     }
 }
 
+/// Name prefix of the custom fields that hold item tags.
+const TAG_FIELD_PREFIX: &str = "tag:";
+
+/// Mapping between vaultmux login field keys and Bitwarden `login` properties.
+///
+/// The `uri` key is handled separately since Bitwarden stores a list of URIs.
+const LOGIN_FIELDS: &[(&str, &str)] = &[
+    ("username", "username"),
+    ("password", "password"),
+    ("totp", "totp"),
+];
+
+/// Mapping between vaultmux card field keys and Bitwarden `card` properties.
+const CARD_FIELDS: &[(&str, &str)] = &[
+    ("cardholder_name", "cardholderName"),
+    ("brand", "brand"),
+    ("number", "number"),
+    ("exp_month", "expMonth"),
+    ("exp_year", "expYear"),
+    ("code", "code"),
+];
+
+/// Mapping between vaultmux identity field keys and Bitwarden `identity` properties.
+const IDENTITY_FIELDS: &[(&str, &str)] = &[
+    ("title", "title"),
+    ("first_name", "firstName"),
+    ("middle_name", "middleName"),
+    ("last_name", "lastName"),
+    ("address1", "address1"),
+    ("address2", "address2"),
+    ("address3", "address3"),
+    ("city", "city"),
+    ("state", "state"),
+    ("postal_code", "postalCode"),
+    ("country", "country"),
+    ("company", "company"),
+    ("email", "email"),
+    ("phone", "phone"),
+    ("ssn", "ssn"),
+    ("username", "username"),
+    ("passport_number", "passportNumber"),
+    ("license_number", "licenseNumber"),
+];
+
+/// Mapping between vaultmux SSH key field keys and Bitwarden `sshKey` properties.
+const SSH_KEY_FIELDS: &[(&str, &str)] = &[
+    ("private_key", "privateKey"),
+    ("public_key", "publicKey"),
+    ("fingerprint", "keyFingerprint"),
+];
+
+/// Converts a Bitwarden item type code to an [`ItemType`].
+fn item_type_from_code(code: u8) -> ItemType {
+    match code {
+        1 => ItemType::Login,
+        2 => ItemType::SecureNote,
+        3 => ItemType::Card,
+        4 => ItemType::Identity,
+        5 => ItemType::SSHKey,
+        _ => ItemType::SecureNote,
+    }
+}
+
+/// Converts an [`ItemType`] to a Bitwarden item type code.
+fn item_type_code(item_type: ItemType) -> u8 {
+    match item_type {
+        ItemType::Login => 1,
+        ItemType::SecureNote => 2,
+        ItemType::Card => 3,
+        ItemType::Identity => 4,
+        ItemType::SSHKey => 5,
+    }
+}
+
+/// Moves mapped keys out of `fields` into a Bitwarden JSON object.
+fn take_fields(
+    fields: &mut BTreeMap<String, String>,
+    mapping: &[(&str, &str)],
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut object = serde_json::Map::new();
+    for (key, bw_key) in mapping {
+        if let Some(value) = fields.remove(*key) {
+            object.insert(bw_key.to_string(), serde_json::Value::String(value));
+        }
+    }
+    object
+}
+
+/// Copies mapped Bitwarden properties into vaultmux field keys.
+fn collect_fields(
+    object: &serde_json::Value,
+    mapping: &[(&str, &str)],
+    fields: &mut HashMap<String, String>,
+) {
+    for (key, bw_key) in mapping {
+        if let Some(value) = object.get(*bw_key).and_then(|v| v.as_str()) {
+            fields.insert(key.to_string(), value.to_string());
+        }
+    }
+}
+
+/// Builds the custom fields that store tags, sorted by key.
+fn tag_fields(tags: &HashMap<String, String>) -> Vec<serde_json::Value> {
+    let tags: BTreeMap<&String, &String> = tags.iter().collect();
+    tags.into_iter()
+        .map(|(key, value)| {
+            serde_json::json!({
+                "name": format!("{}{}", TAG_FIELD_PREFIX, key),
+                "value": value,
+                "type": 0,
+            })
+        })
+        .collect()
+}
+
 /// Bitwarden status response.
 #[derive(Debug, Deserialize)]
 struct BitwardenStatus {
@@ -73,11 +373,89 @@ struct BitwardenItem {
     folder_id: Option<String>,
     #[serde(rename = "revisionDate")]
     revision_date: Option<String>,
+    #[serde(
+        default,
+        rename = "deletedDate",
+        skip_serializing_if = "Option::is_none"
+    )]
+    deleted_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    login: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    card: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    identity: Option<serde_json::Value>,
+    #[serde(default, rename = "sshKey", skip_serializing_if = "Option::is_none")]
+    ssh_key: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fields: Option<Vec<BitwardenField>>,
+}
+
+impl BitwardenItem {
+    /// Flattens type-specific properties and custom fields into vaultmux fields.
+    fn item_fields(&self) -> Option<HashMap<String, String>> {
+        let mut fields = HashMap::new();
+
+        if let Some(ref login) = self.login {
+            collect_fields(login, LOGIN_FIELDS, &mut fields);
+            let uri = login
+                .get("uris")
+                .and_then(|uris| uris.get(0))
+                .and_then(|uri| uri.get("uri"))
+                .and_then(|uri| uri.as_str());
+            if let Some(uri) = uri {
+                fields.insert("uri".to_string(), uri.to_string());
+            }
+        }
+        if let Some(ref card) = self.card {
+            collect_fields(card, CARD_FIELDS, &mut fields);
+        }
+        if let Some(ref identity) = self.identity {
+            collect_fields(identity, IDENTITY_FIELDS, &mut fields);
+        }
+        if let Some(ref ssh_key) = self.ssh_key {
+            collect_fields(ssh_key, SSH_KEY_FIELDS, &mut fields);
+        }
+
+        for field in self.fields.iter().flatten() {
+            if let (Some(name), Some(value)) = (&field.name, &field.value) {
+                if !name.starts_with(TAG_FIELD_PREFIX) {
+                    fields.insert(name.clone(), value.clone());
+                }
+            }
+        }
+
+        if fields.is_empty() {
+            None
+        } else {
+            Some(fields)
+        }
+    }
+
+    /// Collects tags from custom fields named `tag:<key>`.
+    fn item_tags(&self) -> HashMap<String, String> {
+        self.fields
+            .iter()
+            .flatten()
+            .filter_map(|field| {
+                let key = field.name.as_deref()?.strip_prefix(TAG_FIELD_PREFIX)?;
+                Some((key.to_string(), field.value.clone().unwrap_or_default()))
+            })
+            .collect()
+    }
+}
+
+/// Bitwarden custom field.
+#[derive(Debug, Serialize, Deserialize)]
+struct BitwardenField {
+    name: Option<String>,
+    value: Option<String>,
+    #[serde(rename = "type")]
+    field_type: u8,
 }
 
 /// Bitwarden folder.
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 struct BitwardenFolder {
     id: String,
     name: String,
@@ -89,6 +467,21 @@ impl Backend for BitwardenBackend {
         "bitwarden"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            locations: true,
+            create_locations: true,
+            native_rename: true,
+            tags: true,
+            read_tags: true,
+            soft_delete: true,
+            max_value_size: Some(MAX_NOTES_SIZE),
+            compare_and_swap: CompareAndSwap::BestEffort,
+            sync: true,
+            ..Capabilities::default()
+        }
+    }
+
     async fn init(&mut self) -> Result<()> {
         // Check if bw command exists
         if !check_command_exists("bw").await? {
@@ -218,23 +611,24 @@ impl Backend for BitwardenBackend {
             .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse item: {}", e)))?;
 
         // Convert to our Item type
-        let item_type = match bw_item.item_type {
-            1 => ItemType::Login,
-            2 => ItemType::SecureNote,
-            3 => ItemType::Card,
-            4 => ItemType::Identity,
-            _ => ItemType::SecureNote,
-        };
+        let item_type = item_type_from_code(bw_item.item_type);
+        let fields = bw_item.item_fields();
+        let tags = bw_item.item_tags();
 
         Ok(Item {
             id: bw_item.id,
             name: name.to_string(),
             item_type,
             notes: bw_item.notes,
-            fields: None,
+            fields,
+            tags,
             location: bw_item.folder_id,
             created: None,
-            modified: bw_item.revision_date.and_then(|d| d.parse().ok()),
+            modified: bw_item
+                .revision_date
+                .as_deref()
+                .and_then(|d| d.parse().ok()),
+            revision: bw_item.revision_date,
         })
     }
 
@@ -255,57 +649,29 @@ impl Backend for BitwardenBackend {
     }
 
     async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
-        // List all items
-        let output =
-            run_command("bw", &["list", "items"], &[("BW_SESSION", session.token())]).await?;
-
-        let bw_items: Vec<BitwardenItem> = serde_json::from_str(&output)
-            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse items: {}", e)))?;
+        self.search_items(None, session).await
+    }
 
-        // Filter by prefix and convert
-        let mut items = Vec::new();
-        for bw_item in bw_items {
-            // Check if name matches our prefix
-            let matches_prefix = if self.prefix.is_empty() {
-                true
-            } else {
-                bw_item.name.starts_with(&format!("{}/", self.prefix))
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        let items = futures::stream::once(async move {
+            // --search matches anywhere in the item, so apply() still checks the prefix
+            let search = match options.prefix.as_deref() {
+                Some(prefix) => {
+                    validate_item_name(prefix)?;
+                    Some(self.prefixed_name(prefix))
+                }
+                None => None,
             };
+            self.search_items(search.as_deref(), session).await
+        })
+        .map_ok(|items| futures::stream::iter(items.into_iter().map(Ok)))
+        .try_flatten();
 
-            if matches_prefix {
-                let item_type = match bw_item.item_type {
-                    1 => ItemType::Login,
-                    2 => ItemType::SecureNote,
-                    3 => ItemType::Card,
-                    4 => ItemType::Identity,
-                    _ => ItemType::SecureNote,
-                };
-
-                // Strip prefix from name
-                let name = if self.prefix.is_empty() {
-                    bw_item.name.clone()
-                } else {
-                    bw_item
-                        .name
-                        .strip_prefix(&format!("{}/", self.prefix))
-                        .unwrap_or(&bw_item.name)
-                        .to_string()
-                };
-
-                items.push(Item {
-                    id: bw_item.id,
-                    name,
-                    item_type,
-                    notes: None, // Don't fetch notes for all items
-                    fields: None,
-                    location: bw_item.folder_id,
-                    created: None,
-                    modified: None,
-                });
-            }
-        }
-
-        Ok(items)
+        options.apply(items)
     }
 
     async fn create_item(
@@ -333,14 +699,15 @@ impl Backend for BitwardenBackend {
             }
         });
 
-        // Encode as base64 for bw create
+        // bw create takes base64 on stdin, keeping the note out of argv
         let template_str = template.to_string();
         let encoded = base64::engine::general_purpose::STANDARD.encode(template_str.as_bytes());
 
-        run_command(
+        run_command_with_stdin(
             "bw",
-            &["create", "item", &encoded],
+            &["create", "item"],
             &[("BW_SESSION", session.token())],
+            &encoded,
         )
         .await?;
 
@@ -355,42 +722,281 @@ impl Backend for BitwardenBackend {
     ) -> Result<()> {
         validate_item_name(name)?;
 
-        // Get existing item
+        // Only the notes change; tags, folder and login stay as they are
+        let mut raw = self.get_raw_item(name, session).await?;
+        raw["notes"] = serde_json::Value::String(content.to_string());
+
+        self.edit_raw_item(&raw, session).await
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+
+        // Get item ID
         let item = self.get_item(name, session).await?;
 
-        // Update template
-        let template = serde_json::json!({
-            "id": item.id,
-            "type": 2,
-            "name": self.prefixed_name(name),
-            "notes": content,
-            "secureNote": {
-                "type": 0
-            }
-        });
+        run_command(
+            "bw",
+            &["delete", "item", &item.id],
+            &[("BW_SESSION", session.token())],
+        )
+        .await?;
 
-        let template_str = template.to_string();
+        Ok(())
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        // One `bw list items` decrypts the vault once instead of once per name
+        let output =
+            run_command("bw", &["list", "items"], &[("BW_SESSION", session.token())]).await?;
+
+        let bw_items: Vec<BitwardenItem> = serde_json::from_str(&output)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse items: {}", e)))?;
+
+        let mut notes: HashMap<String, Option<String>> = HashMap::new();
+        for bw_item in bw_items {
+            notes.entry(bw_item.name).or_insert(bw_item.notes);
+        }
+
+        Ok(names
+            .iter()
+            .map(|name| {
+                validate_item_name(name)?;
+
+                match notes.get(&self.prefixed_name(name)) {
+                    Some(Some(value)) => Ok(value.clone()),
+                    Some(None) => Err(VaultmuxError::NotFound(format!("{} has no notes", name))),
+                    None => Err(VaultmuxError::NotFound(name.to_string())),
+                }
+            })
+            .collect())
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        validate_item_name(&item.name)?;
+
+        if self.item_exists(&item.name, session).await? {
+            return Err(VaultmuxError::AlreadyExists(item.name.clone()));
+        }
+
+        let template_str = self.item_template(item).to_string();
         let encoded = base64::engine::general_purpose::STANDARD.encode(template_str.as_bytes());
 
-        run_command(
+        run_command_with_stdin(
             "bw",
-            &["edit", "item", &item.id, &encoded],
+            &["create", "item"],
             &[("BW_SESSION", session.token())],
+            &encoded,
         )
         .await?;
 
         Ok(())
     }
 
-    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        validate_item_name(&item.name)?;
+
+        let mut raw = self.get_raw_item(&item.name, session).await?;
+
+        // Keep existing tags unless the item carries its own
+        let template = if item.tags.is_empty() {
+            let existing: BitwardenItem = serde_json::from_value(raw.clone()).map_err(|e| {
+                VaultmuxError::Other(anyhow::anyhow!("Failed to parse item: {}", e))
+            })?;
+            let mut tagged = item.clone();
+            tagged.tags = existing.item_tags();
+            self.item_template(&tagged)
+        } else {
+            self.item_template(item)
+        };
+
+        // Replace the content on the raw item so its folder and other
+        // properties are kept
+        if let Some(object) = raw.as_object_mut() {
+            for key in [
+                "login",
+                "card",
+                "identity",
+                "sshKey",
+                "secureNote",
+                "fields",
+            ] {
+                object.remove(key);
+            }
+        }
+        if let serde_json::Value::Object(template) = template {
+            for (key, value) in template {
+                raw[key] = value;
+            }
+        }
+
+        self.edit_raw_item(&raw, session).await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
+        let mut raw = self.get_raw_item(name, session).await?;
+
+        let mut fields: Vec<serde_json::Value> = raw["fields"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|f| {
+                !f["name"]
+                    .as_str()
+                    .is_some_and(|n| n.starts_with(TAG_FIELD_PREFIX))
+            })
+            .collect();
+        fields.extend(tag_fields(tags));
+        raw["fields"] = serde_json::Value::Array(fields);
+
+        self.edit_raw_item(&raw, session).await
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        // bw list already includes custom fields, so filter the listing
+        let mut items = self.list_items(session).await?;
+        items.retain(|item| item.tags.get(key).map(String::as_str) == Some(value));
+        Ok(items)
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(old_name)?;
+        validate_item_name(new_name)?;
+
+        let mut raw = self.get_raw_item(old_name, session).await?;
+        if self.item_exists(new_name, session).await? {
+            return Err(VaultmuxError::AlreadyExists(new_name.to_string()));
+        }
+
+        raw["name"] = serde_json::Value::String(self.prefixed_name(new_name));
+        self.edit_raw_item(&raw, session).await
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+        validate_location_name(location)?;
+
+        let output = run_command(
+            "bw",
+            &["list", "folders"],
+            &[("BW_SESSION", session.token())],
+        )
+        .await?;
+
+        let folders: Vec<BitwardenFolder> = serde_json::from_str(&output)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse folders: {}", e)))?;
+
+        let folder = folders
+            .into_iter()
+            .find(|f| f.name == location)
+            .ok_or_else(|| VaultmuxError::NotFound(location.to_string()))?;
+
+        let mut raw = self.get_raw_item(name, session).await?;
+        raw["folderId"] = serde_json::Value::String(folder.id);
+        self.edit_raw_item(&raw, session).await
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        // Items go to the trash by default; the retention period is fixed
+        if !options.force {
+            return self.delete_item(name, session).await;
+        }
+
         validate_item_name(name)?;
 
-        // Get item ID
         let item = self.get_item(name, session).await?;
 
         run_command(
             "bw",
-            &["delete", "item", &item.id],
+            &["delete", "item", &item.id, "--permanent"],
+            &[("BW_SESSION", session.token())],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        let trash = self.list_trash(session).await?;
+
+        Ok(trash
+            .into_iter()
+            .map(|bw_item| {
+                let deleted: Option<chrono::DateTime<Utc>> =
+                    bw_item.deleted_date.as_deref().and_then(|d| d.parse().ok());
+                let name = if self.prefix.is_empty() {
+                    bw_item.name.clone()
+                } else {
+                    bw_item
+                        .name
+                        .strip_prefix(&format!("{}/", self.prefix))
+                        .unwrap_or(&bw_item.name)
+                        .to_string()
+                };
+
+                DeletedItem {
+                    item: Item {
+                        id: bw_item.id.clone(),
+                        name,
+                        item_type: item_type_from_code(bw_item.item_type),
+                        notes: None,
+                        fields: None,
+                        tags: bw_item.item_tags(),
+                        location: bw_item.folder_id,
+                        created: None,
+                        modified: None,
+                        revision: bw_item.revision_date,
+                    },
+                    deleted,
+                    purge_after: deleted.map(|d| d + chrono::Duration::days(TRASH_RETENTION_DAYS)),
+                }
+            })
+            .collect())
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+
+        if self.item_exists(name, session).await? {
+            return Err(VaultmuxError::AlreadyExists(name.to_string()));
+        }
+
+        // Several trashed items can share a name; restore the latest
+        let full_name = self.prefixed_name(name);
+        let bw_item = self
+            .list_trash(session)
+            .await?
+            .into_iter()
+            .filter(|bw_item| bw_item.name == full_name)
+            .max_by(|a, b| a.deleted_date.cmp(&b.deleted_date))
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))?;
+
+        run_command(
+            "bw",
+            &["restore", "item", &bw_item.id],
             &[("BW_SESSION", session.token())],
         )
         .await?;
@@ -479,6 +1085,62 @@ mod tests {
         assert_eq!(backend.prefixed_name("api-key"), "myapp/api-key");
     }
 
+    #[test]
+    fn test_login_template() {
+        let config = Config::new(crate::BackendType::Bitwarden).with_prefix("myapp");
+        let backend = BitwardenBackend::new(config);
+
+        let mut item = Item::new_login("github", "octocat".to_string(), "pw".to_string());
+        let fields = item.fields.as_mut().unwrap();
+        fields.insert("uri".to_string(), "https://github.com".to_string());
+        fields.insert("recovery".to_string(), "abc-123".to_string());
+
+        let template = backend.item_template(&item);
+        assert_eq!(template["type"], 1);
+        assert_eq!(template["name"], "myapp/github");
+        assert_eq!(template["login"]["username"], "octocat");
+        assert_eq!(template["login"]["password"], "pw");
+        assert_eq!(template["login"]["uris"][0]["uri"], "https://github.com");
+        assert_eq!(template["fields"][0]["name"], "recovery");
+        assert_eq!(template["fields"][0]["value"], "abc-123");
+    }
+
+    #[test]
+    fn test_item_fields_roundtrip() {
+        let bw_item: BitwardenItem = serde_json::from_value(serde_json::json!({
+            "id": "abc",
+            "name": "myapp/visa",
+            "type": 3,
+            "notes": null,
+            "folderId": null,
+            "revisionDate": "2026-01-01T00:00:00.000Z",
+            "card": {
+                "cardholderName": "Jane Doe",
+                "number": "4111111111111111",
+                "expMonth": "12",
+                "expYear": null
+            },
+            "fields": [
+                { "name": "pin-hint", "value": "birthday", "type": 0 },
+                { "name": "tag:team", "value": "payments", "type": 0 }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(item_type_from_code(bw_item.item_type), ItemType::Card);
+
+        let fields = bw_item.item_fields().unwrap();
+        assert_eq!(fields.get("cardholder_name").unwrap(), "Jane Doe");
+        assert_eq!(fields.get("number").unwrap(), "4111111111111111");
+        assert_eq!(fields.get("exp_month").unwrap(), "12");
+        assert!(!fields.contains_key("exp_year"));
+        assert_eq!(fields.get("pin-hint").unwrap(), "birthday");
+        assert!(!fields.contains_key("tag:team"));
+
+        let tags = bw_item.item_tags();
+        assert_eq!(tags.get("team").unwrap(), "payments");
+    }
+
     #[test]
     fn test_prefixed_name_empty() {
         let config = Config::new(crate::BackendType::Bitwarden).with_prefix("");
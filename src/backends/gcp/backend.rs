@@ -1,13 +1,29 @@
 //! GCP Secret Manager backend implementation.
 
 use crate::backends::gcp::GCPSession;
+use crate::encoding::encode_binary;
+use crate::options::paginate;
 use crate::validation::validate_item_name;
-use crate::{Backend, Config, Item, ItemType, Result, Session, VaultmuxError};
+use crate::{
+    Backend, Capabilities, CompareAndSwap, Config, Item, ItemType, ItemVersion, ListOptions,
+    NameCharset, Result, Session, VaultmuxError, VersionState,
+};
 use async_trait::async_trait;
-use google_secretmanager1::api::{AddSecretVersionRequest, Replication, Secret};
-use google_secretmanager1::{hyper, hyper_rustls, oauth2, SecretManager};
+use futures::stream::BoxStream;
+use google_secretmanager1::api::{
+    AddSecretVersionRequest, DestroySecretVersionRequest, DisableSecretVersionRequest, Replication,
+    Secret,
+};
+use google_secretmanager1::{hyper, hyper_rustls, oauth2, FieldMask, SecretManager};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Maximum size of a secret version payload (64 KiB).
+const MAX_PAYLOAD_SIZE: usize = 65536;
+
+/// Maximum number of secrets per `secrets.list` page.
+const MAX_PAGE_SIZE: usize = 25000;
+
 /// GCP Secret Manager backend.
 ///
 /// Integrates with Google Cloud Secret Manager using the official API client.
@@ -59,6 +75,173 @@ impl GCPBackend {
         format!("{}/versions/{}", self.secret_path(name), version)
     }
 
+    /// Creates an empty secret with automatic replication.
+    ///
+    /// Returns the full resource path of the created secret.
+    async fn create_secret(&self, name: &str) -> Result<String> {
+        let hub = self.hub()?;
+        let parent = format!("projects/{}", self.project_id);
+        let secret_name = self.secret_name(name);
+
+        let secret = Secret {
+            replication: Some(Replication {
+                automatic: Some(Default::default()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let (_, created_secret) = hub
+            .projects()
+            .secrets_create(secret, &parent)
+            .secret_id(&secret_name)
+            .doit()
+            .await
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to create secret: {}", e)))?;
+
+        Ok(created_secret
+            .name
+            .unwrap_or_else(|| self.secret_path(name)))
+    }
+
+    /// Adds a new version with the given payload to a secret.
+    async fn add_version(&self, secret_path: &str, data: Vec<u8>) -> Result<()> {
+        let hub = self.hub()?;
+
+        let version_request = AddSecretVersionRequest {
+            payload: Some(google_secretmanager1::api::SecretPayload {
+                data: Some(data),
+                ..Default::default()
+            }),
+        };
+
+        hub.projects()
+            .secrets_add_version(version_request, secret_path)
+            .doit()
+            .await
+            .map_err(|e| {
+                VaultmuxError::Other(anyhow::anyhow!("Failed to add secret version: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Lists secrets under the prefix, optionally narrowed by a server-side filter.
+    async fn list_secrets(&self, filter: Option<&str>) -> Result<Vec<Item>> {
+        let mut items = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let (page, token) = self
+                .list_secrets_page(&self.prefix, filter, None, page_token)
+                .await?;
+            items.extend(page);
+
+            page_token = token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Fetches one page of secrets whose ID starts with `id_prefix`.
+    ///
+    /// The prefix is also sent as a `name:` filter, which matches anywhere
+    /// in the resource name, so it is checked again here.
+    async fn list_secrets_page(
+        &self,
+        id_prefix: &str,
+        filter: Option<&str>,
+        page_size: Option<usize>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<Item>, Option<String>)> {
+        let hub = self.hub()?;
+        let parent = format!("projects/{}", self.project_id);
+
+        let filter = match (id_prefix.is_empty(), filter) {
+            (true, filter) => filter.map(String::from),
+            (false, None) => Some(format!("name:\"{}\"", id_prefix)),
+            (false, Some(filter)) => Some(format!("name:\"{}\" AND {}", id_prefix, filter)),
+        };
+
+        let mut req = hub.projects().secrets_list(&parent);
+        if let Some(ref filter) = filter {
+            req = req.filter(filter);
+        }
+        if let Some(page_size) = page_size {
+            req = req.page_size(page_size.clamp(1, MAX_PAGE_SIZE) as i32);
+        }
+        if let Some(ref token) = page_token {
+            req = req.page_token(token);
+        }
+
+        let (_, response) = req
+            .doit()
+            .await
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to list secrets: {}", e)))?;
+
+        let mut items = Vec::new();
+        for secret in response.secrets.unwrap_or_default() {
+            let Some(full_name) = secret.name else {
+                continue;
+            };
+
+            // Extract secret ID from full name and strip the configured prefix
+            let Some(secret_id) = full_name.rsplit('/').next() else {
+                continue;
+            };
+            if !secret_id.starts_with(id_prefix) {
+                continue;
+            }
+            let Some(name) = secret_id.strip_prefix(&self.prefix) else {
+                continue;
+            };
+
+            items.push(Item {
+                id: full_name.clone(),
+                name: name.to_string(),
+                item_type: ItemType::SecureNote,
+                notes: None,
+                fields: None,
+                tags: secret.labels.unwrap_or_default(),
+                location: None,
+                created: secret.create_time,
+                modified: None,
+                revision: None,
+            });
+        }
+
+        Ok((items, response.next_page_token))
+    }
+
+    /// Reads the raw payload of a secret version.
+    async fn access_version(&self, name: &str, version: &str) -> Result<Vec<u8>> {
+        validate_item_name(name)?;
+
+        let hub = self.hub()?;
+        let version_path = self.version_path(name, version);
+
+        let (_, version_access) = hub
+            .projects()
+            .secrets_versions_access(&version_path)
+            .doit()
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("NOT_FOUND") {
+                    VaultmuxError::NotFound(format!("{} version {}", name, version))
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to access secret value: {}", e))
+                }
+            })?;
+
+        version_access
+            .payload
+            .and_then(|p| p.data)
+            .ok_or_else(|| VaultmuxError::NotFound(format!("{} has no value", name)))
+    }
+
     /// Gets the hub (API client).
     fn hub(
         &self,
@@ -73,6 +256,24 @@ impl Backend for GCPBackend {
         "gcpsecrets"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            versioning: true,
+            disable_versions: true,
+            destroy_versions: true,
+            native_binary: true,
+            tags: true,
+            read_tags: true,
+            max_value_size: Some(MAX_PAYLOAD_SIZE),
+            compare_and_swap: CompareAndSwap::BestEffort,
+            name_charset: NameCharset {
+                extra_chars: Some("_-"),
+                max_length: 255,
+            },
+            ..Capabilities::default()
+        }
+    }
+
     async fn init(&mut self) -> Result<()> {
         if self.project_id.is_empty() {
             return Err(VaultmuxError::Other(anyhow::anyhow!(
@@ -161,31 +362,47 @@ impl Backend for GCPBackend {
                 VaultmuxError::Other(anyhow::anyhow!("Failed to access secret value: {}", e))
             })?;
 
+        // "latest" resolves to a numbered version, which is the revision
+        let revision = version_access
+            .name
+            .as_deref()
+            .and_then(|n| n.rsplit('/').next())
+            .map(String::from);
+
+        // Non-UTF-8 payloads are surfaced in their marked base64 form
         let payload = version_access
             .payload
             .and_then(|p| p.data)
-            .and_then(|data| String::from_utf8(data).ok())
+            .map(|data| String::from_utf8(data).unwrap_or_else(|e| encode_binary(e.as_bytes())))
             .ok_or_else(|| VaultmuxError::Other(anyhow::anyhow!("Secret has no value")))?;
 
         // GCP API already returns DateTime<Utc>
         let created = secret.create_time;
 
-        Ok(Item {
+        let mut item = Item {
             id: secret.name.unwrap_or_default(),
             name: name.to_string(),
             item_type: ItemType::SecureNote,
-            notes: Some(payload),
+            notes: None,
             fields: None,
+            tags: secret.labels.unwrap_or_default(),
             location: None,
             created,
             modified: None, // GCP doesn't track modification time at secret level
-        })
+            revision,
+        };
+
+        // Restores type and fields of items written with create_item_full
+        item.decode_content(payload);
+
+        Ok(item)
     }
 
     async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        // Structured items keep their data in fields, so missing notes
+        // are empty rather than a missing item
         let item = self.get_item(name, session).await?;
-        item.notes
-            .ok_or_else(|| VaultmuxError::NotFound(format!("{} has no value", name)))
+        Ok(item.notes.unwrap_or_default())
     }
 
     async fn item_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
@@ -202,45 +419,142 @@ impl Backend for GCPBackend {
     }
 
     async fn list_items(&self, _session: &dyn Session) -> Result<Vec<Item>> {
+        self.list_secrets(None).await
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        _session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        let id_prefix = format!("{}{}", self.prefix, options.prefix.as_deref().unwrap_or(""));
+
+        let items = paginate(move |page_token| {
+            let id_prefix = id_prefix.clone();
+            async move {
+                self.list_secrets_page(&id_prefix, None, options.page_size, page_token)
+                    .await
+            }
+        });
+
+        options.apply(items)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
+        if self.item_exists(name, _session).await? {
+            return Err(VaultmuxError::AlreadyExists(name.to_string()));
+        }
+
+        // Create the secret, then add the first version with content
+        let secret_path = self.create_secret(name).await?;
+        self.add_version(&secret_path, content.as_bytes().to_vec())
+            .await
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
+        if !self.item_exists(name, _session).await? {
+            return Err(VaultmuxError::NotFound(name.to_string()));
+        }
+
+        // Add a new version (GCP is versioned, updates create new versions)
+        let secret_path = self.secret_path(name);
+        self.add_version(&secret_path, content.as_bytes().to_vec())
+            .await
+    }
+
+    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+
         let hub = self.hub()?;
-        let parent = format!("projects/{}", self.project_id);
+        let secret_path = self.secret_path(name);
 
-        let mut items = Vec::new();
+        hub.projects()
+            .secrets_delete(&secret_path)
+            .doit()
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("NOT_FOUND") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to delete secret: {}", e))
+                }
+            })?;
+
+        Ok(())
+    }
+
+    async fn get_bytes(&self, name: &str, _session: &dyn Session) -> Result<Vec<u8>> {
+        // GCP payloads are raw bytes, no decoding needed
+        self.access_version(name, "latest").await
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+
+        let secret_path = if self.item_exists(name, session).await? {
+            self.secret_path(name)
+        } else {
+            self.create_secret(name).await?
+        };
+
+        self.add_version(&secret_path, data.to_vec()).await
+    }
+
+    async fn list_versions(&self, name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        validate_item_name(name)?;
+
+        let hub = self.hub()?;
+        let secret_path = self.secret_path(name);
+
+        let mut versions = Vec::new();
         let mut page_token: Option<String> = None;
 
         loop {
-            let mut req = hub.projects().secrets_list(&parent);
+            let mut req = hub.projects().secrets_versions_list(&secret_path);
             if let Some(token) = page_token {
                 req = req.page_token(&token);
             }
 
             let (_, response) = req.doit().await.map_err(|e| {
-                VaultmuxError::Other(anyhow::anyhow!("Failed to list secrets: {}", e))
+                if e.to_string().contains("NOT_FOUND") {
+                    VaultmuxError::NotFound(name.to_string())
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to list versions: {}", e))
+                }
             })?;
 
-            if let Some(secrets) = response.secrets {
-                for secret in secrets {
-                    if let Some(full_name) = secret.name {
-                        // Extract secret ID from full name
-                        if let Some(secret_id) = full_name.rsplit('/').next() {
-                            // Filter by prefix
-                            if let Some(name) = secret_id.strip_prefix(&self.prefix) {
-                                let created = secret.create_time;
-
-                                items.push(Item {
-                                    id: full_name.clone(),
-                                    name: name.to_string(),
-                                    item_type: ItemType::SecureNote,
-                                    notes: None,
-                                    fields: None,
-                                    location: None,
-                                    created,
-                                    modified: None,
-                                });
-                            }
-                        }
-                    }
-                }
+            for version in response.versions.unwrap_or_default() {
+                let Some(id) = version.name.as_deref().and_then(|n| n.rsplit('/').next()) else {
+                    continue;
+                };
+
+                let state = match version.state.as_deref() {
+                    Some("DISABLED") => VersionState::Disabled,
+                    Some("DESTROYED") => VersionState::Destroyed,
+                    _ => VersionState::Enabled,
+                };
+
+                versions.push(ItemVersion {
+                    id: id.to_string(),
+                    created: version.create_time,
+                    stages: Vec::new(),
+                    current: false,
+                    state,
+                });
             }
 
             page_token = response.next_page_token;
@@ -249,118 +563,136 @@ impl Backend for GCPBackend {
             }
         }
 
-        Ok(items)
+        // Version numbers increase monotonically; "latest" is the newest one
+        versions.sort_by_key(|v| std::cmp::Reverse(v.id.parse::<u64>().unwrap_or(0)));
+        if let Some(latest) = versions.first_mut() {
+            latest.current = true;
+            latest.stages.push("latest".to_string());
+        }
+
+        Ok(versions)
     }
 
-    async fn create_item(
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<String> {
+        let data = self.access_version(name, version_id).await?;
+        Ok(String::from_utf8(data).unwrap_or_else(|e| encode_binary(e.as_bytes())))
+    }
+
+    async fn disable_version(
         &mut self,
         name: &str,
-        content: &str,
+        version_id: &str,
         _session: &dyn Session,
     ) -> Result<()> {
         validate_item_name(name)?;
 
-        if self.item_exists(name, _session).await? {
-            return Err(VaultmuxError::AlreadyExists(name.to_string()));
-        }
-
         let hub = self.hub()?;
-        let parent = format!("projects/{}", self.project_id);
-        let secret_name = self.secret_name(name);
-
-        // Create the secret
-        let secret = Secret {
-            replication: Some(Replication {
-                automatic: Some(Default::default()),
-                ..Default::default()
-            }),
-            ..Default::default()
-        };
-
-        let (_, created_secret) = hub
-            .projects()
-            .secrets_create(secret, &parent)
-            .secret_id(&secret_name)
-            .doit()
-            .await
-            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to create secret: {}", e)))?;
-
-        // Add the first version with content
-        let secret_path = created_secret
-            .name
-            .unwrap_or_else(|| self.secret_path(name));
-
-        let version_request = AddSecretVersionRequest {
-            payload: Some(google_secretmanager1::api::SecretPayload {
-                data: Some(content.as_bytes().to_vec()),
-                ..Default::default()
-            }),
-        };
+        let version_path = self.version_path(name, version_id);
 
         hub.projects()
-            .secrets_add_version(version_request, &secret_path)
+            .secrets_versions_disable(DisableSecretVersionRequest::default(), &version_path)
             .doit()
             .await
             .map_err(|e| {
-                VaultmuxError::Other(anyhow::anyhow!("Failed to add secret version: {}", e))
+                if e.to_string().contains("NOT_FOUND") {
+                    VaultmuxError::NotFound(format!("{} version {}", name, version_id))
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to disable version: {}", e))
+                }
             })?;
 
         Ok(())
     }
 
-    async fn update_item(
+    async fn destroy_version(
         &mut self,
         name: &str,
-        content: &str,
+        version_id: &str,
         _session: &dyn Session,
     ) -> Result<()> {
         validate_item_name(name)?;
 
-        if !self.item_exists(name, _session).await? {
-            return Err(VaultmuxError::NotFound(name.to_string()));
-        }
-
         let hub = self.hub()?;
-        let secret_path = self.secret_path(name);
-
-        // Add a new version (GCP is versioned, updates create new versions)
-        let version_request = AddSecretVersionRequest {
-            payload: Some(google_secretmanager1::api::SecretPayload {
-                data: Some(content.as_bytes().to_vec()),
-                ..Default::default()
-            }),
-        };
+        let version_path = self.version_path(name, version_id);
 
         hub.projects()
-            .secrets_add_version(version_request, &secret_path)
+            .secrets_versions_destroy(DestroySecretVersionRequest::default(), &version_path)
             .doit()
             .await
-            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to update secret: {}", e)))?;
+            .map_err(|e| {
+                if e.to_string().contains("NOT_FOUND") {
+                    VaultmuxError::NotFound(format!("{} version {}", name, version_id))
+                } else {
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to destroy version: {}", e))
+                }
+            })?;
 
         Ok(())
     }
 
-    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        // Re-add the raw payload so binary values survive the rollback
+        let data = self.access_version(name, version_id).await?;
+        let secret_path = self.secret_path(name);
+        self.add_version(&secret_path, data).await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
         validate_item_name(name)?;
 
         let hub = self.hub()?;
         let secret_path = self.secret_path(name);
 
+        let secret = Secret {
+            labels: Some(tags.clone()),
+            ..Default::default()
+        };
+
         hub.projects()
-            .secrets_delete(&secret_path)
+            .secrets_patch(secret, &secret_path)
+            .update_mask(FieldMask::new(&["labels"]))
             .doit()
             .await
             .map_err(|e| {
                 if e.to_string().contains("NOT_FOUND") {
                     VaultmuxError::NotFound(name.to_string())
                 } else {
-                    VaultmuxError::Other(anyhow::anyhow!("Failed to delete secret: {}", e))
+                    VaultmuxError::Other(anyhow::anyhow!("Failed to set labels: {}", e))
                 }
             })?;
 
         Ok(())
     }
 
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        _session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        let filter = format!("labels.{}={}", key, value);
+        let mut items = self.list_secrets(Some(&filter)).await?;
+
+        // The filter also matches on substrings, so keep exact values only
+        items.retain(|item| item.tags.get(key).map(String::as_str) == Some(value));
+        Ok(items)
+    }
+
     async fn list_locations(&self, _session: &dyn Session) -> Result<Vec<String>> {
         Err(VaultmuxError::NotSupported(
             "GCP Secret Manager does not support locations (secrets are project-scoped)"
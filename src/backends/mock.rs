@@ -8,8 +8,12 @@ use async_trait::async_trait;
 use chrono::Utc;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// How long soft-deleted items stay in the trash by default (30 days).
+const DEFAULT_RECOVERY_WINDOW: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
 /// Mock backend for testing.
 ///
 /// Stores all data in memory with support for error injection to simulate
@@ -42,7 +46,12 @@ use tokio::sync::RwLock;
 pub struct MockBackend {
     items: Arc<RwLock<HashMap<String, Item>>>,
     locations: Arc<RwLock<HashMap<String, bool>>>,
+    versions: Arc<RwLock<HashMap<String, Vec<MockVersion>>>>,
+    trash: Arc<RwLock<HashMap<String, MockDeleted>>>,
+    session_token: Arc<std::sync::Mutex<Option<String>>>,
 
+    /// Error to return from `init()`
+    pub init_error: Option<VaultmuxError>,
     /// Error to return from `authenticate()`
     pub auth_error: Option<VaultmuxError>,
     /// Error to return from `get_item()` and `get_notes()`
@@ -53,6 +62,48 @@ pub struct MockBackend {
     pub update_error: Option<VaultmuxError>,
     /// Error to return from `delete_item()`
     pub delete_error: Option<VaultmuxError>,
+    /// Reject sessions other than the one from the latest `authenticate()`
+    ///
+    /// Reads, `create_item()`, `update_item()` and `delete_item()` then fail
+    /// with [`VaultmuxError::SessionExpired`] for any other session, and
+    /// after [`expire_sessions()`](MockBackend::expire_sessions).
+    pub check_sessions: bool,
+}
+
+/// Returns a copy of an injected error.
+///
+/// Variants that only carry a message keep their type; the rest become
+/// [`VaultmuxError::Other`] with the same message.
+fn injected(err: &VaultmuxError) -> VaultmuxError {
+    match err {
+        VaultmuxError::NotFound(s) => VaultmuxError::NotFound(s.clone()),
+        VaultmuxError::AlreadyExists(s) => VaultmuxError::AlreadyExists(s.clone()),
+        VaultmuxError::NotAuthenticated => VaultmuxError::NotAuthenticated,
+        VaultmuxError::SessionExpired => VaultmuxError::SessionExpired,
+        VaultmuxError::BackendNotInstalled(s) => VaultmuxError::BackendNotInstalled(s.clone()),
+        VaultmuxError::BackendLocked => VaultmuxError::BackendLocked,
+        VaultmuxError::PermissionDenied(s) => VaultmuxError::PermissionDenied(s.clone()),
+        VaultmuxError::NotSupported(s) => VaultmuxError::NotSupported(s.clone()),
+        VaultmuxError::Conflict(s) => VaultmuxError::Conflict(s.clone()),
+        VaultmuxError::Throttled(s) => VaultmuxError::Throttled(s.clone()),
+        VaultmuxError::Timeout(s) => VaultmuxError::Timeout(s.clone()),
+        VaultmuxError::Cancelled => VaultmuxError::Cancelled,
+        VaultmuxError::InvalidItemName(s) => VaultmuxError::InvalidItemName(s.clone()),
+        VaultmuxError::CommandFailed(s) => VaultmuxError::CommandFailed(s.clone()),
+        other => VaultmuxError::Other(anyhow::anyhow!("{}", other)),
+    }
+}
+
+/// A value recorded in the mock backend's version history.
+struct MockVersion {
+    version: ItemVersion,
+    value: Option<String>,
+}
+
+/// A soft-deleted item held in the mock backend's trash.
+struct MockDeleted {
+    deleted: DeletedItem,
+    history: Vec<MockVersion>,
 }
 
 impl MockBackend {
@@ -61,11 +112,32 @@ impl MockBackend {
         Self {
             items: Arc::new(RwLock::new(HashMap::new())),
             locations: Arc::new(RwLock::new(HashMap::new())),
+            versions: Arc::new(RwLock::new(HashMap::new())),
+            trash: Arc::new(RwLock::new(HashMap::new())),
+            session_token: Arc::new(std::sync::Mutex::new(None)),
+            init_error: None,
             auth_error: None,
             get_error: None,
             create_error: None,
             update_error: None,
             delete_error: None,
+            check_sessions: false,
+        }
+    }
+
+    /// Invalidates the current session, as if it had timed out.
+    pub fn expire_sessions(&self) {
+        *self.session_token.lock().unwrap() = None;
+    }
+
+    /// Fails unless `session` is current, when sessions are checked.
+    fn check_session(&self, session: &dyn Session) -> Result<()> {
+        if !self.check_sessions {
+            return Ok(());
+        }
+        match self.session_token.lock().unwrap().as_deref() {
+            Some(token) if token == session.token() => Ok(()),
+            _ => Err(VaultmuxError::SessionExpired),
         }
     }
 
@@ -73,11 +145,69 @@ impl MockBackend {
     ///
     /// Useful for setting up test fixtures.
     pub async fn set_item(&self, name: impl Into<String>, content: impl Into<String>) {
-        let item = Item::new_secure_note(name, content);
+        let mut item = Item::new_secure_note(name, content);
+        let revision = self
+            .record_version(&item.name, item.notes.clone().unwrap_or_default())
+            .await;
+        item.revision = Some(revision);
         let mut items = self.items.write().await;
         items.insert(item.name.clone(), item);
     }
 
+    /// Appends a new current version to an item's history.
+    ///
+    /// Returns the new version ID, which doubles as the item's revision.
+    async fn record_version(&self, name: &str, value: String) -> String {
+        let mut versions = self.versions.write().await;
+        let history = versions.entry(name.to_string()).or_default();
+
+        for previous in history.iter_mut() {
+            previous.version.current = false;
+        }
+
+        let id = (history.len() + 1).to_string();
+        history.push(MockVersion {
+            version: ItemVersion {
+                id: id.clone(),
+                created: Some(Utc::now()),
+                stages: Vec::new(),
+                current: true,
+                state: VersionState::Enabled,
+            },
+            value: Some(value),
+        });
+
+        id
+    }
+
+    /// Sets the state of a recorded version.
+    async fn set_version_state(
+        &self,
+        name: &str,
+        version_id: &str,
+        state: VersionState,
+    ) -> Result<()> {
+        let mut versions = self.versions.write().await;
+        let version = versions
+            .get_mut(name)
+            .and_then(|history| history.iter_mut().find(|v| v.version.id == version_id))
+            .ok_or_else(|| VaultmuxError::NotFound(format!("{} version {}", name, version_id)))?;
+
+        if version.version.state == VersionState::Destroyed {
+            return Err(VaultmuxError::Other(anyhow::anyhow!(
+                "version {} of {} has been destroyed",
+                version_id,
+                name
+            )));
+        }
+
+        version.version.state = state;
+        if state == VersionState::Destroyed {
+            version.value = None;
+        }
+        Ok(())
+    }
+
     /// Pre-populates the backend with a location.
     pub async fn set_location(&self, name: impl Into<String>) {
         let mut locations = self.locations.write().await;
@@ -97,10 +227,8 @@ pub struct MockSession {
 }
 
 impl MockSession {
-    fn new() -> Self {
-        Self {
-            token: "mock-session-token".to_string(),
-        }
+    fn new(token: String) -> Self {
+        Self { token }
     }
 }
 
@@ -129,8 +257,27 @@ impl Backend for MockBackend {
         "mock"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            locations: true,
+            create_locations: true,
+            native_rename: true,
+            versioning: true,
+            disable_versions: true,
+            destroy_versions: true,
+            tags: true,
+            read_tags: true,
+            soft_delete: true,
+            compare_and_swap: CompareAndSwap::Atomic,
+            ..Capabilities::default()
+        }
+    }
+
     async fn init(&mut self) -> Result<()> {
-        Ok(())
+        match self.init_error {
+            Some(ref err) => Err(injected(err)),
+            None => Ok(()),
+        }
     }
 
     async fn close(&mut self) -> Result<()> {
@@ -143,18 +290,21 @@ impl Backend for MockBackend {
 
     async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
         if let Some(ref err) = self.auth_error {
-            return Err(VaultmuxError::Other(anyhow::anyhow!("{}", err)));
+            return Err(injected(err));
         }
-        Ok(Arc::new(MockSession::new()))
+        let token = format!("mock-session-{}", uuid::Uuid::new_v4());
+        *self.session_token.lock().unwrap() = Some(token.clone());
+        Ok(Arc::new(MockSession::new(token)))
     }
 
     async fn sync(&mut self, _session: &dyn Session) -> Result<()> {
         Ok(())
     }
 
-    async fn get_item(&self, name: &str, _session: &dyn Session) -> Result<Item> {
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        self.check_session(session)?;
         if let Some(ref err) = self.get_error {
-            return Err(VaultmuxError::Other(anyhow::anyhow!("{}", err)));
+            return Err(injected(err));
         }
 
         let items = self.items.read().await;
@@ -166,16 +316,17 @@ impl Backend for MockBackend {
 
     async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
         let item = self.get_item(name, session).await?;
-        item.notes
-            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))
+        Ok(item.notes.unwrap_or_default())
     }
 
-    async fn item_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        self.check_session(session)?;
         let items = self.items.read().await;
         Ok(items.contains_key(name))
     }
 
-    async fn list_items(&self, _session: &dyn Session) -> Result<Vec<Item>> {
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        self.check_session(session)?;
         let items = self.items.read().await;
         Ok(items.values().cloned().collect())
     }
@@ -184,10 +335,11 @@ impl Backend for MockBackend {
         &mut self,
         name: &str,
         content: &str,
-        _session: &dyn Session,
+        session: &dyn Session,
     ) -> Result<()> {
+        self.check_session(session)?;
         if let Some(ref err) = self.create_error {
-            return Err(VaultmuxError::Other(anyhow::anyhow!("{}", err)));
+            return Err(injected(err));
         }
 
         let mut items = self.items.write().await;
@@ -195,7 +347,8 @@ impl Backend for MockBackend {
             return Err(VaultmuxError::AlreadyExists(name.to_string()));
         }
 
-        let item = Item::new_secure_note(name, content);
+        let mut item = Item::new_secure_note(name, content);
+        item.revision = Some(self.record_version(name, content.to_string()).await);
         items.insert(name.to_string(), item);
         Ok(())
     }
@@ -204,10 +357,11 @@ impl Backend for MockBackend {
         &mut self,
         name: &str,
         content: &str,
-        _session: &dyn Session,
+        session: &dyn Session,
     ) -> Result<()> {
+        self.check_session(session)?;
         if let Some(ref err) = self.update_error {
-            return Err(VaultmuxError::Other(anyhow::anyhow!("{}", err)));
+            return Err(injected(err));
         }
 
         let mut items = self.items.write().await;
@@ -217,18 +371,300 @@ impl Backend for MockBackend {
 
         item.notes = Some(content.to_string());
         item.modified = Some(Utc::now());
+        item.revision = Some(self.record_version(name, content.to_string()).await);
         Ok(())
     }
 
-    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        self.check_session(session)?;
         if let Some(ref err) = self.delete_error {
-            return Err(VaultmuxError::Other(anyhow::anyhow!("{}", err)));
+            return Err(injected(err));
         }
 
         let mut items = self.items.write().await;
         items
             .remove(name)
             .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))?;
+        self.versions.write().await.remove(name);
+        Ok(())
+    }
+
+    async fn create_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        if let Some(ref err) = self.create_error {
+            return Err(injected(err));
+        }
+
+        let content = item.encode_content()?;
+
+        let mut items = self.items.write().await;
+        if items.contains_key(&item.name) {
+            return Err(VaultmuxError::AlreadyExists(item.name.clone()));
+        }
+
+        let mut stored = item.clone();
+        stored.created = Some(Utc::now());
+        stored.modified = stored.created;
+        stored.revision = Some(self.record_version(&item.name, content).await);
+        items.insert(item.name.clone(), stored);
+        Ok(())
+    }
+
+    async fn update_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        if let Some(ref err) = self.update_error {
+            return Err(injected(err));
+        }
+
+        let content = item.encode_content()?;
+
+        let mut items = self.items.write().await;
+        let existing = items
+            .get_mut(&item.name)
+            .ok_or_else(|| VaultmuxError::NotFound(item.name.clone()))?;
+
+        existing.item_type = item.item_type;
+        existing.notes = item.notes.clone();
+        existing.fields = item.fields.clone();
+        if !item.tags.is_empty() {
+            existing.tags = item.tags.clone();
+        }
+        existing.modified = Some(Utc::now());
+        existing.revision = Some(self.record_version(&item.name, content).await);
+        Ok(())
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        if let Some(ref err) = self.update_error {
+            return Err(injected(err));
+        }
+
+        // The write lock is held across compare and write
+        let mut items = self.items.write().await;
+        let item = items
+            .get_mut(name)
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))?;
+
+        let revision = item.revision.clone().unwrap_or_default();
+        if revision != expected_revision {
+            return Err(VaultmuxError::Conflict(format!(
+                "{} is at revision {}, expected {}",
+                name, revision, expected_revision
+            )));
+        }
+
+        item.notes = Some(content.to_string());
+        item.modified = Some(Utc::now());
+        item.revision = Some(self.record_version(name, content.to_string()).await);
+        Ok(())
+    }
+
+    async fn list_versions(&self, name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        let versions = self.versions.read().await;
+        let history = versions
+            .get(name)
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))?;
+
+        Ok(history.iter().rev().map(|v| v.version.clone()).collect())
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<String> {
+        if let Some(ref err) = self.get_error {
+            return Err(injected(err));
+        }
+
+        let versions = self.versions.read().await;
+        let version = versions
+            .get(name)
+            .and_then(|history| history.iter().find(|v| v.version.id == version_id))
+            .ok_or_else(|| VaultmuxError::NotFound(format!("{} version {}", name, version_id)))?;
+
+        match version.version.state {
+            VersionState::Enabled => Ok(version.value.clone().unwrap_or_default()),
+            VersionState::Disabled => Err(VaultmuxError::Other(anyhow::anyhow!(
+                "version {} of {} is disabled",
+                version_id,
+                name
+            ))),
+            VersionState::Destroyed => Err(VaultmuxError::Other(anyhow::anyhow!(
+                "version {} of {} has been destroyed",
+                version_id,
+                name
+            ))),
+        }
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.set_version_state(name, version_id, VersionState::Disabled)
+            .await
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.set_version_state(name, version_id, VersionState::Destroyed)
+            .await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        if let Some(ref err) = self.update_error {
+            return Err(injected(err));
+        }
+
+        let mut items = self.items.write().await;
+        let item = items
+            .get_mut(name)
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))?;
+
+        item.tags = tags.clone();
+        Ok(())
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        if let Some(ref err) = self.update_error {
+            return Err(injected(err));
+        }
+
+        let mut items = self.items.write().await;
+        if items.contains_key(new_name) {
+            return Err(VaultmuxError::AlreadyExists(new_name.to_string()));
+        }
+
+        let mut item = items
+            .remove(old_name)
+            .ok_or_else(|| VaultmuxError::NotFound(old_name.to_string()))?;
+        item.name = new_name.to_string();
+        items.insert(new_name.to_string(), item);
+
+        let mut versions = self.versions.write().await;
+        if let Some(history) = versions.remove(old_name) {
+            versions.insert(new_name.to_string(), history);
+        }
+        Ok(())
+    }
+
+    async fn move_item(
+        &mut self,
+        name: &str,
+        location: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        if let Some(ref err) = self.update_error {
+            return Err(injected(err));
+        }
+
+        if !self.locations.read().await.contains_key(location) {
+            return Err(VaultmuxError::NotFound(location.to_string()));
+        }
+
+        let mut items = self.items.write().await;
+        let item = items
+            .get_mut(name)
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))?;
+
+        item.location = Some(location.to_string());
+        item.modified = Some(Utc::now());
+        Ok(())
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        if options.force {
+            return self.delete_item(name, session).await;
+        }
+        if let Some(ref err) = self.delete_error {
+            return Err(injected(err));
+        }
+
+        let item = self
+            .items
+            .write()
+            .await
+            .remove(name)
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))?;
+        let history = self.versions.write().await.remove(name).unwrap_or_default();
+
+        let now = Utc::now();
+        let window = options.recovery_window.unwrap_or(DEFAULT_RECOVERY_WINDOW);
+        let purge_after = chrono::Duration::from_std(window)
+            .ok()
+            .and_then(|window| now.checked_add_signed(window));
+
+        self.trash.write().await.insert(
+            name.to_string(),
+            MockDeleted {
+                deleted: DeletedItem {
+                    item,
+                    deleted: Some(now),
+                    purge_after,
+                },
+                history,
+            },
+        );
+        Ok(())
+    }
+
+    async fn list_deleted_items(&self, _session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        let now = Utc::now();
+        let mut trash = self.trash.write().await;
+        trash.retain(|_, entry| entry.deleted.purge_after.is_none_or(|purge| purge > now));
+
+        Ok(trash.values().map(|entry| entry.deleted.clone()).collect())
+    }
+
+    async fn restore_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        if let Some(ref err) = self.update_error {
+            return Err(injected(err));
+        }
+
+        let mut items = self.items.write().await;
+        if items.contains_key(name) {
+            return Err(VaultmuxError::AlreadyExists(name.to_string()));
+        }
+
+        let now = Utc::now();
+        let mut trash = self.trash.write().await;
+        let entry = trash
+            .remove(name)
+            .filter(|entry| entry.deleted.purge_after.is_none_or(|purge| purge > now))
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))?;
+
+        items.insert(name.to_string(), entry.deleted.item);
+        self.versions
+            .write()
+            .await
+            .insert(name.to_string(), entry.history);
         Ok(())
     }
 
@@ -332,6 +768,416 @@ mod tests {
         assert_eq!(items.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_mock_backend_list_stream() {
+        use futures::TryStreamExt;
+
+        let backend = MockBackend::new();
+        let session = MockSession::new("mock-session-token".to_string());
+
+        for name in [
+            "prod/api-key",
+            "prod/db-password",
+            "prod/db-user",
+            "staging/api-key",
+        ] {
+            backend.set_item(name, "value").await;
+        }
+
+        let options = ListOptions::new()
+            .with_prefix("prod/")
+            .with_name_glob("*/db-*");
+        let mut names: Vec<String> = backend
+            .list_items_stream(&options, &session)
+            .map_ok(|item| item.name)
+            .try_collect()
+            .await
+            .unwrap();
+        names.sort();
+        assert_eq!(names, vec!["prod/db-password", "prod/db-user"]);
+
+        let options = ListOptions::new().with_limit(3);
+        let items: Vec<Item> = backend
+            .list_items_stream(&options, &session)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_mock_backend_watch() {
+        use futures::StreamExt;
+        use std::time::Duration;
+
+        let backend = MockBackend::new();
+        let session = MockSession::new("mock-session-token".to_string());
+        backend.set_item("app/db-password", "v1").await;
+        backend.set_item("other/key", "v1").await;
+
+        let options = WatchOptions::prefix("app/").with_interval(Duration::from_millis(10));
+        let mut changes = backend.watch(&options, &session);
+
+        let writer = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            backend.set_item("other/key", "v2").await;
+            backend.set_item("app/db-password", "v2").await;
+        };
+        let (change, _) = tokio::join!(changes.next(), writer);
+
+        let change = change.unwrap().unwrap();
+        assert_eq!(change.name, "app/db-password");
+        assert_eq!(change.kind, ChangeKind::Updated);
+        assert_ne!(change.old_revision, change.new_revision);
+
+        let writer = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            backend.set_item("app/api-key", "v1").await;
+        };
+        let (change, _) = tokio::join!(changes.next(), writer);
+        assert_eq!(change.unwrap().unwrap().kind, ChangeKind::Created);
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_batch() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        backend.set_item("existing", "old").await;
+
+        let results = backend
+            .put_many(&[("existing", "new"), ("fresh", "v1")], &*session)
+            .await
+            .unwrap();
+        assert!(results.iter().all(|r| r.is_ok()));
+
+        let results = backend
+            .get_many(&["fresh", "missing", "existing"], &*session)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_deref().unwrap(), "v1");
+        assert!(matches!(results[1], Err(VaultmuxError::NotFound(_))));
+        assert_eq!(results[2].as_deref().unwrap(), "new");
+
+        let results = backend
+            .delete_many(&["fresh", "missing"], &*session)
+            .await
+            .unwrap();
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(VaultmuxError::NotFound(_))));
+        assert!(!backend.item_exists("fresh", &*session).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_structured_items() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        let login = Item::new_login("db", "admin".to_string(), "hunter2".to_string());
+        backend.create_item_full(&login, &*session).await.unwrap();
+
+        let item = backend.get_item("db", &*session).await.unwrap();
+        assert_eq!(item.item_type, ItemType::Login);
+        assert_eq!(item.fields, login.fields);
+
+        let mut rotated = login.clone();
+        rotated
+            .fields
+            .as_mut()
+            .unwrap()
+            .insert("password".to_string(), "correct-horse".to_string());
+        backend.update_item_full(&rotated, &*session).await.unwrap();
+
+        let item = backend.get_item("db", &*session).await.unwrap();
+        assert_eq!(
+            item.fields.unwrap().get("password").map(String::as_str),
+            Some("correct-horse")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_bytes() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        let keytab = vec![0x05, 0x02, 0x00, 0xff, 0xfe];
+        backend
+            .put_bytes("service.keytab", &keytab, &*session)
+            .await
+            .unwrap();
+        assert_eq!(
+            backend
+                .get_bytes("service.keytab", &*session)
+                .await
+                .unwrap(),
+            keytab
+        );
+
+        // put_bytes replaces an existing value
+        backend
+            .put_bytes("service.keytab", b"v2", &*session)
+            .await
+            .unwrap();
+        assert_eq!(
+            backend
+                .get_bytes("service.keytab", &*session)
+                .await
+                .unwrap(),
+            b"v2".to_vec()
+        );
+
+        // Plain text items read back as UTF-8 bytes
+        backend.set_item("text", "hello").await;
+        assert_eq!(
+            backend.get_bytes("text", &*session).await.unwrap(),
+            b"hello".to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_versions() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        backend
+            .create_item("db-password", "v1", &*session)
+            .await
+            .unwrap();
+        backend
+            .update_item("db-password", "v2-bad", &*session)
+            .await
+            .unwrap();
+
+        let versions = backend
+            .list_versions("db-password", &*session)
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].id, "2");
+        assert!(versions[0].current);
+        assert!(!versions[1].current);
+        assert_eq!(
+            backend
+                .get_version("db-password", "1", &*session)
+                .await
+                .unwrap(),
+            "v1"
+        );
+
+        // Rollback writes the old value as a new current version
+        backend
+            .rollback("db-password", "1", &*session)
+            .await
+            .unwrap();
+        assert_eq!(
+            backend.get_notes("db-password", &*session).await.unwrap(),
+            "v1"
+        );
+        assert_eq!(
+            backend
+                .list_versions("db-password", &*session)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+
+        backend
+            .disable_version("db-password", "2", &*session)
+            .await
+            .unwrap();
+        assert!(backend
+            .get_version("db-password", "2", &*session)
+            .await
+            .is_err());
+
+        backend
+            .destroy_version("db-password", "2", &*session)
+            .await
+            .unwrap();
+        let versions = backend
+            .list_versions("db-password", &*session)
+            .await
+            .unwrap();
+        assert_eq!(versions[1].state, VersionState::Destroyed);
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_tags() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        let mut item = Item::new_secure_note("db-password", "hunter2");
+        item.tags.insert("team".to_string(), "payments".to_string());
+        item.tags.insert("env".to_string(), "prod".to_string());
+        backend.create_item_full(&item, &*session).await.unwrap();
+        backend
+            .create_item("api-key", "sk_test", &*session)
+            .await
+            .unwrap();
+
+        let tags = backend.get_tags("db-password", &*session).await.unwrap();
+        assert_eq!(tags, item.tags);
+
+        let tagged = backend
+            .list_items_by_tag("team", "payments", &*session)
+            .await
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].name, "db-password");
+
+        let mut staging = HashMap::new();
+        staging.insert("env".to_string(), "staging".to_string());
+        backend
+            .set_tags("api-key", &staging, &*session)
+            .await
+            .unwrap();
+
+        let tagged = backend
+            .list_items_by_tag("env", "prod", &*session)
+            .await
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert!(backend
+            .list_items_by_tag("team", "identity", &*session)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_update_if() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        backend.create_item("token", "v1", &*session).await.unwrap();
+        let read = backend.get_item("token", &*session).await.unwrap();
+        let revision = read.revision.unwrap();
+
+        backend
+            .update_item_if("token", "v2", &revision, &*session)
+            .await
+            .unwrap();
+
+        // A second writer holding the stale revision loses
+        let result = backend
+            .update_item_if("token", "v2-other", &revision, &*session)
+            .await;
+        assert!(matches!(result, Err(VaultmuxError::Conflict(_))));
+        assert_eq!(backend.get_notes("token", &*session).await.unwrap(), "v2");
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_rename_and_move() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        backend
+            .create_item("old-key", "v1", &*session)
+            .await
+            .unwrap();
+        backend
+            .update_item("old-key", "v2", &*session)
+            .await
+            .unwrap();
+        backend
+            .create_item("taken", "other", &*session)
+            .await
+            .unwrap();
+
+        let result = backend.rename_item("old-key", "taken", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::AlreadyExists(_))));
+
+        backend
+            .rename_item("old-key", "new-key", &*session)
+            .await
+            .unwrap();
+        assert!(!backend.item_exists("old-key", &*session).await.unwrap());
+        assert_eq!(backend.get_notes("new-key", &*session).await.unwrap(), "v2");
+        assert_eq!(
+            backend
+                .list_versions("new-key", &*session)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        backend.set_location("prod").await;
+        backend
+            .move_item("new-key", "prod", &*session)
+            .await
+            .unwrap();
+        let items = backend
+            .list_items_in_location("folder", "prod", &*session)
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
+
+        let result = backend.move_item("new-key", "staging", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_soft_delete() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        backend.create_item("key", "v1", &*session).await.unwrap();
+        backend
+            .delete_item_with("key", &DeleteOptions::new(), &*session)
+            .await
+            .unwrap();
+        assert!(!backend.item_exists("key", &*session).await.unwrap());
+
+        let deleted = backend.list_deleted_items(&*session).await.unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].item.name, "key");
+        assert!(deleted[0].purge_after > deleted[0].deleted);
+
+        backend.create_item("key", "v2", &*session).await.unwrap();
+        let result = backend.restore_item("key", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::AlreadyExists(_))));
+
+        backend.delete_item("key", &*session).await.unwrap();
+        backend.restore_item("key", &*session).await.unwrap();
+        assert_eq!(backend.get_notes("key", &*session).await.unwrap(), "v1");
+        assert!(backend
+            .list_deleted_items(&*session)
+            .await
+            .unwrap()
+            .is_empty());
+
+        backend
+            .delete_item_with("key", &DeleteOptions::new().with_force(true), &*session)
+            .await
+            .unwrap();
+        let result = backend.restore_item("key", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_recovery_window_expiry() {
+        let mut backend = MockBackend::new();
+        let session = backend.authenticate().await.unwrap();
+
+        backend.create_item("key", "v1", &*session).await.unwrap();
+        let options = DeleteOptions::new().with_recovery_window(Duration::ZERO);
+        backend
+            .delete_item_with("key", &options, &*session)
+            .await
+            .unwrap();
+
+        assert!(backend
+            .list_deleted_items(&*session)
+            .await
+            .unwrap()
+            .is_empty());
+        let result = backend.restore_item("key", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::NotFound(_))));
+    }
+
     #[tokio::test]
     async fn test_error_injection() {
         let mut backend = MockBackend::new();
@@ -340,7 +1186,7 @@ mod tests {
         let session = backend.authenticate().await.unwrap();
         let result = backend.get_notes("anything", &*session).await;
 
-        assert!(result.is_err());
+        assert!(matches!(result, Err(VaultmuxError::PermissionDenied(_))));
     }
 
     #[tokio::test]
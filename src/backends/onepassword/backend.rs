@@ -1,11 +1,15 @@
 //! 1Password backend implementation.
 
 use crate::backends::onepassword::OnePasswordSession;
-use crate::cli::{check_command_exists, run_command, StatusCache};
-use crate::validation::validate_item_name;
-use crate::{Backend, Config, Item, ItemType, Result, Session, VaultmuxError};
+use crate::cli::{check_command_exists, run_command, run_command_with_stdin, StatusCache};
+use crate::validation::{validate_item_name, validate_location_name};
+use crate::{
+    Backend, Capabilities, CompareAndSwap, Config, DeleteOptions, DeletedItem, Item, ItemType,
+    Result, Session, VaultmuxError,
+};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
@@ -63,6 +67,97 @@ impl OnePasswordBackend {
         }
     }
 
+    /// Builds an `op item create` template from a structured item.
+    fn item_template(&self, item: &Item) -> serde_json::Value {
+        let mut fields: Vec<serde_json::Value> = Vec::new();
+
+        let mut entries: Vec<(&String, &String)> = item.fields.iter().flatten().collect();
+        entries.sort();
+
+        for (key, value) in entries {
+            let mut field = serde_json::json!({
+                "id": key,
+                "type": field_type(key),
+                "label": key,
+                "value": value,
+            });
+            match key.as_str() {
+                "username" => field["purpose"] = serde_json::json!("USERNAME"),
+                "password" => field["purpose"] = serde_json::json!("PASSWORD"),
+                _ => {}
+            }
+            fields.push(field);
+        }
+
+        if let Some(ref notes) = item.notes {
+            fields.push(serde_json::json!({
+                "id": "notesPlain",
+                "type": "STRING",
+                "purpose": "NOTES",
+                "label": "notesPlain",
+                "value": notes,
+            }));
+        }
+
+        let mut template = serde_json::json!({
+            "title": self.item_name(&item.name),
+            "category": category(item.item_type),
+            "fields": fields,
+        });
+
+        if !item.tags.is_empty() {
+            let tags: Vec<String> = op_tags(&item.tags).split(',').map(String::from).collect();
+            template["tags"] = serde_json::json!(tags);
+        }
+
+        template
+    }
+
+    /// Parses `op item list` output into items under the prefix.
+    ///
+    /// Items are placed in `location` if given, else in their own vault.
+    fn parse_item_list(&self, output: &str, location: Option<&str>) -> Result<Vec<Item>> {
+        let op_items: Vec<OpItem> = serde_json::from_str(output)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse items: {}", e)))?;
+
+        let mut items = Vec::new();
+        for op_item in op_items {
+            if let Some(name) = self.strip_prefix(&op_item.title) {
+                let created = op_item.created_at.as_ref().and_then(|s| {
+                    chrono::DateTime::parse_from_rfc3339(s)
+                        .ok()
+                        .map(|dt| dt.with_timezone(&chrono::Utc))
+                });
+
+                let modified = op_item.updated_at.as_ref().and_then(|s| {
+                    chrono::DateTime::parse_from_rfc3339(s)
+                        .ok()
+                        .map(|dt| dt.with_timezone(&chrono::Utc))
+                });
+
+                let item_type = item_type_from_category(op_item.category.as_deref());
+                let tags = op_item.item_tags();
+
+                items.push(Item {
+                    id: op_item.id,
+                    name: name.to_string(),
+                    item_type,
+                    notes: None,
+                    fields: None,
+                    tags,
+                    location: location
+                        .map(String::from)
+                        .or_else(|| op_item.vault.as_ref().map(|v| v.name.clone())),
+                    created,
+                    modified,
+                    revision: op_item.updated_at,
+                });
+            }
+        }
+
+        Ok(items)
+    }
+
     /// Gets account from session or uses configured account.
     fn get_account(&self, _session: &dyn Session) -> String {
         if let Some(ref account) = self.account {
@@ -73,6 +168,38 @@ impl OnePasswordBackend {
     }
 }
 
+/// Converts a 1Password category to an [`ItemType`].
+fn item_type_from_category(category: Option<&str>) -> ItemType {
+    match category {
+        Some("LOGIN") | Some("PASSWORD") => ItemType::Login,
+        Some("CREDIT_CARD") => ItemType::Card,
+        Some("IDENTITY") => ItemType::Identity,
+        Some("SSH_KEY") => ItemType::SSHKey,
+        _ => ItemType::SecureNote,
+    }
+}
+
+/// Converts an [`ItemType`] to a 1Password category.
+fn category(item_type: ItemType) -> &'static str {
+    match item_type {
+        ItemType::Login => "LOGIN",
+        ItemType::SecureNote => "SECURE_NOTE",
+        ItemType::Card => "CREDIT_CARD",
+        ItemType::Identity => "IDENTITY",
+        ItemType::SSHKey => "SSH_KEY",
+    }
+}
+
+/// Returns the 1Password field type for a vaultmux field key.
+fn field_type(key: &str) -> &'static str {
+    match key {
+        "password" | "code" | "number" | "ssn" => "CONCEALED",
+        "private_key" => "SSHKEY",
+        "totp" => "OTP",
+        _ => "STRING",
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct OpAccount {
     shorthand: String,
@@ -95,9 +222,60 @@ struct OpItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     urls: Option<Vec<OpUrl>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state: Option<String>,
+}
+
+impl OpItem {
+    /// Collects labelled field values, excluding the notes field.
+    fn item_fields(&self) -> Option<HashMap<String, String>> {
+        let fields: HashMap<String, String> = self
+            .fields
+            .iter()
+            .flatten()
+            .filter(|f| f.id != "notesPlain" && f.label != "notesPlain")
+            .filter_map(|f| f.value.clone().map(|v| (f.label.clone(), v)))
+            .collect();
+
+        if fields.is_empty() {
+            None
+        } else {
+            Some(fields)
+        }
+    }
+
+    /// Parses `key=value` tags into a map; bare tags get an empty value.
+    fn item_tags(&self) -> HashMap<String, String> {
+        self.tags
+            .iter()
+            .flatten()
+            .map(|tag| match tag.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (tag.clone(), String::new()),
+            })
+            .collect()
+    }
+}
+
+/// Formats tags as op's comma-separated `key=value` list.
+fn op_tags(tags: &HashMap<String, String>) -> String {
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|(key, value)| {
+            if value.is_empty() {
+                key.clone()
+            } else {
+                format!("{}={}", key, value)
+            }
+        })
+        .collect();
+    tags.sort();
+    tags.join(",")
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -129,6 +307,17 @@ impl Backend for OnePasswordBackend {
         "onepassword"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            locations: true,
+            native_rename: true,
+            tags: true,
+            read_tags: true,
+            compare_and_swap: CompareAndSwap::BestEffort,
+            ..Capabilities::default()
+        }
+    }
+
     async fn init(&mut self) -> Result<()> {
         if !check_command_exists("op").await? {
             return Err(VaultmuxError::BackendNotInstalled(
@@ -256,15 +445,21 @@ impl Backend for OnePasswordBackend {
                 .map(|dt| dt.with_timezone(&chrono::Utc))
         });
 
+        let item_type = item_type_from_category(op_item.category.as_deref());
+        let fields = op_item.item_fields();
+        let tags = op_item.item_tags();
+
         Ok(Item {
             id: op_item.id,
             name: name.to_string(),
-            item_type: ItemType::SecureNote,
+            item_type,
             notes,
-            fields: None,
+            fields,
+            tags,
             location: op_item.vault.as_ref().map(|v| v.name.clone()),
             created,
             modified,
+            revision: op_item.updated_at,
         })
     }
 
@@ -319,38 +514,7 @@ impl Backend for OnePasswordBackend {
         )
         .await?;
 
-        let op_items: Vec<OpItem> = serde_json::from_str(&output)
-            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse items: {}", e)))?;
-
-        let mut items = Vec::new();
-        for op_item in op_items {
-            if let Some(name) = self.strip_prefix(&op_item.title) {
-                let created = op_item.created_at.as_ref().and_then(|s| {
-                    chrono::DateTime::parse_from_rfc3339(s)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                });
-
-                let modified = op_item.updated_at.as_ref().and_then(|s| {
-                    chrono::DateTime::parse_from_rfc3339(s)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                });
-
-                items.push(Item {
-                    id: op_item.id,
-                    name: name.to_string(),
-                    item_type: ItemType::SecureNote,
-                    notes: None,
-                    fields: None,
-                    location: op_item.vault.as_ref().map(|v| v.name.clone()),
-                    created,
-                    modified,
-                });
-            }
-        }
-
-        Ok(items)
+        self.parse_item_list(&output, None)
     }
 
     async fn create_item(
@@ -391,17 +555,11 @@ impl Backend for OnePasswordBackend {
             VaultmuxError::Other(anyhow::anyhow!("Failed to create template: {}", e))
         })?;
 
-        run_command(
+        run_command_with_stdin(
             "op",
-            &[
-                "item",
-                "create",
-                "--vault",
-                &self.vault,
-                "--template",
-                &template_str,
-            ],
+            &["item", "create", "--vault", &self.vault, "-"],
             &env,
+            &template_str,
         )
         .await?;
 
@@ -475,6 +633,348 @@ impl Backend for OnePasswordBackend {
         Ok(())
     }
 
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        validate_item_name(&item.name)?;
+
+        if self.item_exists(&item.name, session).await? {
+            return Err(VaultmuxError::AlreadyExists(item.name.clone()));
+        }
+
+        let account = self.get_account(session);
+
+        let env = if !account.is_empty() {
+            vec![("OP_SESSION", session.token())]
+        } else {
+            vec![]
+        };
+
+        let template_str = serde_json::to_string(&self.item_template(item)).map_err(|e| {
+            VaultmuxError::Other(anyhow::anyhow!("Failed to create template: {}", e))
+        })?;
+
+        // The template goes on stdin so field values stay out of argv
+        run_command_with_stdin(
+            "op",
+            &["item", "create", "--vault", &self.vault, "-"],
+            &env,
+            &template_str,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        validate_item_name(&item.name)?;
+
+        let existing = self.get_item(&item.name, session).await?;
+
+        let item_name = self.item_name(&item.name);
+        let account = self.get_account(session);
+
+        let env = if !account.is_empty() {
+            vec![("OP_SESSION", session.token())]
+        } else {
+            vec![]
+        };
+
+        // The template replaces the item, so keep existing tags unless the
+        // item carries its own
+        let template = if item.tags.is_empty() {
+            let mut tagged = item.clone();
+            tagged.tags = existing.tags;
+            self.item_template(&tagged)
+        } else {
+            self.item_template(item)
+        };
+        let template_str = serde_json::to_string(&template).map_err(|e| {
+            VaultmuxError::Other(anyhow::anyhow!("Failed to create template: {}", e))
+        })?;
+
+        // The template goes on stdin so field values stay out of argv
+        run_command_with_stdin(
+            "op",
+            &[
+                "item",
+                "edit",
+                &item_name,
+                "--vault",
+                &self.vault,
+                "--template",
+                "-",
+            ],
+            &env,
+            &template_str,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(name)?;
+
+        let item_name = self.item_name(name);
+        let account = self.get_account(session);
+
+        let env = if !account.is_empty() {
+            vec![("OP_SESSION", session.token())]
+        } else {
+            vec![]
+        };
+
+        // --tags replaces the full tag set; an empty list clears it
+        let tags = op_tags(tags);
+        run_command(
+            "op",
+            &[
+                "item",
+                "edit",
+                &item_name,
+                "--tags",
+                &tags,
+                "--vault",
+                &self.vault,
+            ],
+            &env,
+        )
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("isn't an item") {
+                VaultmuxError::NotFound(name.to_string())
+            } else {
+                e
+            }
+        })?;
+
+        Ok(())
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        let account = self.get_account(session);
+
+        let env = if !account.is_empty() {
+            vec![("OP_SESSION", session.token())]
+        } else {
+            vec![]
+        };
+
+        let tag = format!("{}={}", key, value);
+        let output = run_command(
+            "op",
+            &[
+                "item",
+                "list",
+                "--vault",
+                &self.vault,
+                "--tags",
+                &tag,
+                "--format=json",
+            ],
+            &env,
+        )
+        .await?;
+
+        let mut items = self.parse_item_list(&output, None)?;
+        items.retain(|item| item.tags.get(key).map(String::as_str) == Some(value));
+        Ok(items)
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(old_name)?;
+        validate_item_name(new_name)?;
+
+        if self.item_exists(new_name, session).await? {
+            return Err(VaultmuxError::AlreadyExists(new_name.to_string()));
+        }
+
+        let old_item_name = self.item_name(old_name);
+        let new_item_name = self.item_name(new_name);
+        let account = self.get_account(session);
+
+        let env = if !account.is_empty() {
+            vec![("OP_SESSION", session.token())]
+        } else {
+            vec![]
+        };
+
+        run_command(
+            "op",
+            &[
+                "item",
+                "edit",
+                &old_item_name,
+                "--title",
+                &new_item_name,
+                "--vault",
+                &self.vault,
+            ],
+            &env,
+        )
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("isn't an item") {
+                VaultmuxError::NotFound(old_name.to_string())
+            } else {
+                e
+            }
+        })?;
+
+        Ok(())
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+        validate_location_name(location)?;
+
+        let item_name = self.item_name(name);
+        let account = self.get_account(session);
+
+        let env = if !account.is_empty() {
+            vec![("OP_SESSION", session.token())]
+        } else {
+            vec![]
+        };
+
+        run_command(
+            "op",
+            &[
+                "item",
+                "move",
+                &item_name,
+                "--current-vault",
+                &self.vault,
+                "--destination-vault",
+                location,
+            ],
+            &env,
+        )
+        .await
+        .map_err(|e| {
+            let msg = e.to_string();
+            if msg.contains("isn't an item") {
+                VaultmuxError::NotFound(name.to_string())
+            } else if msg.contains("isn't a vault") {
+                VaultmuxError::NotFound(location.to_string())
+            } else {
+                e
+            }
+        })?;
+
+        Ok(())
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        if options.force {
+            return self.delete_item(name, session).await;
+        }
+
+        validate_item_name(name)?;
+
+        let item_name = self.item_name(name);
+        let account = self.get_account(session);
+
+        let env = if !account.is_empty() {
+            vec![("OP_SESSION", session.token())]
+        } else {
+            vec![]
+        };
+
+        // Archived items are kept until removed by hand
+        run_command(
+            "op",
+            &[
+                "item",
+                "delete",
+                &item_name,
+                "--vault",
+                &self.vault,
+                "--archive",
+            ],
+            &env,
+        )
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("isn't an item") {
+                VaultmuxError::NotFound(name.to_string())
+            } else {
+                e
+            }
+        })?;
+
+        Ok(())
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        let account = self.get_account(session);
+
+        let env = if !account.is_empty() {
+            vec![("OP_SESSION", session.token())]
+        } else {
+            vec![]
+        };
+
+        let output = run_command(
+            "op",
+            &[
+                "item",
+                "list",
+                "--vault",
+                &self.vault,
+                "--include-archive",
+                "--format=json",
+            ],
+            &env,
+        )
+        .await?;
+
+        let op_items: Vec<OpItem> = serde_json::from_str(&output)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse items: {}", e)))?;
+        let archived: HashSet<String> = op_items
+            .into_iter()
+            .filter(|op_item| op_item.state.as_deref() == Some("ARCHIVED"))
+            .map(|op_item| op_item.id)
+            .collect();
+
+        // op doesn't report when an item was archived
+        Ok(self
+            .parse_item_list(&output, None)?
+            .into_iter()
+            .filter(|item| archived.contains(&item.id))
+            .map(|item| DeletedItem {
+                item,
+                deleted: None,
+                purge_after: None,
+            })
+            .collect())
+    }
+
+    async fn restore_item(&mut self, _name: &str, _session: &dyn Session) -> Result<()> {
+        Err(VaultmuxError::NotSupported(
+            "1Password CLI cannot unarchive items; restore them in the 1Password app".to_string(),
+        ))
+    }
+
     async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
         let account = self.get_account(session);
 
@@ -524,38 +1024,7 @@ impl Backend for OnePasswordBackend {
         )
         .await?;
 
-        let op_items: Vec<OpItem> = serde_json::from_str(&output)
-            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("Failed to parse items: {}", e)))?;
-
-        let mut items = Vec::new();
-        for op_item in op_items {
-            if let Some(name) = self.strip_prefix(&op_item.title) {
-                let created = op_item.created_at.as_ref().and_then(|s| {
-                    chrono::DateTime::parse_from_rfc3339(s)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                });
-
-                let modified = op_item.updated_at.as_ref().and_then(|s| {
-                    chrono::DateTime::parse_from_rfc3339(s)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                });
-
-                items.push(Item {
-                    id: op_item.id,
-                    name: name.to_string(),
-                    item_type: ItemType::SecureNote,
-                    notes: None,
-                    fields: None,
-                    location: Some(loc_value.to_string()),
-                    created,
-                    modified,
-                });
-            }
-        }
-
-        Ok(items)
+        self.parse_item_list(&output, Some(loc_value))
     }
 }
 
@@ -579,6 +1048,66 @@ mod tests {
         assert_eq!(backend.item_name("api-key"), "api-key");
     }
 
+    #[test]
+    fn test_login_template() {
+        let config = Config::new(crate::BackendType::OnePassword).with_option("prefix", "test-");
+        let backend = OnePasswordBackend::new(config);
+
+        let mut item = Item::new_login("github", "octocat".to_string(), "pw".to_string());
+        item.notes = Some("2FA on phone".to_string());
+
+        let template = backend.item_template(&item);
+        assert_eq!(template["title"], "test-github");
+        assert_eq!(template["category"], "LOGIN");
+
+        let fields = template["fields"].as_array().unwrap();
+        assert_eq!(fields[0]["id"], "password");
+        assert_eq!(fields[0]["type"], "CONCEALED");
+        assert_eq!(fields[0]["purpose"], "PASSWORD");
+        assert_eq!(fields[1]["id"], "username");
+        assert_eq!(fields[1]["purpose"], "USERNAME");
+        assert_eq!(fields[2]["id"], "notesPlain");
+        assert_eq!(fields[2]["value"], "2FA on phone");
+    }
+
+    #[test]
+    fn test_item_fields_from_op_item() {
+        let op_item: OpItem = serde_json::from_value(serde_json::json!({
+            "id": "abc",
+            "title": "test-github",
+            "category": "LOGIN",
+            "fields": [
+                { "id": "username", "type": "STRING", "label": "username", "value": "octocat" },
+                { "id": "password", "type": "CONCEALED", "label": "password", "value": "pw" },
+                { "id": "notesPlain", "type": "STRING", "label": "notesPlain", "value": "n" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            item_type_from_category(op_item.category.as_deref()),
+            ItemType::Login
+        );
+
+        let fields = op_item.item_fields().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields.get("username").unwrap(), "octocat");
+        assert_eq!(fields.get("password").unwrap(), "pw");
+    }
+
+    #[test]
+    fn test_item_tags() {
+        let op_item: OpItem = serde_json::from_str(
+            r#"{"id": "abc", "title": "db", "tags": ["team=payments", "env=prod", "legacy"]}"#,
+        )
+        .unwrap();
+
+        let tags = op_item.item_tags();
+        assert_eq!(tags.get("team").map(String::as_str), Some("payments"));
+        assert_eq!(tags.get("legacy").map(String::as_str), Some(""));
+        assert_eq!(op_tags(&tags), "env=prod,legacy,team=payments");
+    }
+
     #[test]
     fn test_strip_prefix() {
         let config = Config::new(crate::BackendType::OnePassword).with_option("prefix", "test-");
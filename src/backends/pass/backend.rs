@@ -2,12 +2,27 @@
 
 use crate::backends::pass::PassSession;
 use crate::cli::{check_command_exists, run_command, StatusCache};
+use crate::encoding::decode_value;
 use crate::validation::{validate_item_name, validate_location_name};
-use crate::{Backend, Config, Item, ItemType, Result, Session, VaultmuxError};
+use crate::{
+    Backend, Capabilities, Config, Item, ItemType, ItemVersion, Result, Session, VaultmuxError,
+    VersionState,
+};
 use async_trait::async_trait;
+use base64::Engine;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+/// Header line marking a base64-encoded binary entry.
+///
+/// pass entries are multi-line text, so binary values are stored as a
+/// header followed by base64 wrapped at 76 columns (like PEM bodies).
+const BINARY_HEADER: &str = "Content-Transfer-Encoding: base64";
+
+/// Line width for base64 bodies of binary entries.
+const BINARY_LINE_WIDTH: usize = 76;
+
 /// pass (Unix password manager) backend.
 ///
 /// Integrates with the `pass` command-line tool for GPG-encrypted password storage.
@@ -47,6 +62,67 @@ impl PassBackend {
         }
     }
 
+    /// Encodes binary data as a pass entry with an encoding header.
+    fn encode_binary_entry(data: &[u8]) -> String {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+        let mut entry = String::from(BINARY_HEADER);
+        for chunk in encoded.as_bytes().chunks(BINARY_LINE_WIDTH) {
+            entry.push('\n');
+            // base64 output is ASCII, so chunking by bytes is safe
+            entry.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        }
+        entry.push('\n');
+        entry
+    }
+
+    /// Decodes a pass entry, honouring the binary encoding header.
+    ///
+    /// Entries without the header are returned as their UTF-8 bytes (or
+    /// decoded via [`decode_value`] if they carry the generic binary marker).
+    fn decode_entry(content: &str) -> Result<Vec<u8>> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(BINARY_HEADER) {
+            return decode_value(content);
+        }
+
+        let body: String = lines.map(str::trim).collect();
+        base64::engine::general_purpose::STANDARD
+            .decode(body)
+            .map_err(|e| {
+                VaultmuxError::Other(anyhow::anyhow!("Invalid base64 in pass entry: {}", e))
+            })
+    }
+
+    /// Parses `git log` output (`%H<TAB>%aI` per line) into versions.
+    ///
+    /// Git lists commits newest first, so the first entry is current.
+    fn parse_git_log(output: &str) -> Vec<ItemVersion> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.trim().splitn(2, '\t');
+                let id = parts.next().filter(|id| !id.is_empty())?;
+                let created = parts
+                    .next()
+                    .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+                    .map(|date| date.with_timezone(&chrono::Utc));
+
+                Some(ItemVersion {
+                    id: id.to_string(),
+                    created,
+                    stages: Vec::new(),
+                    current: false,
+                    state: VersionState::Enabled,
+                })
+            })
+            .enumerate()
+            .map(|(i, mut version)| {
+                version.current = i == 0;
+                version
+            })
+            .collect()
+    }
+
     /// Lists all items in the password store (recursively).
     async fn list_all_items(&self) -> Result<Vec<String>> {
         let output = run_command("pass", &["ls"], &[]).await?;
@@ -95,6 +171,15 @@ impl Backend for PassBackend {
         "pass"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            native_rename: true,
+            // History comes from git, so it needs a git-backed store
+            versioning: true,
+            ..Capabilities::default()
+        }
+    }
+
     async fn init(&mut self) -> Result<()> {
         // Check if pass command exists
         if !check_command_exists("pass").await? {
@@ -170,12 +255,13 @@ impl Backend for PassBackend {
                 }
             })?;
 
-        Ok(Item {
+        let mut item = Item {
             id: name.to_string(),
             name: name.to_string(),
             item_type: ItemType::SecureNote,
-            notes: Some(content.trim().to_string()),
+            notes: None,
             fields: None,
+            tags: HashMap::new(),
             location: if self.prefix.is_empty() {
                 None
             } else {
@@ -183,24 +269,19 @@ impl Backend for PassBackend {
             },
             created: None,
             modified: None,
-        })
-    }
+            revision: None,
+        };
 
-    async fn get_notes(&self, name: &str, _session: &dyn Session) -> Result<String> {
-        validate_item_name(name)?;
+        // Restores type and fields of items written with create_item_full
+        item.decode_content(content.trim().to_string());
 
-        let path = self.item_path(name);
-        let content = run_command("pass", &["show", &path], &[])
-            .await
-            .map_err(|e| {
-                if e.to_string().contains("not in the password store") {
-                    VaultmuxError::NotFound(name.to_string())
-                } else {
-                    e
-                }
-            })?;
+        Ok(item)
+    }
 
-        Ok(content.trim().to_string())
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        let item = self.get_item(name, session).await?;
+        item.notes
+            .ok_or_else(|| VaultmuxError::NotFound(format!("{} has no notes", name)))
     }
 
     async fn item_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
@@ -226,6 +307,7 @@ impl Backend for PassBackend {
                 item_type: ItemType::SecureNote,
                 notes: None,
                 fields: None,
+                tags: HashMap::new(),
                 location: if self.prefix.is_empty() {
                     None
                 } else {
@@ -233,6 +315,7 @@ impl Backend for PassBackend {
                 },
                 created: None,
                 modified: None,
+                revision: None,
             });
         }
 
@@ -301,6 +384,114 @@ impl Backend for PassBackend {
         Ok(())
     }
 
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        let content = self.get_notes(name, session).await?;
+        Self::decode_entry(&content)
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], _session: &dyn Session) -> Result<()> {
+        validate_item_name(name)?;
+
+        let path = self.item_path(name);
+        let entry = Self::encode_binary_entry(data);
+
+        // insert -f creates or overwrites in one step
+        crate::cli::run_command_with_stdin("pass", &["insert", "-m", "-f", &path], &[], &entry)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lists versions from the store's git history.
+    ///
+    /// Requires a git-backed store (`pass git init`); each commit that
+    /// added or changed the entry is one version.
+    async fn list_versions(&self, name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        validate_item_name(name)?;
+
+        let file = format!("{}.gpg", self.item_path(name));
+        let output = run_command(
+            "pass",
+            &[
+                "git",
+                "log",
+                "--diff-filter=AM",
+                "--format=%H%x09%aI",
+                "--",
+                &file,
+            ],
+            &[],
+        )
+        .await?;
+
+        let versions = Self::parse_git_log(&output);
+        if versions.is_empty() {
+            return Err(VaultmuxError::NotFound(name.to_string()));
+        }
+
+        Ok(versions)
+    }
+
+    /// Reads an entry as of a git revision.
+    ///
+    /// Relies on the `gpg` textconv driver that `pass git init` configures.
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<String> {
+        validate_item_name(name)?;
+
+        let object = format!("{}:{}.gpg", version_id, self.item_path(name));
+        let content = run_command("pass", &["git", "show", "--textconv", &object], &[])
+            .await
+            .map_err(|e| {
+                let msg = e.to_string();
+                if msg.contains("does not exist") || msg.contains("invalid object name") {
+                    VaultmuxError::NotFound(format!("{} version {}", name, version_id))
+                } else {
+                    e
+                }
+            })?;
+
+        Ok(content.trim().to_string())
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        validate_item_name(old_name)?;
+        validate_item_name(new_name)?;
+
+        if !self.item_exists(old_name, session).await? {
+            return Err(VaultmuxError::NotFound(old_name.to_string()));
+        }
+        if self.item_exists(new_name, session).await? {
+            return Err(VaultmuxError::AlreadyExists(new_name.to_string()));
+        }
+
+        let old_path = self.item_path(old_name);
+        let new_path = self.item_path(new_name);
+
+        // pass mv moves the file (and commits the move in git-backed stores)
+        run_command("pass", &["mv", &old_path, &new_path], &[]).await?;
+
+        Ok(())
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        validate_location_name(location)?;
+
+        let leaf = name.rsplit('/').next().unwrap_or(name);
+        let new_name = format!("{}/{}", location.trim_end_matches('/'), leaf);
+
+        self.rename_item(name, &new_name, session).await
+    }
+
     async fn list_locations(&self, _session: &dyn Session) -> Result<Vec<String>> {
         // pass uses directory structure
         // For now, return error - full directory enumeration would be complex
@@ -351,6 +542,39 @@ mod tests {
         assert_eq!(backend.item_path("api-key"), "myapp/api-key");
     }
 
+    #[test]
+    fn test_binary_entry_roundtrip() {
+        let data: Vec<u8> = (0..=255).cycle().take(300).collect();
+        let entry = PassBackend::encode_binary_entry(&data);
+
+        assert!(entry.starts_with(BINARY_HEADER));
+        assert!(entry.lines().all(|l| l.len() <= BINARY_LINE_WIDTH));
+        assert_eq!(PassBackend::decode_entry(entry.trim()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_plain_entry() {
+        assert_eq!(
+            PassBackend::decode_entry("hunter2").unwrap(),
+            b"hunter2".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_parse_git_log() {
+        let output = "abc123\t2024-03-02T10:00:00+01:00\ndef456\t2024-03-01T09:00:00+00:00\n";
+        let versions = PassBackend::parse_git_log(output);
+
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].id, "abc123");
+        assert!(versions[0].current);
+        assert!(!versions[1].current);
+        assert_eq!(
+            versions[0].created.unwrap().to_rfc3339(),
+            "2024-03-02T09:00:00+00:00"
+        );
+    }
+
     #[test]
     fn test_item_path_no_prefix() {
         let config = Config::new(crate::BackendType::Pass).with_prefix("");
@@ -2,13 +2,17 @@
 
 use crate::backends::wincred::WincredSession;
 use crate::validation::validate_item_name;
-use crate::{Backend, Config, Item, ItemType, Result, Session, VaultmuxError};
+use crate::{Backend, Capabilities, Config, Item, ItemType, Result, Session, VaultmuxError};
 use async_trait::async_trait;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::process::Stdio;
 use std::sync::Arc;
 use tokio::process::Command;
 
+/// Maximum size of a credential blob (`CRED_MAX_CREDENTIAL_BLOB_SIZE`).
+const MAX_CREDENTIAL_BLOB_SIZE: usize = 2560;
+
 /// Windows Credential Manager backend.
 ///
 /// Uses PowerShell to interact with the Windows Credential Manager.
@@ -83,6 +87,13 @@ impl Backend for WincredBackend {
         "wincred"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            max_value_size: Some(MAX_CREDENTIAL_BLOB_SIZE),
+            ..Capabilities::default()
+        }
+    }
+
     async fn init(&mut self) -> Result<()> {
         let script = "$PSVersionTable.PSVersion.Major";
         match self.run_powershell(script).await {
@@ -112,18 +123,25 @@ impl Backend for WincredBackend {
     async fn get_item(&self, name: &str, _session: &dyn Session) -> Result<Item> {
         validate_item_name(name)?;
 
-        let notes = self.get_notes(name, _session).await?;
+        let content = self.get_notes(name, _session).await?;
 
-        Ok(Item {
+        let mut item = Item {
             id: self.credential_target(name),
             name: name.to_string(),
             item_type: ItemType::SecureNote,
-            notes: Some(notes),
+            notes: None,
             fields: None,
+            tags: HashMap::new(),
             location: None,
             created: None,
             modified: None,
-        })
+            revision: None,
+        };
+
+        // Restores type and fields of items written with create_item_full
+        item.decode_content(content);
+
+        Ok(item)
     }
 
     async fn get_notes(&self, name: &str, _session: &dyn Session) -> Result<String> {
@@ -206,9 +224,11 @@ $creds | ForEach-Object {{
                     item_type: ItemType::SecureNote,
                     notes: None,
                     fields: None,
+                    tags: HashMap::new(),
                     location: None,
                     created: None,
                     modified: None,
+                    revision: None,
                 })
                 .collect()
         } else {
@@ -221,9 +241,11 @@ $creds | ForEach-Object {{
                 item_type: ItemType::SecureNote,
                 notes: None,
                 fields: None,
+                tags: HashMap::new(),
                 location: None,
                 created: None,
                 modified: None,
+                revision: None,
             }]
         };
 
@@ -0,0 +1,241 @@
+//! Backend capability descriptors.
+//!
+//! [`Backend::capabilities`](crate::Backend::capabilities) reports which
+//! optional operations a backend supports, so generic tooling can adapt
+//! before calling instead of handling
+//! [`VaultmuxError::NotSupported`](crate::VaultmuxError::NotSupported) at runtime.
+
+use crate::validation::validate_item_name;
+use serde::Serialize;
+
+/// Maximum name length enforced by vaultmux itself.
+const DEFAULT_MAX_NAME_LENGTH: usize = 255;
+
+/// Optional features supported by a backend.
+///
+/// The default describes a backend with none of the optional features.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::{factory, BackendType, Config};
+///
+/// let backend = factory::new_backend(Config::new(BackendType::Bitwarden)).unwrap();
+/// let caps = backend.capabilities();
+///
+/// if !caps.create_locations {
+///     eprintln!("{} cannot create folders; create them by hand", backend.name());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Capabilities {
+    /// Items can be listed and stored by location (folder, vault, directory)
+    pub locations: bool,
+
+    /// New locations can be created with `create_location`
+    pub create_locations: bool,
+
+    /// `rename_item` renames in place (otherwise it copies and deletes,
+    /// losing version history)
+    pub native_rename: bool,
+
+    /// Version history can be listed and read with `list_versions`/`get_version`
+    pub versioning: bool,
+
+    /// Individual versions can be disabled with `disable_version`
+    pub disable_versions: bool,
+
+    /// Individual versions can be destroyed with `destroy_version`
+    pub destroy_versions: bool,
+
+    /// Binary values are stored as raw bytes rather than base64 text
+    pub native_binary: bool,
+
+    /// Tags can be written with `set_tags`
+    pub tags: bool,
+
+    /// Tags written by `set_tags` can be read back
+    pub read_tags: bool,
+
+    /// `delete_item_with` can keep items recoverable, and they can be
+    /// listed with `list_deleted_items` and brought back with `restore_item`
+    pub soft_delete: bool,
+
+    /// Maximum size of a stored value in bytes, if the backend has a limit
+    pub max_value_size: Option<usize>,
+
+    /// How `update_item_if` detects concurrent changes
+    pub compare_and_swap: CompareAndSwap,
+
+    /// Characters and length allowed in item names
+    pub name_charset: NameCharset,
+
+    /// `sync` pulls remote changes (it is a no-op otherwise)
+    pub sync: bool,
+}
+
+impl Capabilities {
+    /// Returns the features supported by both `self` and `other`.
+    ///
+    /// Used by backends that combine several inner backends, where an
+    /// operation may reach any of them.
+    pub fn intersection(self, other: Capabilities) -> Capabilities {
+        Capabilities {
+            locations: self.locations && other.locations,
+            create_locations: self.create_locations && other.create_locations,
+            native_rename: self.native_rename && other.native_rename,
+            versioning: self.versioning && other.versioning,
+            disable_versions: self.disable_versions && other.disable_versions,
+            destroy_versions: self.destroy_versions && other.destroy_versions,
+            native_binary: self.native_binary && other.native_binary,
+            tags: self.tags && other.tags,
+            read_tags: self.read_tags && other.read_tags,
+            soft_delete: self.soft_delete && other.soft_delete,
+            max_value_size: match (self.max_value_size, other.max_value_size) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+            compare_and_swap: self.compare_and_swap.min(other.compare_and_swap),
+            name_charset: self.name_charset.intersection(other.name_charset),
+            sync: self.sync && other.sync,
+        }
+    }
+}
+
+/// How a backend enforces [`update_item_if`](crate::Backend::update_item_if).
+///
+/// Variants are ordered from weakest to strongest guarantee.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum CompareAndSwap {
+    /// Items carry no revision; `update_item_if` is not supported
+    #[default]
+    Unsupported,
+
+    /// The revision is compared before writing; a write landing between
+    /// the read and the write can still be overwritten
+    BestEffort,
+
+    /// The backend rejects the write itself if the revision changed
+    Atomic,
+}
+
+/// Item names accepted by a backend.
+///
+/// Limits apply to the full stored name, including any configured prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NameCharset {
+    /// Characters allowed besides ASCII letters and digits, or `None` if
+    /// any name that passes [`validate_item_name`] is allowed
+    pub extra_chars: Option<&'static str>,
+
+    /// Maximum name length in bytes
+    pub max_length: usize,
+}
+
+impl NameCharset {
+    /// Returns `true` if `name` is valid for vaultmux and for the backend.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vaultmux::NameCharset;
+    ///
+    /// let azure = NameCharset { extra_chars: Some("-"), max_length: 127 };
+    /// assert!(azure.allows("db-password"));
+    /// assert!(!azure.allows("db/password"));
+    /// ```
+    pub fn allows(&self, name: &str) -> bool {
+        validate_item_name(name).is_ok()
+            && name.len() <= self.max_length
+            && self.extra_chars.is_none_or(|extra| {
+                name.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || extra.contains(c))
+            })
+    }
+
+    /// Returns a charset allowing only names both charsets allow.
+    ///
+    /// When neither set of extra characters contains the other, only ASCII
+    /// letters and digits are kept.
+    pub fn intersection(self, other: NameCharset) -> NameCharset {
+        let extra_chars = match (self.extra_chars, other.extra_chars) {
+            (Some(a), Some(b)) if a.chars().all(|c| b.contains(c)) => Some(a),
+            (Some(a), Some(b)) if b.chars().all(|c| a.contains(c)) => Some(b),
+            (Some(_), Some(_)) => Some(""),
+            (a, b) => a.or(b),
+        };
+
+        NameCharset {
+            extra_chars,
+            max_length: self.max_length.min(other.max_length),
+        }
+    }
+}
+
+impl Default for NameCharset {
+    fn default() -> Self {
+        Self {
+            extra_chars: None,
+            max_length: DEFAULT_MAX_NAME_LENGTH,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_charset_follows_validation() {
+        let charset = NameCharset::default();
+
+        assert!(charset.allows("prod/db.password"));
+        assert!(!charset.allows("name; rm -rf /"));
+        assert!(!charset.allows(&"a".repeat(256)));
+    }
+
+    #[test]
+    fn test_restricted_charset() {
+        let charset = NameCharset {
+            extra_chars: Some("_-"),
+            max_length: 8,
+        };
+
+        assert!(charset.allows("api_key"));
+        assert!(!charset.allows("api.key"));
+        assert!(!charset.allows("api_key_2"));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let aws = Capabilities {
+            versioning: true,
+            tags: true,
+            max_value_size: Some(65536),
+            compare_and_swap: CompareAndSwap::Atomic,
+            name_charset: NameCharset {
+                extra_chars: Some("/_+=.@-"),
+                max_length: 512,
+            },
+            ..Capabilities::default()
+        };
+        let azure = Capabilities {
+            versioning: true,
+            max_value_size: Some(25600),
+            compare_and_swap: CompareAndSwap::BestEffort,
+            name_charset: NameCharset {
+                extra_chars: Some("-"),
+                max_length: 127,
+            },
+            ..Capabilities::default()
+        };
+
+        let both = aws.intersection(azure);
+        assert!(both.versioning);
+        assert!(!both.tags);
+        assert_eq!(both.max_value_size, Some(25600));
+        assert_eq!(both.compare_and_swap, CompareAndSwap::BestEffort);
+        assert_eq!(both.name_charset.extra_chars, Some("-"));
+        assert_eq!(both.name_charset.max_length, 127);
+    }
+}
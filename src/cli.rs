@@ -3,16 +3,64 @@
 //! This module provides shared infrastructure for backends that integrate
 //! via command-line tools (Bitwarden, 1Password, pass).
 
+use crate::middleware::timeout::bounded;
+use crate::telemetry::{in_command_span, record_exit_code};
 use crate::{Result, VaultmuxError};
-use std::process::Stdio;
+use std::process::{Output, Stdio};
 use std::time::{Duration, Instant};
 use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+/// Limits applied to a command run by [`run_command_with_options`] or
+/// [`run_command_with_stdin_options`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use tokio_util::sync::CancellationToken;
+/// use vaultmux::cli::CommandOptions;
+///
+/// let cancel = CancellationToken::new();
+/// let options = CommandOptions::new()
+///     .with_timeout(Duration::from_secs(30))
+///     .with_cancellation(cancel.clone());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CommandOptions {
+    /// Time after which the command is killed
+    pub timeout: Option<Duration>,
+    /// Token that kills the command when cancelled
+    pub cancel: Option<CancellationToken>,
+}
+
+impl CommandOptions {
+    /// Creates options with no timeout and no cancellation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Kills the command if it runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Kills the command when `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+}
 
 /// Executes a command and returns stdout as a string.
 ///
 /// This is the primary way CLI backends should execute commands.
 ///
+/// The child process is killed if the returned future is dropped before it
+/// finishes.
+///
 /// # Arguments
 ///
 /// - `program`: Command to execute (e.g., "bw", "op", "pass")
@@ -26,75 +74,138 @@ use tokio::process::Command;
 /// - Exit code is non-zero
 /// - Output is not valid UTF-8
 pub async fn run_command(program: &str, args: &[&str], env: &[(&str, &str)]) -> Result<String> {
-    let mut cmd = Command::new(program);
-    cmd.args(args);
+    run_command_with_options(program, args, env, &CommandOptions::default()).await
+}
+
+/// Executes a command with a timeout or cancellation token.
+///
+/// Behaves like [`run_command`], but kills the child process when the
+/// timeout fires or the token is cancelled.
+///
+/// # Errors
+///
+/// In addition to the errors from [`run_command`], returns
+/// [`VaultmuxError::Timeout`] or [`VaultmuxError::Cancelled`].
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use vaultmux::cli::{run_command_with_options, CommandOptions};
+///
+/// #[tokio::main]
+/// async fn main() -> vaultmux::Result<()> {
+///     let options = CommandOptions::new().with_timeout(Duration::from_secs(30));
+///     let status = run_command_with_options("bw", &["status"], &[], &options).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_command_with_options(
+    program: &str,
+    args: &[&str],
+    env: &[(&str, &str)],
+    options: &CommandOptions,
+) -> Result<String> {
+    let mut cmd = command(program, args, env);
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
-    for (key, value) in env {
-        cmd.env(key, value);
-    }
-
-    let output = cmd.output().await.map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            VaultmuxError::BackendNotInstalled(format!("{} command not found", program))
-        } else {
-            VaultmuxError::Io(e)
-        }
-    })?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(VaultmuxError::CommandFailed(format!(
-            "{} failed with exit code {}: {}",
+    in_command_span(program, args, async {
+        let output = bounded(
+            cmd.output(),
+            options.timeout,
+            options.cancel.as_ref(),
             program,
-            output.status.code().unwrap_or(-1),
-            stderr
-        )));
-    }
+        )
+        .await?
+        .map_err(|e| spawn_error(program, e))?;
 
-    String::from_utf8(output.stdout).map_err(|e| {
-        VaultmuxError::Other(anyhow::anyhow!("Invalid UTF-8 in command output: {}", e))
+        command_output(program, output)
     })
+    .await
 }
 
 /// Executes a command with stdin input.
 ///
-/// Used for interactive operations like authentication.
+/// Used for interactive operations like authentication, and to keep secret
+/// values out of argv. As with [`run_command`], the child process is killed
+/// if the future is dropped.
 pub async fn run_command_with_stdin(
     program: &str,
     args: &[&str],
     env: &[(&str, &str)],
     stdin_data: &str,
 ) -> Result<String> {
-    let mut cmd = Command::new(program);
-    cmd.args(args);
+    run_command_with_stdin_options(program, args, env, stdin_data, &CommandOptions::default()).await
+}
+
+/// Executes a command with stdin input and a timeout or cancellation token.
+///
+/// Behaves like [`run_command_with_stdin`], but kills the child process
+/// when the timeout fires or the token is cancelled, including while it
+/// is still reading stdin.
+///
+/// # Errors
+///
+/// In addition to the errors from [`run_command`], returns
+/// [`VaultmuxError::Timeout`] or [`VaultmuxError::Cancelled`].
+pub async fn run_command_with_stdin_options(
+    program: &str,
+    args: &[&str],
+    env: &[(&str, &str)],
+    stdin_data: &str,
+    options: &CommandOptions,
+) -> Result<String> {
+    let mut cmd = command(program, args, env);
     cmd.stdin(Stdio::piped());
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
+    let run = async {
+        let mut child = cmd.spawn().map_err(|e| spawn_error(program, e))?;
+
+        // Write to stdin
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(stdin_data.as_bytes())
+                .await
+                .map_err(VaultmuxError::Io)?;
+            stdin.flush().await.map_err(VaultmuxError::Io)?;
+        }
+
+        let output = child.wait_with_output().await.map_err(VaultmuxError::Io)?;
+        command_output(program, output)
+    };
+
+    in_command_span(program, args, async {
+        bounded(run, options.timeout, options.cancel.as_ref(), program).await?
+    })
+    .await
+}
+
+/// Builds a command that is killed when its handle is dropped.
+fn command(program: &str, args: &[&str], env: &[(&str, &str)]) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    cmd.kill_on_drop(true);
+
     for (key, value) in env {
         cmd.env(key, value);
     }
+    cmd
+}
 
-    let mut child = cmd.spawn().map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            VaultmuxError::BackendNotInstalled(format!("{} command not found", program))
-        } else {
-            VaultmuxError::Io(e)
-        }
-    })?;
-
-    // Write to stdin
-    if let Some(mut stdin) = child.stdin.take() {
-        stdin
-            .write_all(stdin_data.as_bytes())
-            .await
-            .map_err(VaultmuxError::Io)?;
-        stdin.flush().await.map_err(VaultmuxError::Io)?;
+fn spawn_error(program: &str, e: std::io::Error) -> VaultmuxError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        VaultmuxError::BackendNotInstalled(format!("{} command not found", program))
+    } else {
+        VaultmuxError::Io(e)
     }
+}
 
-    let output = child.wait_with_output().await.map_err(VaultmuxError::Io)?;
+/// Returns stdout of a finished command, or its stderr as an error.
+fn command_output(program: &str, output: Output) -> Result<String> {
+    record_exit_code(output.status.code());
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -131,6 +242,7 @@ pub async fn check_command_exists(program: &str) -> Result<bool> {
         .arg(program)
         .stdout(Stdio::null())
         .stderr(Stdio::null())
+        .kill_on_drop(true)
         .status()
         .await
         .map_err(VaultmuxError::Io)?;
@@ -250,6 +362,42 @@ mod tests {
         assert_eq!(output.trim(), "hello from stdin");
     }
 
+    #[tokio::test]
+    async fn test_run_command_with_stdin_timeout() {
+        let options = CommandOptions::new().with_timeout(Duration::from_millis(100));
+        let started = Instant::now();
+        let result =
+            run_command_with_stdin_options("sh", &["-c", "cat; sleep 5"], &[], "input", &options)
+                .await;
+
+        assert!(matches!(result, Err(VaultmuxError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_run_command_timeout_kills_child() {
+        let options = CommandOptions::new().with_timeout(Duration::from_millis(100));
+        let started = Instant::now();
+        let result = run_command_with_options("sleep", &["5"], &[], &options).await;
+
+        assert!(matches!(result, Err(VaultmuxError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_run_command_cancellation() {
+        let cancel = CancellationToken::new();
+        let options = CommandOptions::new().with_cancellation(cancel.clone());
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            canceller.cancel();
+        });
+
+        let result = run_command_with_options("sleep", &["5"], &[], &options).await;
+        assert!(matches!(result, Err(VaultmuxError::Cancelled)));
+    }
+
     #[tokio::test]
     async fn test_check_command_exists() {
         assert!(check_command_exists("echo").await.unwrap());
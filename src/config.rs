@@ -2,6 +2,7 @@
 
 use std::collections::HashMap;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
 /// Backend type identifier.
 ///
@@ -70,6 +71,12 @@ pub struct Config {
 
     /// Backend-specific options
     pub options: HashMap<String, String>,
+
+    /// Time limit for each operation (default: none)
+    pub timeout: Option<Duration>,
+
+    /// Token that cancels in-flight and future operations (default: none)
+    pub cancellation: Option<CancellationToken>,
 }
 
 impl Default for Config {
@@ -81,6 +88,8 @@ impl Default for Config {
             session_file: None,
             session_ttl: Duration::from_secs(1800), // 30 minutes
             options: HashMap::new(),
+            timeout: None,
+            cancellation: None,
         }
     }
 }
@@ -137,6 +146,27 @@ impl Config {
         self
     }
 
+    /// Sets a time limit for each backend operation.
+    ///
+    /// An operation that runs longer fails with
+    /// [`VaultmuxError::Timeout`](crate::VaultmuxError::Timeout), and any
+    /// CLI process it started is killed. This bounds calls such as a hung
+    /// `bw unlock` as well as stalled SDK requests.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a token that cancels backend operations.
+    ///
+    /// Once `token` is cancelled, in-flight and later operations fail with
+    /// [`VaultmuxError::Cancelled`](crate::VaultmuxError::Cancelled), and
+    /// any CLI processes they started are killed.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
     /// Adds a backend-specific option.
     ///
     /// Common options:
@@ -153,6 +183,12 @@ impl Config {
     /// **Azure Key Vault:**
     /// - `vault_url`: Key Vault URL (e.g., "<https://myvault.vault.azure.net/>")
     /// - `prefix`: Secret name prefix (e.g., "myapp-")
+    ///
+    /// **Rate limiting** (applied by [`factory::new_backend`](crate::factory::new_backend);
+    /// see [`RateLimiter::from_config`](crate::middleware::RateLimiter::from_config)):
+    /// - `read_rate_limit` / `write_rate_limit`: Requests per second
+    /// - `read_burst` / `write_burst`: Requests allowed at once after an idle period
+    /// - `read_max_in_flight` / `write_max_in_flight`: Requests running at the same time
     pub fn with_option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.options.insert(key.into(), value.into());
         self
@@ -173,12 +209,14 @@ mod tests {
         let config = Config::new(BackendType::AWSSecretsManager)
             .with_prefix("myapp")
             .with_option("region", "us-west-2")
-            .with_session_ttl(Duration::from_secs(3600));
+            .with_session_ttl(Duration::from_secs(3600))
+            .with_timeout(Duration::from_secs(30));
 
         assert_eq!(config.backend, BackendType::AWSSecretsManager);
         assert_eq!(config.prefix, "myapp");
         assert_eq!(config.get_option("region"), Some(&"us-west-2".to_string()));
         assert_eq!(config.session_ttl, Duration::from_secs(3600));
+        assert_eq!(config.timeout, Some(Duration::from_secs(30)));
     }
 
     #[test]
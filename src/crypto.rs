@@ -0,0 +1,108 @@
+//! Authenticated encryption helpers.
+//!
+//! Values are sealed with ChaCha20-Poly1305, which detects any change to the
+//! ciphertext or its associated data. Keys derived from passphrases use
+//! Argon2id.
+
+use crate::{Result, VaultmuxError};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+/// Length of an encryption key in bytes.
+pub(crate) const KEY_LEN: usize = 32;
+
+/// Length of a passphrase salt in bytes.
+pub(crate) const SALT_LEN: usize = 16;
+
+/// Length of a nonce in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Bytes [`seal`] adds to a plaintext: the nonce and the authentication tag.
+pub(crate) const SEAL_OVERHEAD: usize = NONCE_LEN + 16;
+
+/// Returns `N` bytes from the operating system's random number generator.
+pub(crate) fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Derives a key from a passphrase and salt with Argon2id.
+pub(crate) fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; KEY_LEN]> {
+    let mut key = [0u8; KEY_LEN];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+/// Encrypts `plaintext`, binding it to `aad`.
+///
+/// Returns the random nonce followed by the ciphertext.
+pub(crate) fn seal(key: &[u8; KEY_LEN], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = random_bytes::<NONCE_LEN>();
+
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| VaultmuxError::Other(anyhow::anyhow!("encryption failed")))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypts a value from [`seal`], checking it and `aad` are unchanged.
+///
+/// # Errors
+///
+/// Fails if the key is wrong or the data was modified.
+pub(crate) fn open(key: &[u8; KEY_LEN], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return Err(integrity_error());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+    ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| integrity_error())
+}
+
+fn integrity_error() -> VaultmuxError {
+    VaultmuxError::Other(anyhow::anyhow!(
+        "integrity check failed: wrong key or modified data"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let key = random_bytes::<KEY_LEN>();
+        let sealed = seal(&key, b"header", b"secret").unwrap();
+
+        assert_eq!(open(&key, b"header", &sealed).unwrap(), b"secret");
+        assert!(open(&key, b"other header", &sealed).is_err());
+        assert!(open(&random_bytes::<KEY_LEN>(), b"header", &sealed).is_err());
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(open(&key, b"header", &tampered).is_err());
+    }
+}
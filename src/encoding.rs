@@ -0,0 +1,85 @@
+//! Encoding of binary values for text-only backends.
+//!
+//! Backends that only store strings (Azure Key Vault, Bitwarden, 1Password,
+//! Windows Credential Manager) hold binary values as base64 behind a
+//! content-type marker, so that [`Backend::get_bytes`](crate::Backend::get_bytes)
+//! can tell them apart from ordinary text and return the original bytes.
+
+use crate::{Result, VaultmuxError};
+use base64::Engine;
+
+/// Marker prepended to base64-encoded binary values.
+///
+/// This is a `data:` URI prefix, so the stored value stays self-describing
+/// when viewed in the vault's own UI.
+pub const BINARY_MARKER: &str = "data:application/octet-stream;base64,";
+
+/// Encodes raw bytes as a marked base64 string.
+///
+/// # Example
+///
+/// ```
+/// use vaultmux::encoding::{decode_value, encode_binary};
+///
+/// let encoded = encode_binary(&[0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(encoded, "data:application/octet-stream;base64,3q2+7w==");
+/// assert_eq!(decode_value(&encoded).unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+/// ```
+pub fn encode_binary(data: &[u8]) -> String {
+    format!(
+        "{}{}",
+        BINARY_MARKER,
+        base64::engine::general_purpose::STANDARD.encode(data)
+    )
+}
+
+/// Returns `true` if the value carries the binary marker.
+pub fn is_binary(value: &str) -> bool {
+    value.starts_with(BINARY_MARKER)
+}
+
+/// Decodes a stored value into bytes.
+///
+/// Marked values are base64-decoded; any other value is returned as its
+/// UTF-8 bytes unchanged.
+///
+/// # Errors
+///
+/// Returns an error if the value carries the marker but the payload is not
+/// valid base64.
+pub fn decode_value(value: &str) -> Result<Vec<u8>> {
+    match value.strip_prefix(BINARY_MARKER) {
+        Some(encoded) => base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| {
+                VaultmuxError::Other(anyhow::anyhow!("Invalid base64 in binary value: {}", e))
+            }),
+        None => Ok(value.as_bytes().to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let encoded = encode_binary(&data);
+
+        assert!(is_binary(&encoded));
+        assert_eq!(decode_value(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_plain_text_passthrough() {
+        assert!(!is_binary("hunter2"));
+        assert_eq!(decode_value("hunter2").unwrap(), b"hunter2".to_vec());
+    }
+
+    #[test]
+    fn test_invalid_base64() {
+        let result = decode_value("data:application/octet-stream;base64,not base64!");
+        assert!(result.is_err());
+    }
+}
@@ -42,6 +42,22 @@ pub enum VaultmuxError {
     #[error("operation not supported by backend: {0}")]
     NotSupported(String),
 
+    /// Item changed since it was read (compare-and-swap failed).
+    #[error("conflict: {0}")]
+    Conflict(String),
+
+    /// The backend rejected the request because of rate limiting.
+    #[error("throttled: {0}")]
+    Throttled(String),
+
+    /// The operation did not finish within its time limit.
+    #[error("timed out: {0}")]
+    Timeout(String),
+
+    /// The operation was cancelled by the caller.
+    #[error("operation cancelled")]
+    Cancelled,
+
     /// Item name contains invalid characters.
     #[error("invalid item name: {0}")]
     InvalidItemName(String),
@@ -60,6 +76,15 @@ pub enum VaultmuxError {
         source: Box<VaultmuxError>,
     },
 
+    /// A mirrored write reached the primary but failed on some replicas.
+    #[error("{item}: write diverged on {}", describe_failures(.failures))]
+    ReplicaDivergence {
+        /// Item name
+        item: String,
+        /// Replicas that failed, in order
+        failures: Vec<ReplicaFailure>,
+    },
+
     /// I/O error occurred.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -77,6 +102,26 @@ pub enum VaultmuxError {
     Other(#[from] anyhow::Error),
 }
 
+/// A replica that failed to apply a mirrored write.
+///
+/// Returned in [`VaultmuxError::ReplicaDivergence`].
+#[derive(Debug)]
+pub struct ReplicaFailure {
+    /// Label of the replica
+    pub replica: String,
+    /// Why the write failed
+    pub error: VaultmuxError,
+}
+
+/// Formats replica failures as `label (error), ...`.
+fn describe_failures(failures: &[ReplicaFailure]) -> String {
+    failures
+        .iter()
+        .map(|f| format!("{} ({})", f.replica, f.error))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 impl VaultmuxError {
     /// Creates a backend operation error with context.
     ///
@@ -114,6 +159,125 @@ impl VaultmuxError {
             source: Box::new(err),
         }
     }
+
+    /// Returns true if the operation may succeed when retried unchanged.
+    ///
+    /// Throttling, dropped connections, timeouts and vault lock contention
+    /// are transient. Errors that describe the request or the item itself,
+    /// such as [`NotFound`](Self::NotFound), [`AlreadyExists`](Self::AlreadyExists)
+    /// or [`PermissionDenied`](Self::PermissionDenied), are never retryable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vaultmux::VaultmuxError;
+    ///
+    /// assert!(VaultmuxError::Throttled("rate exceeded".to_string()).is_retryable());
+    /// assert!(!VaultmuxError::NotFound("api-key".to_string()).is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Throttled(_) | Self::Timeout(_) => true,
+            Self::Io(err) => is_transient_io(err),
+            Self::CommandFailed(message) => is_transient_message(message),
+            Self::Other(err) => match err.downcast_ref::<std::io::Error>() {
+                Some(io) => is_transient_io(io),
+                None => is_transient_message(&format!("{:#}", err)),
+            },
+            Self::BackendOperation { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Returns a stable, lowercase name for the kind of error.
+    ///
+    /// Unlike the `Display` output, the kind never contains item names or
+    /// backend messages, so it is safe to use as a log field or metric
+    /// label. [`BackendOperation`](Self::BackendOperation) reports the kind
+    /// of its source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vaultmux::VaultmuxError;
+    ///
+    /// let err = VaultmuxError::NotFound("prod/db-password".to_string());
+    /// assert_eq!(err.kind(), "not_found");
+    /// ```
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "not_found",
+            Self::AlreadyExists(_) => "already_exists",
+            Self::NotAuthenticated => "not_authenticated",
+            Self::SessionExpired => "session_expired",
+            Self::BackendNotInstalled(_) => "not_installed",
+            Self::BackendLocked => "locked",
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::NotSupported(_) => "not_supported",
+            Self::Conflict(_) => "conflict",
+            Self::Throttled(_) => "throttled",
+            Self::Timeout(_) => "timeout",
+            Self::Cancelled => "cancelled",
+            Self::InvalidItemName(_) => "invalid_item_name",
+            Self::BackendOperation { source, .. } => source.kind(),
+            Self::ReplicaDivergence { .. } => "replica_divergence",
+            Self::Io(_) => "io",
+            Self::Json(_) => "json",
+            Self::CommandFailed(_) => "command_failed",
+            Self::Other(_) => "other",
+        }
+    }
+}
+
+/// Lowercase fragments of error messages that indicate a transient failure.
+///
+/// Covers cloud throttling codes and network failures surfaced as text by
+/// SDKs and CLIs.
+const TRANSIENT_MARKERS: &[&str] = &[
+    "throttling",
+    "too many requests",
+    "toomanyrequests",
+    "requestlimitexceeded",
+    "rate exceeded",
+    "resource_exhausted",
+    "serviceunavailable",
+    "service unavailable",
+    "internalserviceerror",
+    "timed out",
+    "connection reset",
+    "connection refused",
+    "dispatch failure",
+];
+
+fn is_transient_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+        || is_store_busy(&message)
+}
+
+/// Whether the Bitwarden CLI failed because another `bw` process holds its
+/// `data.json` store, e.g. `EBUSY: resource busy or locked, open 'data.json'`.
+fn is_store_busy(message: &str) -> bool {
+    message.contains("data.json")
+        && (message.contains("ebusy") || message.contains("resource busy"))
+}
+
+fn is_transient_io(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    matches!(
+        err.kind(),
+        ErrorKind::TimedOut
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::BrokenPipe
+            | ErrorKind::UnexpectedEof
+    )
 }
 
 #[cfg(test)]
@@ -127,6 +291,69 @@ mod tests {
         assert_eq!(err.to_string(), "item not found: test-item");
     }
 
+    #[test]
+    fn test_conflict_display() {
+        let err = VaultmuxError::Conflict("api-key is at revision 3, expected 2".to_string());
+        assert_eq!(
+            err.to_string(),
+            "conflict: api-key is at revision 3, expected 2"
+        );
+    }
+
+    #[test]
+    fn test_replica_divergence_display() {
+        let err = VaultmuxError::ReplicaDivergence {
+            item: "api-key".to_string(),
+            failures: vec![ReplicaFailure {
+                replica: "break-glass".to_string(),
+                error: VaultmuxError::PermissionDenied("read-only".to_string()),
+            }],
+        };
+        assert_eq!(
+            err.to_string(),
+            "api-key: write diverged on break-glass (permission denied: read-only)"
+        );
+    }
+
+    #[test]
+    fn test_retryable_classification() {
+        let transient = [
+            VaultmuxError::Throttled("Rate exceeded".to_string()),
+            VaultmuxError::Timeout("bw unlock did not finish within 30s".to_string()),
+            VaultmuxError::Io(std::io::ErrorKind::ConnectionReset.into()),
+            VaultmuxError::CommandFailed(
+                "bw failed with exit code 1: EBUSY: resource busy or locked, open 'data.json'"
+                    .to_string(),
+            ),
+            VaultmuxError::Other(anyhow::anyhow!("request timed out")),
+            VaultmuxError::backend_op(
+                "aws",
+                "get",
+                "api-key",
+                VaultmuxError::Throttled("ThrottlingException".to_string()),
+            ),
+        ];
+        for err in &transient {
+            assert!(err.is_retryable(), "{err} should be retryable");
+        }
+
+        let permanent = [
+            VaultmuxError::NotFound("api-key".to_string()),
+            VaultmuxError::AlreadyExists("api-key".to_string()),
+            VaultmuxError::Cancelled,
+            VaultmuxError::PermissionDenied("timed out waiting for approval".to_string()),
+            VaultmuxError::CommandFailed("op failed with exit code 1: invalid vault".to_string()),
+            VaultmuxError::CommandFailed(
+                "bw failed with exit code 1: Unexpected token in JSON at position 0 (data.json)"
+                    .to_string(),
+            ),
+            VaultmuxError::Io(std::io::ErrorKind::NotFound.into()),
+        ];
+        for err in &permanent {
+            assert!(!err.is_retryable(), "{err} should not be retryable");
+        }
+    }
+
     #[test]
     fn test_backend_operation_error() {
         let inner = VaultmuxError::NotFound("api-key".to_string());
@@ -1,5 +1,6 @@
 //! Backend factory and registration system.
 
+use crate::middleware::{RateLimitedBackend, RateLimiter, TimeoutBackend};
 use crate::{Backend, Config, Result, VaultmuxError};
 use std::collections::HashMap;
 use std::sync::{OnceLock, RwLock};
@@ -44,10 +45,19 @@ pub fn register_backend(backend_type: &str, factory: BackendFactory) {
 /// If the backend is not registered, an error is returned with a hint to
 /// check feature flags.
 ///
+/// If the configuration sets rate limiting options, the backend is wrapped in
+/// a [`RateLimitedBackend`] configured by [`RateLimiter::from_config`]. If it
+/// sets a timeout or cancellation token, the backend is wrapped in a
+/// [`TimeoutBackend`] that applies them to every operation, including the
+/// wait for the rate limiter.
+/// With the `metrics` and `tracing` features, the backend is also wrapped in
+/// a `MeteredBackend` and a `TracedBackend` that record each operation.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Backend type is not registered (missing feature flag or `register()` call)
+/// - A rate limiting option is invalid
 /// - Backend factory returns an error during initialization
 ///
 /// # Example
@@ -74,7 +84,38 @@ pub fn new_backend(config: Config) -> Result<Box<dyn Backend>> {
         ))
     })?;
 
-    factory(config)
+    build_backend(*factory, config)
+}
+
+/// Creates a backend with `factory` and wraps it as configured.
+fn build_backend(factory: BackendFactory, config: Config) -> Result<Box<dyn Backend>> {
+    let limiter = RateLimiter::from_config(&config)?;
+    let timeout = config.timeout;
+    let cancellation = config.cancellation.clone();
+    let mut backend = factory(config)?;
+
+    if !limiter.is_unlimited() {
+        backend = Box::new(RateLimitedBackend::new(backend, limiter));
+    }
+
+    if timeout.is_some() || cancellation.is_some() {
+        let mut bounded = TimeoutBackend::new(backend);
+        if let Some(timeout) = timeout {
+            bounded = bounded.with_timeout(timeout);
+        }
+        if let Some(token) = cancellation {
+            bounded = bounded.with_cancellation(token);
+        }
+        backend = Box::new(bounded);
+    }
+
+    // Outside the timeout, so that timed out operations are recorded too
+    #[cfg(feature = "metrics")]
+    let backend: Box<dyn Backend> = Box::new(crate::middleware::MeteredBackend::new(backend));
+    #[cfg(feature = "tracing")]
+    let backend: Box<dyn Backend> = Box::new(crate::middleware::TracedBackend::new(backend));
+
+    Ok(backend)
 }
 
 #[cfg(test)]
@@ -107,4 +148,27 @@ mod tests {
             assert!(err_msg.contains("feature flag"));
         }
     }
+
+    #[tokio::test(start_paused = true)]
+    #[cfg(feature = "mock")]
+    async fn test_rate_limit_options_are_applied() {
+        // Built directly so the test doesn't replace a registered backend
+        let factory: BackendFactory = |_| Ok(Box::new(crate::backends::mock::MockBackend::new()));
+
+        let config = Config::new(BackendType::WindowsCredentialManager)
+            .with_option("read_rate_limit", "fast");
+        assert!(build_backend(factory, config).is_err());
+
+        let config = Config::new(BackendType::WindowsCredentialManager)
+            .with_option("read_rate_limit", "1")
+            .with_option("read_burst", "1");
+        let mut backend = build_backend(factory, config).unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        let started = tokio::time::Instant::now();
+        for _ in 0..3 {
+            backend.item_exists("api-key", &*session).await.unwrap();
+        }
+        assert_eq!(started.elapsed(), std::time::Duration::from_secs(2));
+    }
 }
@@ -28,6 +28,13 @@ pub struct Item {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fields: Option<HashMap<String, String>>,
 
+    /// Metadata tags (e.g. owning team, environment)
+    ///
+    /// Stored as native tags or labels on backends that have them, separate
+    /// from the secret value.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
+
     /// Location (folder/vault/directory name)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub location: Option<String>,
@@ -39,6 +46,14 @@ pub struct Item {
     /// When the item was last modified
     #[serde(skip_serializing_if = "Option::is_none")]
     pub modified: Option<DateTime<Utc>>,
+
+    /// Opaque revision token for [`update_item_if`](crate::Backend::update_item_if)
+    ///
+    /// Set by `get_item` on backends that track revisions (AWS version ID,
+    /// GCP version number, Azure version, Bitwarden `revisionDate`,
+    /// 1Password `updated_at`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
 }
 
 impl Item {
@@ -62,9 +77,11 @@ impl Item {
             item_type: ItemType::SecureNote,
             notes: Some(notes.into()),
             fields: None,
+            tags: HashMap::new(),
             location: None,
             created: Some(Utc::now()),
             modified: Some(Utc::now()),
+            revision: None,
         }
     }
 
@@ -80,9 +97,75 @@ impl Item {
             item_type: ItemType::Login,
             notes: None,
             fields: Some(fields),
+            tags: HashMap::new(),
             location: None,
             created: Some(Utc::now()),
             modified: Some(Utc::now()),
+            revision: None,
+        }
+    }
+
+    /// Returns `true` if the item carries more than a plain note.
+    pub fn is_structured(&self) -> bool {
+        self.item_type != ItemType::SecureNote
+            || self.fields.as_ref().is_some_and(|f| !f.is_empty())
+    }
+
+    /// Encodes the item's content for storage as a single string value.
+    ///
+    /// Plain secure notes are stored as their notes unchanged, so existing
+    /// values stay readable. Structured items (any other type, or any item
+    /// with fields) are stored as a tagged JSON document carrying the type,
+    /// fields and notes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vaultmux::{Item, ItemType};
+    ///
+    /// let login = Item::new_login("db", "admin".to_string(), "hunter2".to_string());
+    /// let encoded = login.encode_content().unwrap();
+    ///
+    /// let mut decoded = Item::new_secure_note("db", "");
+    /// decoded.decode_content(encoded);
+    /// assert_eq!(decoded.item_type, ItemType::Login);
+    /// assert_eq!(decoded.fields, login.fields);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn encode_content(&self) -> crate::Result<String> {
+        if !self.is_structured() {
+            return Ok(self.notes.clone().unwrap_or_default());
+        }
+
+        let content = StructuredContent {
+            format: STRUCTURED_FORMAT.to_string(),
+            item_type: self.item_type,
+            fields: self.fields.clone().unwrap_or_default(),
+            notes: self.notes.clone(),
+        };
+
+        Ok(serde_json::to_string(&content)?)
+    }
+
+    /// Populates type, fields and notes from a stored string value.
+    ///
+    /// Values produced by [`encode_content`](Item::encode_content) restore
+    /// the original structure. Any other value becomes the item's notes.
+    pub fn decode_content(&mut self, content: String) {
+        match serde_json::from_str::<StructuredContent>(&content) {
+            Ok(structured) if structured.format == STRUCTURED_FORMAT => {
+                self.item_type = structured.item_type;
+                self.fields = if structured.fields.is_empty() {
+                    None
+                } else {
+                    Some(structured.fields)
+                };
+                self.notes = structured.notes;
+            }
+            _ => self.notes = Some(content),
         }
     }
 }
@@ -91,6 +174,19 @@ impl Item {
 ///
 /// Different backends support different item types. The most universally
 /// supported type is [`SecureNote`](ItemType::SecureNote).
+///
+/// Structured items use these conventional keys in [`Item::fields`], which
+/// backends map to their native field names:
+///
+/// - **Login**: `username`, `password`, `totp`, `uri`
+/// - **Card**: `cardholder_name`, `brand`, `number`, `exp_month`, `exp_year`, `code`
+/// - **Identity**: `title`, `first_name`, `middle_name`, `last_name`, `email`,
+///   `phone`, `company`, `address1`, `address2`, `address3`, `city`, `state`,
+///   `postal_code`, `country`, `ssn`, `username`, `passport_number`,
+///   `license_number`
+/// - **SSHKey**: `private_key`, `public_key`, `fingerprint`
+///
+/// Any other keys are stored as custom fields where the backend supports them.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub enum ItemType {
@@ -118,6 +214,76 @@ impl std::fmt::Display for ItemType {
     }
 }
 
+/// A historical version of an item's value.
+///
+/// Returned by [`Backend::list_versions`](crate::Backend::list_versions).
+/// Version identifiers are backend-specific: AWS version IDs, GCP version
+/// numbers, Azure version hashes or git commit hashes for pass.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemVersion {
+    /// Backend-specific version identifier
+    pub id: String,
+
+    /// When this version was created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Utc>>,
+
+    /// Stage labels attached to this version (e.g., "AWSCURRENT", "AWSPREVIOUS")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<String>,
+
+    /// Whether this is the version returned by `get_item()`/`get_notes()`
+    pub current: bool,
+
+    /// Version state
+    pub state: VersionState,
+}
+
+/// An item that was deleted but can still be restored.
+///
+/// Returned by [`Backend::list_deleted_items`](crate::Backend::list_deleted_items).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeletedItem {
+    /// The item as it was when deleted (content is usually not included)
+    pub item: Item,
+
+    /// When the item was deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<DateTime<Utc>>,
+
+    /// When the backend will permanently delete the item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purge_after: Option<DateTime<Utc>>,
+}
+
+/// State of an item version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum VersionState {
+    /// Version can be read
+    Enabled,
+    /// Version is retained but cannot be read until re-enabled
+    Disabled,
+    /// Version value has been irreversibly destroyed
+    Destroyed,
+}
+
+/// Format tag identifying a JSON-encoded structured item.
+const STRUCTURED_FORMAT: &str = "vaultmux.item/v1";
+
+/// JSON envelope used to store structured items in string-only backends.
+#[derive(Debug, Serialize, Deserialize)]
+struct StructuredContent {
+    #[serde(rename = "vaultmux")]
+    format: String,
+    #[serde(rename = "type")]
+    item_type: ItemType,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    fields: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -155,6 +321,39 @@ mod tests {
         assert_eq!(ItemType::Login.to_string(), "Login");
     }
 
+    #[test]
+    fn test_plain_note_content_unchanged() {
+        let item = Item::new_secure_note("test", "plain value");
+        assert!(!item.is_structured());
+        assert_eq!(item.encode_content().unwrap(), "plain value");
+    }
+
+    #[test]
+    fn test_structured_content_roundtrip() {
+        let mut item = Item::new_login("github", "octocat".to_string(), "pw".to_string());
+        item.notes = Some("recovery codes in drawer".to_string());
+
+        let encoded = item.encode_content().unwrap();
+        let mut decoded = Item::new_secure_note("github", "");
+        decoded.decode_content(encoded);
+
+        assert_eq!(decoded.item_type, ItemType::Login);
+        assert_eq!(decoded.fields, item.fields);
+        assert_eq!(decoded.notes, item.notes);
+    }
+
+    #[test]
+    fn test_decode_foreign_json_as_notes() {
+        let mut item = Item::new_secure_note("config", "");
+        item.decode_content(r#"{"type":"Login","fields":{}}"#.to_string());
+
+        assert_eq!(item.item_type, ItemType::SecureNote);
+        assert_eq!(
+            item.notes.as_deref(),
+            Some(r#"{"type":"Login","fields":{}}"#)
+        );
+    }
+
     #[test]
     fn test_item_serialization() {
         let item = Item::new_secure_note("test", "value");
@@ -71,22 +71,45 @@
 //! [dependencies]
 //! vaultmux = { version = "0.1", features = ["full"] }
 //! ```
+//!
+//! Optional middleware:
+//!
+//! - `encryption`: Client-side envelope encryption of values
+//!   ([`middleware::EncryptedBackend`])
+//! - `snapshot`: Encrypted offline snapshots ([`middleware::SnapshotBackend`])
+//! - `metrics`: Operation, latency, authentication and cache metrics through
+//!   the `metrics` facade ([`middleware::MeteredBackend`])
+//! - `toml`: TOML policy files for [`middleware::PolicyBackend`]
+//! - `tracing`: `tracing` spans for every backend operation and CLI command
+//!   ([`middleware::TracedBackend`])
 
 pub mod backend;
 pub mod backends;
+pub mod capabilities;
 pub mod cli;
 pub mod config;
+#[cfg(feature = "encryption")]
+pub(crate) mod crypto;
+pub mod encoding;
 pub mod error;
 pub mod factory;
 pub mod item;
+pub mod middleware;
+pub mod options;
+pub mod pattern;
 pub mod session;
+pub(crate) mod telemetry;
 pub mod validation;
+pub mod watch;
 
 pub use backend::Backend;
+pub use capabilities::{Capabilities, CompareAndSwap, NameCharset};
 pub use config::{BackendType, Config};
-pub use error::{Result, VaultmuxError};
-pub use item::{Item, ItemType};
+pub use error::{ReplicaFailure, Result, VaultmuxError};
+pub use item::{DeletedItem, Item, ItemType, ItemVersion, VersionState};
+pub use options::{DeleteOptions, ListOptions};
 pub use session::Session;
+pub use watch::{ChangeEvent, ChangeKind, WatchOptions, WatchTarget};
 
 use std::sync::Once;
 
@@ -126,6 +149,8 @@ mod tests {
             session_file: None,
             session_ttl: std::time::Duration::from_secs(1800),
             options: std::collections::HashMap::new(),
+            timeout: None,
+            cancellation: None,
         };
 
         // Pass backend should be available now with the pass feature
@@ -0,0 +1,907 @@
+//! Backend that records every operation to an audit log.
+
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, WatchOptions,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// One recorded backend operation.
+///
+/// Entries never contain secret values, only item names and, for renames,
+/// moves and version operations, the other names involved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the operation started
+    pub timestamp: DateTime<Utc>,
+    /// Name of the audited backend
+    pub backend: String,
+    /// Operation name (`get_notes`, `create_item`, ...)
+    pub operation: String,
+    /// Item the operation acted on, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item: Option<String>,
+    /// What else the operation was given besides the item: the new name of
+    /// a renamed item, the location of a moved one, or the version acted on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Whether the operation succeeded
+    pub outcome: AuditOutcome,
+    /// Who performed the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// Why the operation was performed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// How long the operation took
+    #[serde(rename = "duration_ms", with = "duration_ms")]
+    pub duration: Duration,
+}
+
+/// Result of an audited operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// The operation succeeded
+    Success,
+    /// The operation failed
+    Failure {
+        /// The error message
+        error: String,
+    },
+}
+
+impl AuditOutcome {
+    fn from_result<T>(result: &Result<T>) -> Self {
+        match result {
+            Ok(_) => Self::Success,
+            Err(e) => Self::Failure {
+                error: e.to_string(),
+            },
+        }
+    }
+}
+
+/// Serializes a [`Duration`] as whole milliseconds.
+mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis().try_into().unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+/// Who is acting through an [`AuditedBackend`], and why.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditContext {
+    /// Who performs the operations (a user, service or job name)
+    pub actor: Option<String>,
+    /// Why the operations are performed (a ticket, deploy ID, ...)
+    pub reason: Option<String>,
+}
+
+impl AuditContext {
+    /// Creates a context for `actor`.
+    pub fn new(actor: impl Into<String>) -> Self {
+        Self {
+            actor: Some(actor.into()),
+            reason: None,
+        }
+    }
+
+    /// Sets the reason.
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+/// Destination for audit entries.
+///
+/// `record` is called after each operation finishes. It should be quick,
+/// since the operation's result is held until it returns.
+pub trait AuditSink: Send + Sync {
+    /// Stores one entry.
+    fn record(&self, entry: &AuditEntry) -> Result<()>;
+}
+
+/// Sink that appends entries to a file as JSON lines.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::JsonLinesSink;
+///
+/// # fn example() -> vaultmux::Result<()> {
+/// let sink = JsonLinesSink::open("/var/log/vaultmux/audit.jsonl")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct JsonLinesSink {
+    file: Mutex<File>,
+}
+
+impl JsonLinesSink {
+    /// Opens `path` for appending, creating it if needed.
+    ///
+    /// On Unix a new file is only readable by its owner.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        Ok(Self {
+            file: Mutex::new(options.open(path)?),
+        })
+    }
+}
+
+impl AuditSink for JsonLinesSink {
+    fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(&line)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Sink that keeps entries in memory, for tests.
+///
+/// Clones share the same entries, so keep a clone to inspect what the
+/// backend recorded.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    entries: Arc<Mutex<Vec<AuditEntry>>>,
+}
+
+impl MemorySink {
+    /// Creates an empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded entries, oldest first.
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Removes all recorded entries.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl AuditSink for MemorySink {
+    fn record(&self, entry: &AuditEntry) -> Result<()> {
+        self.entries.lock().unwrap().push(entry.clone());
+        Ok(())
+    }
+}
+
+/// Backend that records every operation to an [`AuditSink`].
+///
+/// Each entry names the operation and item, its outcome and duration, and
+/// the actor and reason from the current [`AuditContext`]. Renames, moves
+/// and version operations also record the new name, location or version
+/// as the entry's `detail`. Secret values are
+/// never recorded. Batch operations record one entry per item.
+///
+/// If the sink fails, the operation returns the sink's error instead of its
+/// result, so that no secret is handed out without a trace. Writes have
+/// already been applied by then.
+///
+/// Streams from `list_items_stream` and `watch` are recorded when they are
+/// opened.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::{AuditContext, AuditedBackend, JsonLinesSink};
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let inner = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+/// let mut backend = AuditedBackend::new(inner, JsonLinesSink::open("audit.jsonl")?)
+///     .with_context(AuditContext::new("deploy-bot").with_reason("release 4.2"));
+///
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// // Recorded as a `get_notes` of `db-password` by deploy-bot
+/// let password = backend.get_notes("db-password", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AuditedBackend<B> {
+    inner: B,
+    sink: Arc<dyn AuditSink>,
+    context: Mutex<AuditContext>,
+}
+
+impl<B: Backend> AuditedBackend<B> {
+    /// Wraps `inner`, recording its operations to `sink`.
+    pub fn new(inner: B, sink: impl AuditSink + 'static) -> Self {
+        Self {
+            inner,
+            sink: Arc::new(sink),
+            context: Mutex::new(AuditContext::default()),
+        }
+    }
+
+    /// Sets the actor and reason recorded with each operation.
+    pub fn with_context(self, context: AuditContext) -> Self {
+        self.set_context(context);
+        self
+    }
+
+    /// Changes the actor and reason for later operations.
+    pub fn set_context(&self, context: AuditContext) {
+        *self.context.lock().unwrap() = context;
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn entry(
+        &self,
+        operation: &str,
+        item: Option<&str>,
+        outcome: AuditOutcome,
+        started: Instant,
+    ) -> AuditEntry {
+        let context = self.context.lock().unwrap().clone();
+        let duration = started.elapsed();
+        AuditEntry {
+            timestamp: Utc::now() - duration,
+            backend: self.inner.name().to_string(),
+            operation: operation.to_string(),
+            item: item.map(str::to_string),
+            detail: None,
+            outcome,
+            actor: context.actor,
+            reason: context.reason,
+            duration,
+        }
+    }
+
+    fn record<T>(
+        &self,
+        operation: &str,
+        item: Option<&str>,
+        detail: Option<&str>,
+        started: Instant,
+        result: Result<T>,
+    ) -> Result<T> {
+        let mut entry = self.entry(operation, item, AuditOutcome::from_result(&result), started);
+        entry.detail = detail.map(str::to_string);
+        self.sink.record(&entry)?;
+        result
+    }
+
+    /// Records one entry per item of a batch operation.
+    fn record_batch<T>(
+        &self,
+        operation: &str,
+        names: impl Iterator<Item = impl AsRef<str>>,
+        started: Instant,
+        result: Result<Vec<Result<T>>>,
+    ) -> Result<Vec<Result<T>>> {
+        match &result {
+            Ok(results) => {
+                for (name, item_result) in names.zip(results) {
+                    let outcome = AuditOutcome::from_result(item_result);
+                    self.sink.record(&self.entry(
+                        operation,
+                        Some(name.as_ref()),
+                        outcome,
+                        started,
+                    ))?;
+                }
+            }
+            Err(e) => {
+                for name in names {
+                    let outcome = AuditOutcome::Failure {
+                        error: e.to_string(),
+                    };
+                    self.sink.record(&self.entry(
+                        operation,
+                        Some(name.as_ref()),
+                        outcome,
+                        started,
+                    ))?;
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Runs a call and records its outcome.
+macro_rules! audited {
+    ($self:ident, $operation:literal, $item:expr, $call:expr) => {
+        audited!($self, $operation, $item, None, $call)
+    };
+    ($self:ident, $operation:literal, $item:expr, $detail:expr, $call:expr) => {{
+        let started = Instant::now();
+        let result = $call.await;
+        $self.record($operation, $item, $detail, started, result)
+    }};
+}
+
+#[async_trait]
+impl<B: Backend> Backend for AuditedBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        audited!(self, "init", None, self.inner.init())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        audited!(self, "close", None, self.inner.close())
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        audited!(self, "authenticate", None, self.inner.authenticate())
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        audited!(self, "sync", None, self.inner.sync(session))
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        audited!(
+            self,
+            "get_item",
+            Some(name),
+            self.inner.get_item(name, session)
+        )
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        audited!(
+            self,
+            "get_notes",
+            Some(name),
+            self.inner.get_notes(name, session)
+        )
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        audited!(
+            self,
+            "item_exists",
+            Some(name),
+            self.inner.item_exists(name, session)
+        )
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        audited!(self, "list_items", None, self.inner.list_items(session))
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        let entry = self.entry(
+            "list_items_stream",
+            None,
+            AuditOutcome::Success,
+            Instant::now(),
+        );
+        if let Err(e) = self.sink.record(&entry) {
+            return Box::pin(futures::stream::once(async move { Err(e) }));
+        }
+        self.inner.list_items_stream(options, session)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "create_item",
+            Some(name),
+            self.inner.create_item(name, content, session)
+        )
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "update_item",
+            Some(name),
+            self.inner.update_item(name, content, session)
+        )
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        audited!(
+            self,
+            "delete_item",
+            Some(name),
+            self.inner.delete_item(name, session)
+        )
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        let started = Instant::now();
+        let result = self.inner.get_many(names, session).await;
+        self.record_batch("get_many", names.iter(), started, result)
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let started = Instant::now();
+        let result = self.inner.put_many(entries, session).await;
+        self.record_batch(
+            "put_many",
+            entries.iter().map(|(name, _)| name),
+            started,
+            result,
+        )
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let started = Instant::now();
+        let result = self.inner.delete_many(names, session).await;
+        self.record_batch("delete_many", names.iter(), started, result)
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        audited!(
+            self,
+            "create_item_full",
+            Some(&item.name),
+            self.inner.create_item_full(item, session)
+        )
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        audited!(
+            self,
+            "update_item_full",
+            Some(&item.name),
+            self.inner.update_item_full(item, session)
+        )
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "update_item_if",
+            Some(name),
+            self.inner
+                .update_item_if(name, content, expected_revision, session)
+        )
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        audited!(
+            self,
+            "get_bytes",
+            Some(name),
+            self.inner.get_bytes(name, session)
+        )
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        audited!(
+            self,
+            "put_bytes",
+            Some(name),
+            self.inner.put_bytes(name, data, session)
+        )
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        audited!(
+            self,
+            "list_versions",
+            Some(name),
+            self.inner.list_versions(name, session)
+        )
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        audited!(
+            self,
+            "get_version",
+            Some(name),
+            self.inner.get_version(name, version_id, session)
+        )
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "disable_version",
+            Some(name),
+            Some(version_id),
+            self.inner.disable_version(name, version_id, session)
+        )
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "destroy_version",
+            Some(name),
+            Some(version_id),
+            self.inner.destroy_version(name, version_id, session)
+        )
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "rollback",
+            Some(name),
+            Some(version_id),
+            self.inner.rollback(name, version_id, session)
+        )
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        audited!(
+            self,
+            "get_tags",
+            Some(name),
+            self.inner.get_tags(name, session)
+        )
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "set_tags",
+            Some(name),
+            self.inner.set_tags(name, tags, session)
+        )
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        audited!(
+            self,
+            "list_items_by_tag",
+            None,
+            self.inner.list_items_by_tag(key, value, session)
+        )
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "rename_item",
+            Some(old_name),
+            Some(new_name),
+            self.inner.rename_item(old_name, new_name, session)
+        )
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        audited!(
+            self,
+            "move_item",
+            Some(name),
+            Some(location),
+            self.inner.move_item(name, location, session)
+        )
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        audited!(
+            self,
+            "delete_item_with",
+            Some(name),
+            self.inner.delete_item_with(name, options, session)
+        )
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        audited!(
+            self,
+            "list_deleted_items",
+            None,
+            self.inner.list_deleted_items(session)
+        )
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        audited!(
+            self,
+            "restore_item",
+            Some(name),
+            self.inner.restore_item(name, session)
+        )
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        let entry = self.entry("watch", None, AuditOutcome::Success, Instant::now());
+        if let Err(e) = self.sink.record(&entry) {
+            return Box::pin(futures::stream::once(async move { Err(e) }));
+        }
+        self.inner.watch(options, session)
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        audited!(
+            self,
+            "list_locations",
+            None,
+            self.inner.list_locations(session)
+        )
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        audited!(
+            self,
+            "location_exists",
+            Some(name),
+            self.inner.location_exists(name, session)
+        )
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        audited!(
+            self,
+            "create_location",
+            Some(name),
+            self.inner.create_location(name, session)
+        )
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        audited!(
+            self,
+            "list_items_in_location",
+            None,
+            self.inner
+                .list_items_in_location(loc_type, loc_value, session)
+        )
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_audit_records_operations_without_values() {
+        let sink = MemorySink::new();
+        let inner = MockBackend::new();
+        inner.set_item("db-password", "hunter2").await;
+        let mut backend = AuditedBackend::new(inner, sink.clone())
+            .with_context(AuditContext::new("deploy-bot").with_reason("INC-42"));
+        let session = backend.authenticate().await.unwrap();
+        sink.clear();
+
+        backend.get_notes("db-password", &*session).await.unwrap();
+        assert!(backend.get_notes("missing", &*session).await.is_err());
+        let results = backend
+            .get_many(&["db-password", "missing"], &*session)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        let entries = sink.entries();
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| {
+                (
+                    e.operation.as_str(),
+                    e.item.as_deref(),
+                    e.outcome == AuditOutcome::Success,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("get_notes", Some("db-password"), true),
+                ("get_notes", Some("missing"), false),
+                ("get_many", Some("db-password"), true),
+                ("get_many", Some("missing"), false),
+            ]
+        );
+        assert!(entries.iter().all(|e| e.backend == "mock"
+            && e.actor.as_deref() == Some("deploy-bot")
+            && e.reason.as_deref() == Some("INC-42")));
+
+        let json = serde_json::to_string(&entries).unwrap();
+        assert!(!json.contains("hunter2"));
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_sink_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        let mut backend =
+            AuditedBackend::new(MockBackend::new(), JsonLinesSink::open(&path).unwrap());
+        let session = backend.authenticate().await.unwrap();
+        backend
+            .create_item("api-key", "created-value", &*session)
+            .await
+            .unwrap();
+        drop(backend);
+
+        let mut backend =
+            AuditedBackend::new(MockBackend::new(), JsonLinesSink::open(&path).unwrap());
+        backend.init().await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<AuditEntry> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let operations: Vec<_> = entries.iter().map(|e| e.operation.as_str()).collect();
+        assert_eq!(operations, vec!["authenticate", "create_item", "init"]);
+        assert!(!contents.contains("created-value"));
+    }
+
+    #[tokio::test]
+    async fn test_audit_records_details_without_values() {
+        let sink = MemorySink::new();
+        let mut backend = AuditedBackend::new(MockBackend::new(), sink.clone());
+        let session = backend.authenticate().await.unwrap();
+
+        let values = ["first-value", "second-value", "batch-value", "full-value"];
+        backend
+            .create_item("api-key", values[0], &*session)
+            .await
+            .unwrap();
+        backend
+            .update_item("api-key", values[1], &*session)
+            .await
+            .unwrap();
+        backend
+            .put_many(&[("batch-key", values[2])], &*session)
+            .await
+            .unwrap();
+        let mut item = Item::new_secure_note("full-key", values[3]);
+        item.fields = Some(HashMap::from([(
+            "password".to_string(),
+            "field-value".to_string(),
+        )]));
+        backend.create_item_full(&item, &*session).await.unwrap();
+
+        backend
+            .rename_item("api-key", "renamed-key", &*session)
+            .await
+            .unwrap();
+        let _ = backend.move_item("renamed-key", "archive", &*session).await;
+        let _ = backend.rollback("renamed-key", "v1", &*session).await;
+        let _ = backend
+            .destroy_version("renamed-key", "v2", &*session)
+            .await;
+
+        let entries = sink.entries();
+        let details: Vec<_> = entries
+            .iter()
+            .filter(|e| e.detail.is_some())
+            .map(|e| (e.operation.as_str(), e.item.as_deref(), e.detail.as_deref()))
+            .collect();
+        assert_eq!(
+            details,
+            vec![
+                ("rename_item", Some("api-key"), Some("renamed-key")),
+                ("move_item", Some("renamed-key"), Some("archive")),
+                ("rollback", Some("renamed-key"), Some("v1")),
+                ("destroy_version", Some("renamed-key"), Some("v2")),
+            ]
+        );
+
+        for entry in &entries {
+            let json = serde_json::to_string(entry).unwrap();
+            for value in values.iter().chain(&["field-value"]) {
+                assert!(!json.contains(value), "{json} contains {value}");
+            }
+        }
+    }
+}
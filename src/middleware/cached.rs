@@ -0,0 +1,857 @@
+//! Read-through cache for another backend.
+
+use crate::telemetry;
+use crate::{
+    Backend, Capabilities, DeleteOptions, DeletedItem, Item, ItemVersion, Result, Session,
+    VaultmuxError,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+/// Default time a value is served from the cache (60 seconds).
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Default time a `NotFound` answer is served from the cache (10 seconds).
+const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(10);
+
+/// Default time an expired value is still served while it is refreshed (5 minutes).
+const DEFAULT_STALE_TTL: Duration = Duration::from_secs(300);
+
+/// Default time an expired value may stand in for an unavailable backend (1 hour).
+const DEFAULT_MAX_STALE: Duration = Duration::from_secs(3600);
+
+/// Default maximum number of cache entries.
+const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Backend that caches reads from an inner backend in memory.
+///
+/// [`get_item`](Backend::get_item), [`get_notes`](Backend::get_notes),
+/// [`item_exists`](Backend::item_exists) and [`list_items`](Backend::list_items)
+/// are cached:
+///
+/// - Each entry is served for `ttl` after it was fetched, or `negative_ttl`
+///   if the item was not found.
+/// - For `stale_ttl` after that, the old entry is still served while a
+///   background task fetches a new one.
+/// - After that, the entry is fetched before returning. If the fetch fails
+///   because the backend is unreachable, locked, throttled or its session
+///   expired, the old entry is served instead of the error for up to
+///   `max_stale` past its TTL. Any other error, such as `PermissionDenied`
+///   or a failed integrity check, is always returned.
+///
+/// The cache holds at most `capacity` entries. When it is full, entries
+/// too old to be served are dropped first, then the oldest ones.
+///
+/// Writes through the wrapper drop the entries for the items they touch,
+/// and [`sync()`](Backend::sync) drops every entry. Writes made elsewhere are
+/// seen once their entries expire.
+///
+/// Background refreshes use the session from the wrapper's own
+/// [`authenticate()`](Backend::authenticate). If the inner backend was
+/// authenticated separately, expired entries are fetched before returning.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use vaultmux::middleware::CachedBackend;
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let inner = factory::new_backend(Config::new(BackendType::Bitwarden))?;
+/// let mut backend = CachedBackend::new(inner).with_ttl(Duration::from_secs(300));
+///
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// // Only the first read runs the bw CLI
+/// for _ in 0..100 {
+///     let token = backend.get_notes("api-token", &*session).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct CachedBackend<B> {
+    inner: Arc<RwLock<B>>,
+    cache: Arc<Mutex<Cache>>,
+    session: Option<Arc<dyn Session>>,
+    name: String,
+    capabilities: Capabilities,
+    ttl: Duration,
+    negative_ttl: Duration,
+    stale_ttl: Duration,
+    max_stale: Duration,
+    capacity: usize,
+}
+
+/// What a cache entry holds the answer to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Item(String),
+    Notes(String),
+    List,
+}
+
+/// A cached answer.
+#[derive(Debug, Clone)]
+enum Value {
+    Item(Box<Item>),
+    Notes(String),
+    List(Vec<Item>),
+    Missing,
+}
+
+/// What [`CachedBackend::lookup`] found in the cache.
+enum Cached {
+    /// Servable as-is
+    Fresh(Value),
+    /// Too old to serve unless the backend is unavailable
+    Expired(Value),
+    Absent,
+}
+
+struct Entry {
+    value: Value,
+    fetched: Instant,
+    refreshing: bool,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<Key, Entry>,
+    /// Bumped on every invalidation, so fetches that started before one
+    /// don't store outdated values
+    generation: u64,
+}
+
+/// Returns `true` for errors that an expired value may stand in for: the
+/// backend couldn't be reached, was locked or throttled, or the session
+/// expired.
+///
+/// Unclassified errors only count when their message marks them as
+/// transient, so that access and integrity failures are never masked.
+fn is_outage(err: &VaultmuxError) -> bool {
+    match err {
+        VaultmuxError::NotAuthenticated
+        | VaultmuxError::SessionExpired
+        | VaultmuxError::BackendLocked
+        | VaultmuxError::Throttled(_)
+        | VaultmuxError::Timeout(_) => true,
+        VaultmuxError::Io(_) | VaultmuxError::CommandFailed(_) | VaultmuxError::Other(_) => {
+            err.is_retryable()
+        }
+        VaultmuxError::BackendOperation { source, .. } => is_outage(source),
+        _ => false,
+    }
+}
+
+/// Bounds on what the cache keeps.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    capacity: usize,
+    /// Age after which no entry can be served any more
+    retention: Duration,
+}
+
+impl Cache {
+    fn store(&mut self, key: Key, value: Value, generation: u64, limits: Limits) {
+        if generation != self.generation {
+            return;
+        }
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= limits.capacity {
+            self.entries
+                .retain(|_, entry| entry.fetched.elapsed() < limits.retention);
+            while self.entries.len() >= limits.capacity {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.fetched)
+                    .map(|(key, _)| key.clone());
+                match oldest {
+                    Some(oldest) => self.entries.remove(&oldest),
+                    None => break,
+                };
+            }
+        }
+
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                fetched: Instant::now(),
+                refreshing: false,
+            },
+        );
+    }
+}
+
+impl<B: Backend + 'static> CachedBackend<B> {
+    /// Wraps `inner` with the default TTLs (60 seconds, 10 seconds for
+    /// `NotFound`, 5 minutes stale, 1 hour maximum stale) and room for
+    /// 10,000 entries.
+    pub fn new(inner: B) -> Self {
+        Self {
+            name: inner.name().to_string(),
+            capabilities: inner.capabilities(),
+            inner: Arc::new(RwLock::new(inner)),
+            cache: Arc::new(Mutex::new(Cache::default())),
+            session: None,
+            ttl: DEFAULT_TTL,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+            stale_ttl: DEFAULT_STALE_TTL,
+            max_stale: DEFAULT_MAX_STALE,
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Sets how long values are served from the cache.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets how long `NotFound` answers are served from the cache.
+    ///
+    /// Zero disables negative caching.
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Sets how long an expired value is served while it is refreshed.
+    ///
+    /// Zero fetches expired values before returning.
+    pub fn with_stale_ttl(mut self, ttl: Duration) -> Self {
+        self.stale_ttl = ttl;
+        self
+    }
+
+    /// Sets how long past its TTL an expired value is served when the
+    /// backend is unavailable.
+    ///
+    /// Zero returns the error instead.
+    pub fn with_max_stale(mut self, max_stale: Duration) -> Self {
+        self.max_stale = max_stale;
+        self
+    }
+
+    /// Sets the maximum number of cache entries.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    fn limits(&self) -> Limits {
+        Limits {
+            capacity: self.capacity,
+            retention: self.ttl.max(self.negative_ttl) + self.stale_ttl.max(self.max_stale),
+        }
+    }
+
+    /// Drops the cached entries for an item, for changes made outside the
+    /// wrapper.
+    pub fn invalidate(&self, name: &str) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.generation += 1;
+        cache.entries.remove(&Key::Item(name.to_string()));
+        cache.entries.remove(&Key::Notes(name.to_string()));
+        cache.entries.remove(&Key::List);
+    }
+
+    /// Drops every cached entry.
+    pub fn clear(&self) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.generation += 1;
+        cache.entries.clear();
+    }
+
+    /// Returns the cached answer for `key`, fetching it if needed.
+    async fn lookup(&self, key: Key, session: &dyn Session) -> Result<Value> {
+        let mut stale = false;
+        let (cached, generation) = {
+            let mut cache = self.cache.lock().unwrap();
+            let generation = cache.generation;
+            let cached = match cache.entries.get_mut(&key) {
+                None => Cached::Absent,
+                Some(entry) => {
+                    let ttl = match entry.value {
+                        Value::Missing => self.negative_ttl,
+                        _ => self.ttl,
+                    };
+                    let age = entry.fetched.elapsed();
+
+                    match self.session {
+                        _ if age < ttl => Cached::Fresh(entry.value.clone()),
+                        Some(ref session) if age < ttl + self.stale_ttl => {
+                            if !entry.refreshing {
+                                entry.refreshing = true;
+                                self.spawn_refresh(key.clone(), session.clone(), generation);
+                            }
+                            stale = true;
+                            Cached::Fresh(entry.value.clone())
+                        }
+                        _ if age < ttl + self.max_stale => Cached::Expired(entry.value.clone()),
+                        _ => Cached::Absent,
+                    }
+                }
+            };
+            (cached, generation)
+        };
+
+        let result = match cached {
+            Cached::Fresh(_) if stale => "stale",
+            Cached::Fresh(_) => "hit",
+            _ => "miss",
+        };
+        telemetry::record_cache_lookup(&self.name, result);
+
+        let expired = match cached {
+            Cached::Fresh(value) => return Ok(value),
+            Cached::Expired(value) => Some(value),
+            Cached::Absent => None,
+        };
+
+        let inner = self.inner.read().await;
+        match fetch(&*inner, &key, session).await {
+            Ok(value) => {
+                self.cache
+                    .lock()
+                    .unwrap()
+                    .store(key, value.clone(), generation, self.limits());
+                Ok(value)
+            }
+            Err(e) if is_outage(&e) => expired.ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fetches a new value for `key` in the background.
+    ///
+    /// A failed fetch keeps the old entry.
+    fn spawn_refresh(&self, key: Key, session: Arc<dyn Session>, generation: u64) {
+        let inner = self.inner.clone();
+        let cache = self.cache.clone();
+        let limits = self.limits();
+
+        tokio::spawn(async move {
+            let result = {
+                let inner = inner.read().await;
+                fetch(&*inner, &key, &*session).await
+            };
+
+            let mut cache = cache.lock().unwrap();
+            match result {
+                Ok(value) => cache.store(key, value, generation, limits),
+                Err(_) => {
+                    if let Some(entry) = cache.entries.get_mut(&key) {
+                        entry.refreshing = false;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Fetches the answer for `key` from the inner backend.
+async fn fetch<B: Backend>(inner: &B, key: &Key, session: &dyn Session) -> Result<Value> {
+    let result = match key {
+        Key::Item(name) => inner
+            .get_item(name, session)
+            .await
+            .map(|item| Value::Item(Box::new(item))),
+        Key::Notes(name) => inner.get_notes(name, session).await.map(Value::Notes),
+        Key::List => inner.list_items(session).await.map(Value::List),
+    };
+
+    match result {
+        Err(VaultmuxError::NotFound(_)) => Ok(Value::Missing),
+        other => other,
+    }
+}
+
+#[async_trait]
+impl<B: Backend + 'static> Backend for CachedBackend<B> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.inner.write().await.init().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.session = None;
+        self.clear();
+        self.inner.write().await.close().await
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.read().await.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        let session = self.inner.write().await.authenticate().await?;
+        self.session = Some(session.clone());
+        Ok(session)
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        let result = self.inner.write().await.sync(session).await;
+        self.clear();
+        result
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        match self.lookup(Key::Item(name.to_string()), session).await? {
+            Value::Item(item) => Ok(*item),
+            _ => Err(VaultmuxError::NotFound(name.to_string())),
+        }
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        match self.lookup(Key::Notes(name.to_string()), session).await? {
+            Value::Notes(notes) => Ok(notes),
+            _ => Err(VaultmuxError::NotFound(name.to_string())),
+        }
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        let value = self.lookup(Key::Item(name.to_string()), session).await?;
+        Ok(matches!(value, Value::Item(_)))
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        match self.lookup(Key::List, session).await? {
+            Value::List(items) => Ok(items),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .create_item(name, content, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .update_item(name, content, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        let result = self.inner.write().await.delete_item(name, session).await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let result = self.inner.write().await.put_many(entries, session).await;
+        for (name, _) in entries {
+            self.invalidate(name);
+        }
+        result
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let result = self.inner.write().await.delete_many(names, session).await;
+        for name in names {
+            self.invalidate(name);
+        }
+        result
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .create_item_full(item, session)
+            .await;
+        self.invalidate(&item.name);
+        result
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .update_item_full(item, session)
+            .await;
+        self.invalidate(&item.name);
+        result
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .update_item_if(name, content, expected_revision, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        self.inner.read().await.get_bytes(name, session).await
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .put_bytes(name, data, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        self.inner.read().await.list_versions(name, session).await
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        self.inner
+            .read()
+            .await
+            .get_version(name, version_id, session)
+            .await
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .disable_version(name, version_id, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .destroy_version(name, version_id, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .rollback(name, version_id, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        self.inner.read().await.get_tags(name, session).await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self.inner.write().await.set_tags(name, tags, session).await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.inner
+            .read()
+            .await
+            .list_items_by_tag(key, value, session)
+            .await
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .rename_item(old_name, new_name, session)
+            .await;
+        self.invalidate(old_name);
+        self.invalidate(new_name);
+        result
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .move_item(name, location, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .write()
+            .await
+            .delete_item_with(name, options, session)
+            .await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        self.inner.read().await.list_deleted_items(session).await
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        let result = self.inner.write().await.restore_item(name, session).await;
+        self.invalidate(name);
+        result
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        self.inner.read().await.list_locations(session).await
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        self.inner.read().await.location_exists(name, session).await
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        self.inner
+            .write()
+            .await
+            .create_location(name, session)
+            .await
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.inner
+            .read()
+            .await
+            .list_items_in_location(loc_type, loc_value, session)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    /// Changes the inner backend without going through the cache.
+    async fn update_behind(backend: &CachedBackend<MockBackend>, name: &str, value: &str) {
+        backend.inner.read().await.set_item(name, value).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_ttl_and_stale_refresh() {
+        let mut backend = CachedBackend::new(MockBackend::new())
+            .with_ttl(Duration::from_secs(10))
+            .with_stale_ttl(Duration::from_secs(60));
+        let session = backend.authenticate().await.unwrap();
+
+        update_behind(&backend, "api-key", "v1").await;
+        assert_eq!(backend.get_notes("api-key", &*session).await.unwrap(), "v1");
+
+        update_behind(&backend, "api-key", "v2").await;
+        assert_eq!(backend.get_notes("api-key", &*session).await.unwrap(), "v1");
+
+        // Expired: the stale value is served while the refresh runs
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(backend.get_notes("api-key", &*session).await.unwrap(), "v1");
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(backend.get_notes("api-key", &*session).await.unwrap(), "v2");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_negative_and_invalidation() {
+        let mut backend = CachedBackend::new(MockBackend::new())
+            .with_negative_ttl(Duration::from_secs(5))
+            .with_stale_ttl(Duration::ZERO);
+        let session = backend.authenticate().await.unwrap();
+
+        assert!(!backend.item_exists("api-key", &*session).await.unwrap());
+        update_behind(&backend, "api-key", "v1").await;
+        assert!(!backend.item_exists("api-key", &*session).await.unwrap());
+
+        tokio::time::sleep(Duration::from_secs(6)).await;
+        assert!(backend.item_exists("api-key", &*session).await.unwrap());
+
+        // Writes through the wrapper are seen immediately
+        backend
+            .update_item("api-key", "v2", &*session)
+            .await
+            .unwrap();
+        assert_eq!(backend.get_notes("api-key", &*session).await.unwrap(), "v2");
+        backend.delete_item("api-key", &*session).await.unwrap();
+        assert!(backend.list_items(&*session).await.unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_serves_expired_value_on_error() {
+        let mut backend = CachedBackend::new(MockBackend::new())
+            .with_ttl(Duration::from_secs(10))
+            .with_stale_ttl(Duration::ZERO);
+        let session = backend.authenticate().await.unwrap();
+
+        update_behind(&backend, "api-key", "v1").await;
+        assert_eq!(backend.get_notes("api-key", &*session).await.unwrap(), "v1");
+
+        backend.inner.write().await.get_error = Some(VaultmuxError::BackendLocked);
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(backend.get_notes("api-key", &*session).await.unwrap(), "v1");
+
+        // Permanent failures are never masked by the old value
+        let permanent = [
+            VaultmuxError::PermissionDenied("access revoked".to_string()),
+            VaultmuxError::Other(anyhow::anyhow!(
+                "integrity check failed: wrong key or modified data"
+            )),
+        ];
+        for err in permanent {
+            let kind = err.kind();
+            backend.inner.write().await.get_error = Some(err);
+            let result = backend.get_notes("api-key", &*session).await;
+            assert_eq!(result.unwrap_err().kind(), kind);
+        }
+
+        backend.inner.write().await.get_error = None;
+        backend
+            .inner
+            .write()
+            .await
+            .delete_item("api-key", &*session)
+            .await
+            .unwrap();
+        assert!(matches!(
+            backend.get_notes("api-key", &*session).await,
+            Err(VaultmuxError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_limits_staleness_and_size() {
+        let mut backend = CachedBackend::new(MockBackend::new())
+            .with_ttl(Duration::from_secs(10))
+            .with_stale_ttl(Duration::ZERO)
+            .with_max_stale(Duration::from_secs(60))
+            .with_capacity(2);
+        let session = backend.authenticate().await.unwrap();
+
+        update_behind(&backend, "api-key", "v1").await;
+        assert_eq!(backend.get_notes("api-key", &*session).await.unwrap(), "v1");
+
+        backend.inner.write().await.get_error = Some(VaultmuxError::BackendLocked);
+        tokio::time::sleep(Duration::from_secs(71)).await;
+        assert!(matches!(
+            backend.get_notes("api-key", &*session).await,
+            Err(VaultmuxError::BackendLocked)
+        ));
+        backend.inner.write().await.get_error = None;
+
+        for name in ["a", "b", "c"] {
+            update_behind(&backend, name, name).await;
+            backend.get_notes(name, &*session).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        let cache = backend.cache.lock().unwrap();
+        assert_eq!(cache.entries.len(), 2);
+        assert!(!cache.entries.contains_key(&Key::Notes("a".to_string())));
+    }
+}
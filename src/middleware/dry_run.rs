@@ -0,0 +1,532 @@
+//! Backend that records changes instead of making them.
+
+use crate::telemetry;
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, WatchOptions,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex};
+
+/// A change a [`DryRunBackend`] would have made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedChange {
+    /// Operation name (`create_item`, `delete_item`, ...)
+    pub operation: String,
+    /// Item the operation acts on
+    pub item: String,
+    /// Size of the new value in bytes, for operations that write one
+    pub size: Option<usize>,
+    /// Fingerprint of the new value, for operations that write one
+    ///
+    /// Equal values have equal fingerprints within one [`DryRunBackend`],
+    /// so a plan shows which values would change. Fingerprints are keyed
+    /// per backend and can't be compared across runs or used to recover
+    /// the value.
+    pub fingerprint: Option<String>,
+    /// Other arguments, such as a rename target or version ID
+    pub detail: Option<String>,
+}
+
+impl fmt::Display for PlannedChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.operation, self.item)?;
+        if let Some(detail) = &self.detail {
+            write!(f, " {}", detail)?;
+        }
+        if let (Some(size), Some(fingerprint)) = (self.size, &self.fingerprint) {
+            write!(f, " ({} bytes, {})", size, fingerprint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Backend that records writes as [`PlannedChange`]s without applying them.
+///
+/// Reads go to the inner backend, so tooling sees the real store. Every
+/// write returns success without calling the inner backend, and is added to
+/// the plan returned by [`changes`](Self::changes). With the `tracing`
+/// feature each change is also logged as an event; without it, pass a
+/// callback to [`with_observer`](Self::with_observer) to see changes as
+/// they happen.
+///
+/// Because writes are skipped, later reads don't reflect them: an item
+/// "created" earlier in the run is still missing.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::DryRunBackend;
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let inner = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+/// let mut backend = DryRunBackend::new(inner);
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// // Run the rotation against production without changing anything
+/// backend.update_item("db-password", "new-password", &*session).await?;
+///
+/// for change in backend.changes() {
+///     println!("would {}", change);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct DryRunBackend<B> {
+    inner: B,
+    changes: Mutex<Vec<PlannedChange>>,
+    fingerprint_key: RandomState,
+    observer: Option<Observer>,
+}
+
+/// Callback run for each planned change.
+type Observer = Box<dyn Fn(&PlannedChange) + Send + Sync>;
+
+impl<B: Backend> DryRunBackend<B> {
+    /// Wraps `inner`.
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            changes: Mutex::new(Vec::new()),
+            fingerprint_key: RandomState::new(),
+            observer: None,
+        }
+    }
+
+    /// Calls `observer` with each change as it is planned.
+    ///
+    /// Works with or without the `tracing` feature, e.g. for printing the
+    /// plan of a long-running job while it runs.
+    pub fn with_observer(
+        mut self,
+        observer: impl Fn(&PlannedChange) + Send + Sync + 'static,
+    ) -> Self {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Returns the changes recorded so far, in order.
+    pub fn changes(&self) -> Vec<PlannedChange> {
+        self.changes.lock().unwrap().clone()
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn plan(&self, operation: &str, item: &str, value: Option<&[u8]>, detail: Option<String>) {
+        let change = PlannedChange {
+            operation: operation.to_string(),
+            item: item.to_string(),
+            size: value.map(<[u8]>::len),
+            fingerprint: value
+                .map(|value| format!("{:016x}", self.fingerprint_key.hash_one(value))),
+            detail,
+        };
+        telemetry::log_planned_change(&change);
+        if let Some(observer) = &self.observer {
+            observer(&change);
+        }
+        self.changes.lock().unwrap().push(change);
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Backend for DryRunBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.inner.init().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        self.inner.authenticate().await
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        self.inner.sync(session).await
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        self.inner.get_item(name, session).await
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        self.inner.get_notes(name, session).await
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        self.inner.item_exists(name, session).await
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        self.inner.list_items(session).await
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        self.inner.list_items_stream(options, session)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan("create_item", name, Some(content.as_bytes()), None);
+        Ok(())
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan("update_item", name, Some(content.as_bytes()), None);
+        Ok(())
+    }
+
+    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        self.plan("delete_item", name, None, None);
+        Ok(())
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        self.inner.get_many(names, session).await
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        Ok(entries
+            .iter()
+            .map(|(name, content)| {
+                self.plan("put_many", name, Some(content.as_bytes()), None);
+                Ok(())
+            })
+            .collect())
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        Ok(names
+            .iter()
+            .map(|name| {
+                self.plan("delete_many", name, None, None);
+                Ok(())
+            })
+            .collect())
+    }
+
+    async fn create_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        self.plan(
+            "create_item_full",
+            &item.name,
+            Some(item.encode_content()?.as_bytes()),
+            None,
+        );
+        Ok(())
+    }
+
+    async fn update_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        self.plan(
+            "update_item_full",
+            &item.name,
+            Some(item.encode_content()?.as_bytes()),
+            None,
+        );
+        Ok(())
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan(
+            "update_item_if",
+            name,
+            Some(content.as_bytes()),
+            Some(format!("if revision {}", expected_revision)),
+        );
+        Ok(())
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        self.inner.get_bytes(name, session).await
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], _session: &dyn Session) -> Result<()> {
+        self.plan("put_bytes", name, Some(data), None);
+        Ok(())
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        self.inner.list_versions(name, session).await
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        self.inner.get_version(name, version_id, session).await
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan(
+            "disable_version",
+            name,
+            None,
+            Some(format!("version {}", version_id)),
+        );
+        Ok(())
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan(
+            "destroy_version",
+            name,
+            None,
+            Some(format!("version {}", version_id)),
+        );
+        Ok(())
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan(
+            "rollback",
+            name,
+            None,
+            Some(format!("to version {}", version_id)),
+        );
+        Ok(())
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        self.inner.get_tags(name, session).await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan("set_tags", name, None, Some(format!("{} tags", tags.len())));
+        Ok(())
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.inner.list_items_by_tag(key, value, session).await
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan(
+            "rename_item",
+            old_name,
+            None,
+            Some(format!("to {}", new_name)),
+        );
+        Ok(())
+    }
+
+    async fn move_item(
+        &mut self,
+        name: &str,
+        location: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan("move_item", name, None, Some(format!("to {}", location)));
+        Ok(())
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.plan(
+            "delete_item_with",
+            name,
+            None,
+            Some(format!("{:?}", options)),
+        );
+        Ok(())
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        self.inner.list_deleted_items(session).await
+    }
+
+    async fn restore_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        self.plan("restore_item", name, None, None);
+        Ok(())
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        self.inner.watch(options, session)
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        self.inner.list_locations(session).await
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        self.inner.location_exists(name, session).await
+    }
+
+    async fn create_location(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        self.plan("create_location", name, None, None);
+        Ok(())
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.inner
+            .list_items_in_location(loc_type, loc_value, session)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_dry_run_plans_without_writing() {
+        let inner = MockBackend::new();
+        inner.set_item("db-password", "old").await;
+        let mut backend = DryRunBackend::new(inner);
+        let session = backend.authenticate().await.unwrap();
+
+        backend
+            .update_item("db-password", "rotated", &*session)
+            .await
+            .unwrap();
+        backend
+            .create_item("api-key", "rotated", &*session)
+            .await
+            .unwrap();
+        backend
+            .rename_item("db-password", "db-password-old", &*session)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            backend.get_notes("db-password", &*session).await.unwrap(),
+            "old"
+        );
+        assert!(!backend.item_exists("api-key", &*session).await.unwrap());
+
+        let changes = backend.changes();
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.operation.as_str(), c.item.as_str(), c.size))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("update_item", "db-password", Some(7)),
+                ("create_item", "api-key", Some(7)),
+                ("rename_item", "db-password", None),
+            ]
+        );
+        assert_eq!(changes[0].fingerprint, changes[1].fingerprint);
+        assert_eq!(
+            changes[2].to_string(),
+            "rename_item db-password to db-password-old"
+        );
+        assert!(!format!("{:?}", changes).contains("rotated"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_observer_sees_each_change() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let observed = seen.clone();
+        let mut backend = DryRunBackend::new(MockBackend::new())
+            .with_observer(move |change| observed.lock().unwrap().push(change.to_string()));
+        let session = backend.authenticate().await.unwrap();
+
+        backend
+            .create_item("api-key", "value", &*session)
+            .await
+            .unwrap();
+        backend.delete_item("api-key", &*session).await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert!(seen[0].starts_with("create_item api-key (5 bytes, "));
+        assert_eq!(seen[1], "delete_item api-key");
+    }
+}
@@ -0,0 +1,990 @@
+//! Backend that encrypts values before they reach another backend.
+
+use crate::crypto::{self, KEY_LEN, SALT_LEN, SEAL_OVERHEAD};
+use crate::encoding;
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemType, ItemVersion,
+    ListOptions, Result, Session, VaultmuxError, WatchOptions,
+};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Default name of the item holding the wrapped data key.
+pub const DEFAULT_KEY_ITEM: &str = "vaultmux-data-key";
+
+/// Marker prepended to encrypted values.
+///
+/// Like [`encoding::BINARY_MARKER`], this is a `data:` URI prefix, so
+/// encrypted values are recognisable in the vault's own UI.
+pub const ENCRYPTED_MARKER: &str = "data:application/vnd.vaultmux.encrypted;base64,";
+
+/// Format tag of the key item, also bound to the wrapped key.
+const KEY_FORMAT: &str = "vaultmux-data-key-v1";
+
+/// Key that wraps the data key of an [`EncryptedBackend`].
+///
+/// Either a passphrase, stretched with Argon2id and a random salt kept
+/// beside the wrapped key, or a random 256-bit key. A key is usually
+/// kept in a different backend from the one it protects and read with
+/// [`from_backend()`](KeyEncryptionKey::from_backend).
+#[derive(Clone)]
+pub enum KeyEncryptionKey {
+    /// Derive the key from a passphrase.
+    Passphrase(String),
+    /// Use a random 256-bit key.
+    Key([u8; KEY_LEN]),
+}
+
+impl KeyEncryptionKey {
+    /// Generates a random key.
+    ///
+    /// Store it with [`to_base64()`](KeyEncryptionKey::to_base64), for
+    /// example in another backend read back with
+    /// [`from_backend()`](KeyEncryptionKey::from_backend).
+    pub fn generate() -> Self {
+        Self::Key(crypto::random_bytes())
+    }
+
+    /// Parses a base64-encoded 256-bit key.
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = BASE64.decode(encoded.trim()).map_err(|e| {
+            VaultmuxError::Other(anyhow::anyhow!("invalid key-encryption key: {}", e))
+        })?;
+        let key: [u8; KEY_LEN] = bytes.try_into().map_err(|_| {
+            VaultmuxError::Other(anyhow::anyhow!(
+                "invalid key-encryption key: expected {} bytes",
+                KEY_LEN
+            ))
+        })?;
+        Ok(Self::Key(key))
+    }
+
+    /// Reads a base64-encoded key stored as an item in another backend.
+    pub async fn from_backend<B>(backend: &B, name: &str, session: &dyn Session) -> Result<Self>
+    where
+        B: Backend + ?Sized,
+    {
+        Self::from_base64(&backend.get_notes(name, session).await?)
+    }
+
+    /// Returns a random key as base64, or `None` for a passphrase.
+    pub fn to_base64(&self) -> Option<String> {
+        match self {
+            Self::Key(key) => Some(BASE64.encode(key)),
+            Self::Passphrase(_) => None,
+        }
+    }
+
+    /// Seals `data_key` into the contents of a key item.
+    fn wrap(&self, data_key: &[u8; KEY_LEN]) -> Result<String> {
+        let (key, salt) = match self {
+            Self::Key(key) => (Zeroizing::new(*key), None),
+            Self::Passphrase(passphrase) => {
+                let salt = crypto::random_bytes::<SALT_LEN>();
+                (
+                    Zeroizing::new(crypto::derive_key(passphrase, &salt)?),
+                    Some(BASE64.encode(salt)),
+                )
+            }
+        };
+        let wrapped = crypto::seal(&key, KEY_FORMAT.as_bytes(), data_key)?;
+
+        Ok(serde_json::to_string(&KeyRecord {
+            format: KEY_FORMAT.to_string(),
+            salt,
+            wrapped_key: BASE64.encode(wrapped),
+        })?)
+    }
+
+    /// Recovers the data key from the contents of a key item.
+    fn unwrap(&self, record: &str) -> Result<Zeroizing<[u8; KEY_LEN]>> {
+        let record: KeyRecord = serde_json::from_str(record)?;
+        if record.format != KEY_FORMAT {
+            return Err(VaultmuxError::Other(anyhow::anyhow!(
+                "unsupported data key format: {}",
+                record.format
+            )));
+        }
+
+        let key = match (self, record.salt) {
+            (Self::Key(key), None) => Zeroizing::new(*key),
+            (Self::Passphrase(passphrase), Some(salt)) => {
+                Zeroizing::new(crypto::derive_key(passphrase, &decode(&salt)?)?)
+            }
+            (Self::Key(_), Some(_)) => {
+                return Err(VaultmuxError::Other(anyhow::anyhow!(
+                    "data key was wrapped with a passphrase, not a key"
+                )))
+            }
+            (Self::Passphrase(_), None) => {
+                return Err(VaultmuxError::Other(anyhow::anyhow!(
+                    "data key was wrapped with a key, not a passphrase"
+                )))
+            }
+        };
+
+        let data_key = Zeroizing::new(crypto::open(
+            &key,
+            KEY_FORMAT.as_bytes(),
+            &decode(&record.wrapped_key)?,
+        )?);
+        let data_key: [u8; KEY_LEN] = data_key
+            .as_slice()
+            .try_into()
+            .map_err(|_| VaultmuxError::Other(anyhow::anyhow!("invalid data key length")))?;
+        Ok(Zeroizing::new(data_key))
+    }
+}
+
+impl Drop for KeyEncryptionKey {
+    fn drop(&mut self) {
+        match self {
+            Self::Passphrase(passphrase) => passphrase.zeroize(),
+            Self::Key(key) => key.zeroize(),
+        }
+    }
+}
+
+impl fmt::Debug for KeyEncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Passphrase(_) => f.write_str("KeyEncryptionKey::Passphrase(..)"),
+            Self::Key(_) => f.write_str("KeyEncryptionKey::Key(..)"),
+        }
+    }
+}
+
+/// Contents of the key item.
+#[derive(Serialize, Deserialize)]
+struct KeyRecord {
+    format: String,
+    /// Argon2id salt, present when wrapped with a passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    /// Data key sealed under the key-encryption key
+    wrapped_key: String,
+}
+
+fn decode(encoded: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(encoded.trim())
+        .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("invalid base64: {}", e)))
+}
+
+/// Largest plaintext whose encrypted form fits in `max` stored bytes.
+fn max_plaintext_len(max: usize) -> usize {
+    (max.saturating_sub(ENCRYPTED_MARKER.len()) / 4 * 3).saturating_sub(SEAL_OVERHEAD)
+}
+
+/// Backend that encrypts every value client-side with envelope encryption.
+///
+/// Values are sealed with ChaCha20-Poly1305 under a random data key before
+/// they are handed to the inner backend, and opened again on read, so the
+/// provider and anyone else with access to the vault see only ciphertext.
+/// Each value is bound to its item name: a value copied to another item
+/// fails to decrypt.
+///
+/// The data key is stored in the inner backend itself, in a key item
+/// ([`DEFAULT_KEY_ITEM`] unless changed with
+/// [`with_key_item()`](EncryptedBackend::with_key_item)), wrapped by a
+/// [`KeyEncryptionKey`]. [`authenticate()`](Backend::authenticate)
+/// unwraps it, creating it on first use; until then every read and write
+/// fails with [`VaultmuxError::BackendLocked`]. The key item is hidden
+/// from listings and change streams.
+///
+/// [`rotate_key()`](EncryptedBackend::rotate_key) re-wraps the data key
+/// under a new key-encryption key without touching any item.
+///
+/// The key item cannot be written, tagged, moved or deleted through this
+/// backend; such calls fail with [`VaultmuxError::PermissionDenied`].
+/// The data key is zeroized when the backend is closed or dropped.
+///
+/// Item names, tags, locations and version metadata are not encrypted.
+/// Listings clear the notes of items that were not written through this
+/// backend.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::{EncryptedBackend, KeyEncryptionKey};
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// // The key-encryption key lives in a personal 1Password vault; the
+/// // secrets live in a git-synced pass store.
+/// let mut keys = factory::new_backend(Config::new(BackendType::OnePassword))?;
+/// keys.init().await?;
+/// let keys_session = keys.authenticate().await?;
+/// let key = KeyEncryptionKey::from_backend(&keys, "team-kek", &*keys_session).await?;
+///
+/// let inner = factory::new_backend(Config::new(BackendType::Pass))?;
+/// let mut backend = EncryptedBackend::new(inner, key);
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// backend.create_item("db-password", "hunter2", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct EncryptedBackend<B> {
+    inner: B,
+    key: KeyEncryptionKey,
+    key_item: String,
+    data_key: Option<Zeroizing<[u8; KEY_LEN]>>,
+}
+
+impl<B: Backend> EncryptedBackend<B> {
+    /// Wraps `inner`, protecting its data key with `key`.
+    pub fn new(inner: B, key: KeyEncryptionKey) -> Self {
+        Self {
+            inner,
+            key,
+            key_item: DEFAULT_KEY_ITEM.to_string(),
+            data_key: None,
+        }
+    }
+
+    /// Sets the name of the item holding the wrapped data key.
+    pub fn with_key_item(mut self, name: impl Into<String>) -> Self {
+        self.key_item = name.into();
+        self
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// Loads and unwraps the data key, creating it if the key item is missing.
+    ///
+    /// Called by [`authenticate()`](Backend::authenticate); call it
+    /// directly when the session comes from elsewhere.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the key-encryption key does not match the one the data key
+    /// was wrapped with, or the key item was modified.
+    pub async fn unlock(&mut self, session: &dyn Session) -> Result<()> {
+        let data_key = match self.inner.get_notes(&self.key_item, session).await {
+            Ok(record) => self.key.unwrap(&record)?,
+            Err(VaultmuxError::NotFound(_)) => {
+                let data_key = Zeroizing::new(crypto::random_bytes());
+                let record = self.key.wrap(&data_key)?;
+                match self
+                    .inner
+                    .create_item(&self.key_item, &record, session)
+                    .await
+                {
+                    Ok(()) => data_key,
+                    // Another client created the key first.
+                    Err(VaultmuxError::AlreadyExists(_)) => {
+                        let record = self.inner.get_notes(&self.key_item, session).await?;
+                        self.key.unwrap(&record)?
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.data_key = Some(data_key);
+        Ok(())
+    }
+
+    /// Re-wraps the data key under `new_key`.
+    ///
+    /// Items are not re-encrypted, so this is a single write however many
+    /// items the backend holds. Other clients must switch to `new_key`
+    /// before they next unlock. If the inner backend keeps versions,
+    /// destroy the key item's old versions so the previous key stops
+    /// working. Anyone who already holds the data key itself can still
+    /// read every item.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VaultmuxError::BackendLocked`] if the backend has not been
+    /// unlocked.
+    pub async fn rotate_key(
+        &mut self,
+        new_key: KeyEncryptionKey,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let record = new_key.wrap(self.data_key()?)?;
+        self.inner
+            .update_item(&self.key_item, &record, session)
+            .await?;
+        self.key = new_key;
+        Ok(())
+    }
+
+    fn data_key(&self) -> Result<&[u8; KEY_LEN]> {
+        self.data_key.as_deref().ok_or(VaultmuxError::BackendLocked)
+    }
+
+    fn is_visible(&self, name: &str) -> bool {
+        name != self.key_item
+    }
+
+    /// Rejects changes to the key item, which would lock every item out.
+    fn check_writable(&self, name: &str) -> Result<()> {
+        if self.is_visible(name) {
+            Ok(())
+        } else {
+            Err(VaultmuxError::PermissionDenied(format!(
+                "{} holds the data key",
+                name
+            )))
+        }
+    }
+
+    /// Encrypts the value of item `name`.
+    fn seal(&self, name: &str, plaintext: &str) -> Result<String> {
+        self.check_writable(name)?;
+        let sealed = crypto::seal(self.data_key()?, name.as_bytes(), plaintext.as_bytes())?;
+        Ok(format!("{}{}", ENCRYPTED_MARKER, BASE64.encode(sealed)))
+    }
+
+    /// Decrypts the stored value of item `name`.
+    fn open(&self, name: &str, stored: &str) -> Result<String> {
+        let data_key = self.data_key()?;
+        let encoded = stored
+            .strip_prefix(ENCRYPTED_MARKER)
+            .ok_or_else(|| VaultmuxError::Other(anyhow::anyhow!("{} is not encrypted", name)))?;
+        let plaintext = crypto::open(data_key, name.as_bytes(), &decode(encoded)?)?;
+        String::from_utf8(plaintext).map_err(|_| {
+            VaultmuxError::Other(anyhow::anyhow!("{} decrypted to invalid UTF-8", name))
+        })
+    }
+
+    /// Decrypts the stored value of item `name` and returns its notes.
+    ///
+    /// Values of items written with `create_item_full` hold the encoded
+    /// type and fields as well, so they are decoded like in `get_item`.
+    fn open_notes(&self, name: &str, stored: &str) -> Result<String> {
+        let mut item = Item::new_secure_note(name, "");
+        item.decode_content(self.open(name, stored)?);
+        Ok(item.notes.unwrap_or_default())
+    }
+
+    /// Returns the stored form of `item`, with its type and fields folded
+    /// into the encrypted notes.
+    fn conceal(&self, item: &Item) -> Result<Item> {
+        let content = self.seal(&item.name, &item.encode_content()?)?;
+        Ok(Item {
+            item_type: ItemType::SecureNote,
+            fields: None,
+            notes: Some(content),
+            ..item.clone()
+        })
+    }
+
+    /// Decrypts an item read from the inner backend.
+    fn reveal(&self, mut item: Item) -> Result<Item> {
+        if let Some(notes) = item.notes.take() {
+            let content = self.open(&item.name, &notes)?;
+            item.decode_content(content);
+        }
+        Ok(item)
+    }
+
+    /// Decrypts a listed item, clearing notes that are not encrypted.
+    fn reveal_listed(&self, mut item: Item) -> Result<Item> {
+        if item
+            .notes
+            .as_deref()
+            .is_some_and(|notes| !notes.starts_with(ENCRYPTED_MARKER))
+        {
+            item.notes = None;
+        }
+        self.reveal(item)
+    }
+
+    fn reveal_all(&self, items: Vec<Item>) -> Result<Vec<Item>> {
+        items
+            .into_iter()
+            .filter(|item| self.is_visible(&item.name))
+            .map(|item| self.reveal_listed(item))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Backend for EncryptedBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let inner = self.inner.capabilities();
+        Capabilities {
+            native_binary: false,
+            max_value_size: inner.max_value_size.map(max_plaintext_len),
+            ..inner
+        }
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.inner.init().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.data_key = None;
+        self.inner.close().await
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.data_key.is_some() && self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        let session = self.inner.authenticate().await?;
+        self.unlock(&*session).await?;
+        Ok(session)
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        self.inner.sync(session).await
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        self.reveal(self.inner.get_item(name, session).await?)
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        self.open_notes(name, &self.inner.get_notes(name, session).await?)
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        self.inner.item_exists(name, session).await
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        self.reveal_all(self.inner.list_items(session).await?)
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        self.inner
+            .list_items_stream(options, session)
+            .filter(move |item| {
+                let visible = match item {
+                    Ok(item) => self.is_visible(&item.name),
+                    Err(_) => true,
+                };
+                async move { visible }
+            })
+            .map(move |item| item.and_then(|item| self.reveal_listed(item)))
+            .boxed()
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let sealed = self.seal(name, content)?;
+        self.inner.create_item(name, &sealed, session).await
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let sealed = self.seal(name, content)?;
+        self.inner.update_item(name, &sealed, session).await
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        self.check_writable(name)?;
+        self.inner.delete_item(name, session).await
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        let values = self.inner.get_many(names, session).await?;
+        Ok(names
+            .iter()
+            .zip(values)
+            .map(|(name, value)| value.and_then(|value| self.open_notes(name, &value)))
+            .collect())
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let sealed = entries
+            .iter()
+            .map(|(name, content)| Ok((*name, self.seal(name, content)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let sealed: Vec<(&str, &str)> = sealed
+            .iter()
+            .map(|(name, content)| (*name, content.as_str()))
+            .collect();
+        self.inner.put_many(&sealed, session).await
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        for name in names {
+            self.check_writable(name)?;
+        }
+        self.inner.delete_many(names, session).await
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        let concealed = self.conceal(item)?;
+        self.inner.create_item_full(&concealed, session).await
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        let concealed = self.conceal(item)?;
+        self.inner.update_item_full(&concealed, session).await
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        let sealed = self.seal(name, content)?;
+        self.inner
+            .update_item_if(name, &sealed, expected_revision, session)
+            .await
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        encoding::decode_value(&self.get_notes(name, session).await?)
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        let sealed = self.seal(name, &encoding::encode_binary(data))?;
+        if self.inner.item_exists(name, session).await? {
+            self.inner.update_item(name, &sealed, session).await
+        } else {
+            self.inner.create_item(name, &sealed, session).await
+        }
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        self.inner.list_versions(name, session).await
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        self.open_notes(
+            name,
+            &self.inner.get_version(name, version_id, session).await?,
+        )
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        self.check_writable(name)?;
+        self.inner.disable_version(name, version_id, session).await
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        self.check_writable(name)?;
+        self.inner.destroy_version(name, version_id, session).await
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        self.check_writable(name)?;
+        self.inner.rollback(name, version_id, session).await
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        self.inner.get_tags(name, session).await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        self.check_writable(name)?;
+        self.inner.set_tags(name, tags, session).await
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.reveal_all(self.inner.list_items_by_tag(key, value, session).await?)
+    }
+
+    /// Renames the item, then re-encrypts its value under the new name.
+    ///
+    /// If re-encrypting fails the item is renamed back. Earlier versions
+    /// stay bound to the old name and can no longer be read.
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        self.check_writable(old_name)?;
+        let content = self.open(old_name, &self.inner.get_notes(old_name, session).await?)?;
+        let sealed = self.seal(new_name, &content)?;
+
+        self.inner.rename_item(old_name, new_name, session).await?;
+        if let Err(e) = self.inner.update_item(new_name, &sealed, session).await {
+            let _ = self.inner.rename_item(new_name, old_name, session).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Moves the item, re-encrypting its value if the move renamed it.
+    ///
+    /// Backends that keep the name (Bitwarden, 1Password) need no
+    /// re-encryption. pass renames `a/b/key` to `location/key`, so the
+    /// value is sealed again under that name, moving the item back if this
+    /// fails.
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        self.check_writable(name)?;
+        let leaf = name.rsplit('/').next().unwrap_or(name);
+        let new_name = format!("{}/{}", location.trim_end_matches('/'), leaf);
+        let content = self.open(name, &self.inner.get_notes(name, session).await?)?;
+        let sealed = self.seal(&new_name, &content)?;
+
+        self.inner.move_item(name, location, session).await?;
+        if self.inner.item_exists(name, session).await? {
+            return Ok(());
+        }
+        if let Err(e) = self.inner.update_item(&new_name, &sealed, session).await {
+            let _ = self.inner.rename_item(&new_name, name, session).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        self.check_writable(name)?;
+        self.inner.delete_item_with(name, options, session).await
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        self.inner.list_deleted_items(session).await
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        self.inner.restore_item(name, session).await
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        self.inner
+            .watch(options, session)
+            .filter(move |event| {
+                let visible = match event {
+                    Ok(event) => self.is_visible(&event.name),
+                    Err(_) => true,
+                };
+                async move { visible }
+            })
+            .boxed()
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        self.inner.list_locations(session).await
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        self.inner.location_exists(name, session).await
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        self.inner.create_location(name, session).await
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        let items = self
+            .inner
+            .list_items_in_location(loc_type, loc_value, session)
+            .await?;
+        self.reveal_all(items)
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_encrypted_roundtrip_stores_ciphertext() {
+        let key = KeyEncryptionKey::generate();
+        let mut backend = EncryptedBackend::new(MockBackend::new(), key.clone());
+        let session = backend.authenticate().await.unwrap();
+
+        backend
+            .create_item("api-key", "sk-secret", &*session)
+            .await
+            .unwrap();
+        let login = Item::new_login("db", "admin".to_string(), "hunter2".to_string());
+        backend.create_item_full(&login, &*session).await.unwrap();
+        backend
+            .put_bytes("cert", &[0xde, 0xad], &*session)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            backend.get_notes("api-key", &*session).await.unwrap(),
+            "sk-secret"
+        );
+        let item = backend.get_item("db", &*session).await.unwrap();
+        assert_eq!(item.item_type, ItemType::Login);
+        assert_eq!(item.fields, login.fields);
+        assert_eq!(
+            backend.get_bytes("cert", &*session).await.unwrap(),
+            vec![0xde, 0xad]
+        );
+        let names: Vec<String> = backend
+            .list_items(&*session)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| item.name)
+            .collect();
+        assert!(!names.contains(&DEFAULT_KEY_ITEM.to_string()));
+
+        let inner = backend.into_inner();
+        let stored = inner.get_notes("api-key", &*session).await.unwrap();
+        assert!(stored.starts_with(ENCRYPTED_MARKER));
+        assert!(!stored.contains("sk-secret"));
+        let stored = inner.get_item("db", &*session).await.unwrap();
+        assert_eq!(stored.item_type, ItemType::SecureNote);
+        assert!(!stored.notes.unwrap().contains("hunter2"));
+
+        let mut reopened = EncryptedBackend::new(inner, key);
+        let session = reopened.authenticate().await.unwrap();
+        assert_eq!(
+            reopened.get_notes("api-key", &*session).await.unwrap(),
+            "sk-secret"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_requires_unlock_and_matching_key() {
+        let mut backend = EncryptedBackend::new(MockBackend::new(), KeyEncryptionKey::generate());
+        let session = backend.inner.authenticate().await.unwrap();
+        assert!(matches!(
+            backend.create_item("api-key", "secret", &*session).await,
+            Err(VaultmuxError::BackendLocked)
+        ));
+
+        backend.unlock(&*session).await.unwrap();
+        backend
+            .create_item("api-key", "secret", &*session)
+            .await
+            .unwrap();
+
+        let mut other = EncryptedBackend::new(backend.into_inner(), KeyEncryptionKey::generate());
+        assert!(other.authenticate().await.is_err());
+        assert!(!other.is_authenticated().await);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_key_rewraps_data_key() {
+        let old_key = KeyEncryptionKey::Passphrase("correct horse".to_string());
+        let new_key = KeyEncryptionKey::generate();
+        let mut backend = EncryptedBackend::new(MockBackend::new(), old_key.clone());
+        let session = backend.authenticate().await.unwrap();
+        backend
+            .create_item("api-key", "secret", &*session)
+            .await
+            .unwrap();
+        let before = backend.inner.get_notes("api-key", &*session).await.unwrap();
+
+        backend
+            .rotate_key(new_key.clone(), &*session)
+            .await
+            .unwrap();
+        let inner = backend.into_inner();
+        assert_eq!(inner.get_notes("api-key", &*session).await.unwrap(), before);
+
+        let mut stale = EncryptedBackend::new(inner, old_key);
+        assert!(stale.authenticate().await.is_err());
+        let mut rotated = EncryptedBackend::new(stale.into_inner(), new_key);
+        let session = rotated.authenticate().await.unwrap();
+        assert_eq!(
+            rotated.get_notes("api-key", &*session).await.unwrap(),
+            "secret"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_value_is_bound_to_name() {
+        let mut backend = EncryptedBackend::new(MockBackend::new(), KeyEncryptionKey::generate());
+        let session = backend.authenticate().await.unwrap();
+        backend
+            .create_item("admin-password", "root", &*session)
+            .await
+            .unwrap();
+        backend
+            .create_item("guest-password", "guest", &*session)
+            .await
+            .unwrap();
+
+        // The provider copies one ciphertext over another.
+        let stolen = backend
+            .inner
+            .get_notes("admin-password", &*session)
+            .await
+            .unwrap();
+        backend
+            .inner
+            .update_item("guest-password", &stolen, &*session)
+            .await
+            .unwrap();
+        assert!(backend
+            .get_notes("guest-password", &*session)
+            .await
+            .is_err());
+
+        backend
+            .rename_item("admin-password", "root-password", &*session)
+            .await
+            .unwrap();
+        assert_eq!(
+            backend.get_notes("root-password", &*session).await.unwrap(),
+            "root"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_get_notes_decodes_structured_items() {
+        let mut backend = EncryptedBackend::new(MockBackend::new(), KeyEncryptionKey::generate());
+        let session = backend.authenticate().await.unwrap();
+
+        let mut login = Item::new_login("db", "admin".to_string(), "hunter2".to_string());
+        login.notes = Some("rotate monthly".to_string());
+        backend.create_item_full(&login, &*session).await.unwrap();
+
+        assert_eq!(
+            backend.get_notes("db", &*session).await.unwrap(),
+            "rotate monthly"
+        );
+        let values = backend.get_many(&["db"], &*session).await.unwrap();
+        assert_eq!(values[0].as_ref().unwrap(), "rotate monthly");
+
+        backend
+            .rename_item("db", "db-primary", &*session)
+            .await
+            .unwrap();
+        let item = backend.get_item("db-primary", &*session).await.unwrap();
+        assert_eq!(item.item_type, ItemType::Login);
+        assert_eq!(item.fields, login.fields);
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_get_notes_of_structured_item_without_notes() {
+        let mut backend = EncryptedBackend::new(MockBackend::new(), KeyEncryptionKey::generate());
+        let session = backend.authenticate().await.unwrap();
+
+        let login = Item::new_login("db", "admin".to_string(), "hunter2".to_string());
+        backend.create_item_full(&login, &*session).await.unwrap();
+
+        assert!(backend.item_exists("db", &*session).await.unwrap());
+        assert_eq!(backend.get_notes("db", &*session).await.unwrap(), "");
+        let values = backend.get_many(&["db"], &*session).await.unwrap();
+        assert_eq!(values[0].as_ref().unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_protects_key_item() {
+        let mut backend = EncryptedBackend::new(MockBackend::new(), KeyEncryptionKey::generate());
+        let session = backend.authenticate().await.unwrap();
+        backend
+            .create_item("api-key", "secret", &*session)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            backend.delete_item(DEFAULT_KEY_ITEM, &*session).await,
+            Err(VaultmuxError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            backend
+                .delete_many(&["api-key", DEFAULT_KEY_ITEM], &*session)
+                .await,
+            Err(VaultmuxError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            backend
+                .rename_item(DEFAULT_KEY_ITEM, "stolen", &*session)
+                .await,
+            Err(VaultmuxError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            backend
+                .set_tags(DEFAULT_KEY_ITEM, &HashMap::new(), &*session)
+                .await,
+            Err(VaultmuxError::PermissionDenied(_))
+        ));
+        assert!(backend
+            .inner
+            .item_exists(DEFAULT_KEY_ITEM, &*session)
+            .await
+            .unwrap());
+        assert_eq!(
+            backend.get_notes("api-key", &*session).await.unwrap(),
+            "secret"
+        );
+    }
+}
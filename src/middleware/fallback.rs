@@ -0,0 +1,799 @@
+//! Backend that fails over between several backends.
+
+use super::{is_unavailable, MultiSession};
+use crate::{
+    Backend, Capabilities, DeleteOptions, DeletedItem, Item, ItemVersion, Result, Session,
+    VaultmuxError,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Default number of consecutive failures before a backend is marked unhealthy.
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// Default time between probes of an unhealthy backend (30 seconds).
+const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Item name looked up to probe an unhealthy backend.
+///
+/// The item doesn't need to exist; any answer shows the backend is reachable.
+const PROBE_ITEM: &str = "vaultmux-health-probe";
+
+/// Which backends a [`FallbackBackend`] writes to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Write only to the primary (first) backend
+    ///
+    /// Fallbacks are read-only copies kept up to date some other way. Writes
+    /// fail while the primary is unavailable.
+    #[default]
+    PrimaryOnly,
+
+    /// Write to every backend in order
+    ///
+    /// Every backend is attempted even after one fails, and the first error
+    /// is returned. A failed write can leave some backends updated.
+    All,
+}
+
+/// Backend that reads from the first of several backends that answers.
+///
+/// Each read tries the backends in order, moving on when a backend is
+/// unavailable (a connection, CLI or session failure). Answers about the
+/// item itself, such as [`VaultmuxError::NotFound`], are returned as-is.
+///
+/// A backend that fails `failure_threshold` times in a row is marked
+/// unhealthy and tried only after the healthy ones. A background task probes
+/// it every `probe_interval` and marks it healthy again once it answers.
+/// Backends that fail to initialize, and backends whose session expires,
+/// are marked unhealthy at once; the probe initializes or authenticates
+/// them again.
+///
+/// Writes follow the [`WritePolicy`].
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::{FallbackBackend, WritePolicy};
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// // Use pass while AWS is unreachable
+/// let mut backend = FallbackBackend::new()
+///     .with_backend(factory::new_backend(Config::new(BackendType::AWSSecretsManager))?)
+///     .with_backend(factory::new_backend(Config::new(BackendType::Pass))?)
+///     .with_write_policy(WritePolicy::All);
+///
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// let password = backend.get_notes("db-password", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FallbackBackend {
+    members: Vec<Arc<Member>>,
+    capabilities: Option<Capabilities>,
+    write_policy: WritePolicy,
+    failure_threshold: u32,
+    probe_interval: Duration,
+}
+
+/// An inner backend, its session and its health.
+struct Member {
+    name: String,
+    backend: RwLock<Box<dyn Backend>>,
+    session: Mutex<Option<Arc<dyn Session>>>,
+    needs_init: AtomicBool,
+    failures: AtomicU32,
+    healthy: AtomicBool,
+}
+
+impl Member {
+    fn session(&self) -> Option<Arc<dyn Session>> {
+        self.session.lock().unwrap().clone()
+    }
+
+    fn set_session(&self, session: Option<Arc<dyn Session>>) {
+        *self.session.lock().unwrap() = session;
+    }
+
+    /// Authenticates the backend, first initializing it again if its
+    /// `init()` failed.
+    async fn connect(&self) -> Result<Arc<dyn Session>> {
+        let mut backend = self.backend.write().await;
+        if self.needs_init.load(Ordering::SeqCst) {
+            backend.init().await?;
+            self.needs_init.store(false, Ordering::SeqCst);
+        }
+        let session = backend.authenticate().await?;
+        self.set_session(Some(session.clone()));
+        Ok(session)
+    }
+
+    /// Checks whether the backend answers, authenticating it if needed.
+    async fn probe(&self) -> bool {
+        let session = match self.session() {
+            Some(session) => session,
+            None => match self.connect().await {
+                Ok(session) => session,
+                Err(_) => return false,
+            },
+        };
+
+        let backend = self.backend.read().await;
+        match backend.item_exists(PROBE_ITEM, &*session).await {
+            Err(e) => !is_unavailable(&e),
+            Ok(_) => true,
+        }
+    }
+}
+
+/// Runs a read against each backend in turn until one answers.
+macro_rules! read_in_order {
+    ($self:ident, |$backend:ident, $session:ident| $call:expr) => {{
+        let mut result = Err(VaultmuxError::NotAuthenticated);
+        for member in $self.read_order() {
+            let Some(session) = member.session() else {
+                continue;
+            };
+            let guard = member.backend.read().await;
+            let $backend = &**guard;
+            let $session = &*session;
+
+            result = $call.await;
+            match result {
+                Ok(_) => {
+                    $self.succeeded(&member);
+                    break;
+                }
+                Err(ref e) if is_unavailable(e) => $self.failed(&member, e),
+                // Another backend may support it
+                Err(VaultmuxError::NotSupported(_)) => {}
+                Err(_) => break,
+            }
+        }
+        result
+    }};
+}
+
+/// Runs a write against the backends chosen by the write policy.
+///
+/// Returns the first error, or else the primary's result.
+macro_rules! write_by_policy {
+    ($self:ident, |$backend:ident, $session:ident| $call:expr) => {{
+        let mut result = None;
+        for member in $self.write_targets() {
+            let outcome = match member.session() {
+                Some(session) => {
+                    let mut guard = member.backend.write().await;
+                    let $backend = &mut **guard;
+                    let $session = &*session;
+                    $call.await
+                }
+                None => Err(VaultmuxError::NotAuthenticated),
+            };
+
+            match outcome {
+                Ok(_) => $self.succeeded(member),
+                Err(ref e) if is_unavailable(e) => $self.failed(member, e),
+                Err(_) => {}
+            }
+            if !matches!(result, Some(Err(_))) && (result.is_none() || outcome.is_err()) {
+                result = Some(outcome);
+            }
+        }
+        result.unwrap_or_else(|| Err(no_backends()))
+    }};
+}
+
+impl FallbackBackend {
+    /// Creates a fallback chain with no backends.
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            capabilities: None,
+            write_policy: WritePolicy::default(),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            probe_interval: DEFAULT_PROBE_INTERVAL,
+        }
+    }
+
+    /// Appends a backend to the chain.
+    ///
+    /// The first backend added is the primary.
+    pub fn with_backend(mut self, backend: Box<dyn Backend>) -> Self {
+        let caps = backend.capabilities();
+        self.capabilities = Some(match self.capabilities {
+            Some(existing) => existing.intersection(caps),
+            None => caps,
+        });
+
+        self.members.push(Arc::new(Member {
+            name: backend.name().to_string(),
+            backend: RwLock::new(backend),
+            session: Mutex::new(None),
+            needs_init: AtomicBool::new(false),
+            failures: AtomicU32::new(0),
+            healthy: AtomicBool::new(true),
+        }));
+        self
+    }
+
+    /// Sets which backends receive writes (default: primary only).
+    pub fn with_write_policy(mut self, policy: WritePolicy) -> Self {
+        self.write_policy = policy;
+        self
+    }
+
+    /// Sets how many consecutive failures mark a backend unhealthy (default: 3).
+    pub fn with_failure_threshold(mut self, threshold: u32) -> Self {
+        self.failure_threshold = threshold.max(1);
+        self
+    }
+
+    /// Sets the time between probes of an unhealthy backend (default: 30 seconds).
+    pub fn with_probe_interval(mut self, interval: Duration) -> Self {
+        self.probe_interval = interval;
+        self
+    }
+
+    /// Returns each backend's name and whether it is currently healthy, in
+    /// chain order.
+    pub fn health(&self) -> Vec<(&str, bool)> {
+        self.members
+            .iter()
+            .map(|member| (member.name.as_str(), member.healthy.load(Ordering::SeqCst)))
+            .collect()
+    }
+
+    /// Returns the healthy backends, then the unhealthy ones as a last resort.
+    fn read_order(&self) -> Vec<Arc<Member>> {
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) = self
+            .members
+            .iter()
+            .cloned()
+            .partition(|member| member.healthy.load(Ordering::SeqCst));
+        healthy.into_iter().chain(unhealthy).collect()
+    }
+
+    /// Returns the backends that receive writes.
+    fn write_targets(&self) -> &[Arc<Member>] {
+        match self.write_policy {
+            WritePolicy::PrimaryOnly => &self.members[..self.members.len().min(1)],
+            WritePolicy::All => &self.members,
+        }
+    }
+
+    fn succeeded(&self, member: &Member) {
+        member.failures.store(0, Ordering::SeqCst);
+    }
+
+    /// Records a failure, marking the backend unhealthy and starting a probe
+    /// once it reaches the threshold.
+    ///
+    /// A rejected session is dropped and the backend marked unhealthy at
+    /// once, so that the probe authenticates it again.
+    fn failed(&self, member: &Arc<Member>, err: &VaultmuxError) {
+        if is_session_error(err) {
+            member.set_session(None);
+            self.mark_unhealthy(member);
+            return;
+        }
+        let failures = member.failures.fetch_add(1, Ordering::SeqCst) + 1;
+        if failures >= self.failure_threshold && member.healthy.swap(false, Ordering::SeqCst) {
+            spawn_probe(member, self.probe_interval);
+        }
+    }
+
+    /// Marks a backend unhealthy and starts a probe if it wasn't already.
+    fn mark_unhealthy(&self, member: &Arc<Member>) {
+        member
+            .failures
+            .store(self.failure_threshold, Ordering::SeqCst);
+        if member.healthy.swap(false, Ordering::SeqCst) {
+            spawn_probe(member, self.probe_interval);
+        }
+    }
+}
+
+impl Default for FallbackBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Probes an unhealthy backend until it answers.
+///
+/// The task holds a weak reference, so it stops once the fallback chain is
+/// dropped.
+fn spawn_probe(member: &Arc<Member>, interval: Duration) {
+    let member = Arc::downgrade(member);
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let Some(member) = member.upgrade() else {
+                return;
+            };
+            if member.probe().await {
+                member.failures.store(0, Ordering::SeqCst);
+                member.healthy.store(true, Ordering::SeqCst);
+                return;
+            }
+        }
+    });
+}
+
+/// Returns `true` for errors that mean the backend rejected the session.
+fn is_session_error(err: &VaultmuxError) -> bool {
+    match err {
+        VaultmuxError::SessionExpired | VaultmuxError::NotAuthenticated => true,
+        VaultmuxError::BackendOperation { source, .. } => is_session_error(source),
+        _ => false,
+    }
+}
+
+fn no_backends() -> VaultmuxError {
+    VaultmuxError::Other(anyhow::anyhow!("fallback chain has no backends"))
+}
+
+#[async_trait]
+impl Backend for FallbackBackend {
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.unwrap_or_default()
+    }
+
+    /// Initializes every backend, succeeding if at least one does.
+    ///
+    /// Backends that fail are marked unhealthy and initialized again by the
+    /// background probe.
+    async fn init(&mut self) -> Result<()> {
+        let mut initialized = false;
+        let mut first_error = None;
+
+        for member in &self.members {
+            let result = member.backend.write().await.init().await;
+            match result {
+                Ok(()) => {
+                    member.needs_init.store(false, Ordering::SeqCst);
+                    initialized = true;
+                }
+                Err(e) => {
+                    member.needs_init.store(true, Ordering::SeqCst);
+                    self.mark_unhealthy(member);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if !initialized {
+            return Err(first_error.unwrap_or_else(no_backends));
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let mut first_error = None;
+        for member in &self.members {
+            member.set_session(None);
+            if let Err(e) = member.backend.write().await.close().await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        for member in &self.members {
+            if member.backend.read().await.is_authenticated().await {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Authenticates every backend, succeeding if at least one does.
+    ///
+    /// Backends that fail are marked unhealthy and authenticated again by
+    /// the background probe.
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        let mut sessions = Vec::new();
+        let mut first_error = None;
+
+        for member in &self.members {
+            match member.connect().await {
+                Ok(session) => sessions.push(session),
+                Err(e) => {
+                    self.mark_unhealthy(member);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if sessions.is_empty() {
+            return Err(first_error.unwrap_or_else(no_backends));
+        }
+        Ok(Arc::new(MultiSession::new(sessions)))
+    }
+
+    async fn sync(&mut self, _session: &dyn Session) -> Result<()> {
+        let mut first_error = None;
+        for member in &self.members {
+            let Some(session) = member.session() else {
+                continue;
+            };
+            if let Err(e) = member.backend.write().await.sync(&*session).await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn get_item(&self, name: &str, _session: &dyn Session) -> Result<Item> {
+        read_in_order!(self, |backend, session| backend.get_item(name, session))
+    }
+
+    async fn get_notes(&self, name: &str, _session: &dyn Session) -> Result<String> {
+        read_in_order!(self, |backend, session| backend.get_notes(name, session))
+    }
+
+    async fn item_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
+        read_in_order!(self, |backend, session| backend.item_exists(name, session))
+    }
+
+    async fn list_items(&self, _session: &dyn Session) -> Result<Vec<Item>> {
+        read_in_order!(self, |backend, session| backend.list_items(session))
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .create_item(name, content, session))
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .update_item(name, content, session))
+    }
+
+    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend.delete_item(name, session))
+    }
+
+    async fn get_many(
+        &self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<String>>> {
+        read_in_order!(self, |backend, session| backend.get_many(names, session))
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        write_by_policy!(self, |backend, session| backend.put_many(entries, session))
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        write_by_policy!(self, |backend, session| backend.delete_many(names, session))
+    }
+
+    async fn create_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .create_item_full(item, session))
+    }
+
+    async fn update_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .update_item_full(item, session))
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend.update_item_if(
+            name,
+            content,
+            expected_revision,
+            session
+        ))
+    }
+
+    async fn get_bytes(&self, name: &str, _session: &dyn Session) -> Result<Vec<u8>> {
+        read_in_order!(self, |backend, session| backend.get_bytes(name, session))
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], _session: &dyn Session) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .put_bytes(name, data, session))
+    }
+
+    async fn list_versions(&self, name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        read_in_order!(self, |backend, session| backend
+            .list_versions(name, session))
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<String> {
+        read_in_order!(self, |backend, session| backend
+            .get_version(name, version_id, session))
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .disable_version(name, version_id, session))
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .destroy_version(name, version_id, session))
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .rollback(name, version_id, session))
+    }
+
+    async fn get_tags(
+        &self,
+        name: &str,
+        _session: &dyn Session,
+    ) -> Result<HashMap<String, String>> {
+        read_in_order!(self, |backend, session| backend.get_tags(name, session))
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .set_tags(name, tags, session))
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        _session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        read_in_order!(self, |backend, session| backend
+            .list_items_by_tag(key, value, session))
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .rename_item(old_name, new_name, session))
+    }
+
+    async fn move_item(
+        &mut self,
+        name: &str,
+        location: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .move_item(name, location, session))
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .delete_item_with(name, options, session))
+    }
+
+    async fn list_deleted_items(&self, _session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        read_in_order!(self, |backend, session| backend.list_deleted_items(session))
+    }
+
+    async fn restore_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend.restore_item(name, session))
+    }
+
+    async fn list_locations(&self, _session: &dyn Session) -> Result<Vec<String>> {
+        read_in_order!(self, |backend, session| backend.list_locations(session))
+    }
+
+    async fn location_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
+        read_in_order!(self, |backend, session| backend
+            .location_exists(name, session))
+    }
+
+    async fn create_location(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        write_by_policy!(self, |backend, session| backend
+            .create_location(name, session))
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        _session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        read_in_order!(self, |backend, session| backend
+            .list_items_in_location(loc_type, loc_value, session))
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fallback_reads_and_recovers() {
+        let mut primary = MockBackend::new();
+        primary.get_error = Some(VaultmuxError::CommandFailed("unreachable".to_string()));
+        let secondary = MockBackend::new();
+        secondary.set_item("db-password", "from-pass").await;
+
+        let mut backend = FallbackBackend::new()
+            .with_backend(Box::new(primary))
+            .with_backend(Box::new(secondary))
+            .with_failure_threshold(2)
+            .with_probe_interval(Duration::from_secs(10));
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        for _ in 0..2 {
+            let notes = backend.get_notes("db-password", &*session).await.unwrap();
+            assert_eq!(notes, "from-pass");
+        }
+        assert_eq!(backend.health(), vec![("mock", false), ("mock", true)]);
+
+        // The mock's item_exists ignores get_error, so the probe succeeds
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(backend.health(), vec![("mock", true), ("mock", true)]);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_survives_failed_init() {
+        let mut primary = MockBackend::new();
+        primary.init_error = Some(VaultmuxError::BackendNotInstalled("pass".to_string()));
+        let secondary = MockBackend::new();
+        secondary.set_item("db-password", "from-aws").await;
+
+        let mut backend = FallbackBackend::new()
+            .with_backend(Box::new(primary))
+            .with_backend(Box::new(secondary));
+        backend.init().await.unwrap();
+        assert_eq!(backend.health(), vec![("mock", false), ("mock", true)]);
+
+        let session = backend.authenticate().await.unwrap();
+        let notes = backend.get_notes("db-password", &*session).await.unwrap();
+        assert_eq!(notes, "from-aws");
+
+        let mut failing = MockBackend::new();
+        failing.init_error = Some(VaultmuxError::BackendLocked);
+        let mut backend = FallbackBackend::new().with_backend(Box::new(failing));
+        assert!(matches!(
+            backend.init().await,
+            Err(VaultmuxError::BackendLocked)
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fallback_reauthenticates_expired_session() {
+        let mut primary = MockBackend::new();
+        primary.check_sessions = true;
+        primary.set_item("db-password", "from-primary").await;
+        let secondary = MockBackend::new();
+        secondary.set_item("db-password", "from-secondary").await;
+
+        let mut backend = FallbackBackend::new()
+            .with_backend(Box::new(primary))
+            .with_backend(Box::new(secondary))
+            .with_probe_interval(Duration::from_secs(10));
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+        let notes = backend.get_notes("db-password", &*session).await.unwrap();
+        assert_eq!(notes, "from-primary");
+
+        // A login elsewhere replaces the mock's session, expiring the stored one
+        let primary = &backend.members[0];
+        primary.backend.write().await.authenticate().await.unwrap();
+        let notes = backend.get_notes("db-password", &*session).await.unwrap();
+        assert_eq!(notes, "from-secondary");
+        assert!(primary.session().is_none());
+        assert_eq!(backend.health(), vec![("mock", false), ("mock", true)]);
+
+        // The probe authenticates again and the primary recovers
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(backend.health(), vec![("mock", true), ("mock", true)]);
+        let notes = backend.get_notes("db-password", &*session).await.unwrap();
+        assert_eq!(notes, "from-primary");
+    }
+
+    #[tokio::test]
+    async fn test_fallback_not_found_is_authoritative() {
+        let primary = MockBackend::new();
+        let secondary = MockBackend::new();
+        secondary.set_item("api-key", "stale").await;
+
+        let mut backend = FallbackBackend::new()
+            .with_backend(Box::new(primary))
+            .with_backend(Box::new(secondary));
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        let result = backend.get_notes("api-key", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_write_policy() {
+        let mut backend = FallbackBackend::new()
+            .with_backend(Box::new(MockBackend::new()))
+            .with_backend(Box::new(MockBackend::new()))
+            .with_write_policy(WritePolicy::All);
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        backend
+            .create_item("api-key", "secret", &*session)
+            .await
+            .unwrap();
+
+        for member in &backend.members {
+            let inner = member.backend.read().await;
+            let inner_session = member.session().unwrap();
+            assert!(inner.item_exists("api-key", &*inner_session).await.unwrap());
+        }
+    }
+}
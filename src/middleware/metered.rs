@@ -0,0 +1,585 @@
+//! Backend that records metrics for every operation.
+
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, WatchOptions,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use metrics::{counter, describe_counter, describe_histogram, histogram, Unit};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Counter of operations, labelled by `backend`, `operation` and `outcome`.
+///
+/// `outcome` is `ok` or the kind of error, such as `not_found`, `throttled`
+/// or `timeout`.
+pub const OPERATIONS: &str = "vaultmux_operations_total";
+
+/// Histogram of operation latency in seconds, labelled by `backend` and
+/// `operation`.
+pub const OPERATION_DURATION: &str = "vaultmux_operation_duration_seconds";
+
+/// Counter of authentications, labelled by `backend` and `outcome`.
+pub const AUTHENTICATIONS: &str = "vaultmux_authentications_total";
+
+/// Counter of successful authentications after the first, labelled by
+/// `backend`.
+///
+/// A rising rate usually means sessions are expiring or being rejected.
+pub const REAUTHENTICATIONS: &str = "vaultmux_reauthentications_total";
+
+/// Counter of [`CachedBackend`](super::CachedBackend) lookups, labelled by
+/// `backend` and `result` (`hit`, `stale` or `miss`).
+pub const CACHE_LOOKUPS: &str = "vaultmux_cache_lookups_total";
+
+/// Registers descriptions of the vaultmux metrics with the installed recorder.
+///
+/// Call this once after installing a recorder, such as a Prometheus
+/// exporter, so that scrapes include help text and units.
+pub fn describe_metrics() {
+    describe_counter!(OPERATIONS, "Backend operations by outcome");
+    describe_histogram!(
+        OPERATION_DURATION,
+        Unit::Seconds,
+        "Backend operation latency"
+    );
+    describe_counter!(AUTHENTICATIONS, "Backend authentications by outcome");
+    describe_counter!(REAUTHENTICATIONS, "Backend authentications after the first");
+    describe_counter!(CACHE_LOOKUPS, "Cache lookups by result");
+}
+
+/// Returns the `outcome` label for a result.
+fn outcome<T>(result: &Result<T>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(e) => e.kind(),
+    }
+}
+
+/// Backend that records operation counts, latencies and authentications
+/// through the [`metrics`] facade.
+///
+/// See the constants in this module for the metric names and labels. Error
+/// rates can be alerted on from [`OPERATIONS`] with `outcome != "ok"`, and
+/// cache hit ratios computed from [`CACHE_LOOKUPS`]. Nothing is recorded
+/// unless a recorder is installed.
+///
+/// [`factory::new_backend`](crate::factory::new_backend) applies this
+/// wrapper to every backend when the `metrics` feature is enabled. Streams
+/// from `list_items_stream` and `watch` are not measured.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::metered::describe_metrics;
+/// use vaultmux::middleware::MeteredBackend;
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// // After installing a recorder, e.g. a Prometheus exporter
+/// describe_metrics();
+///
+/// // Already metered; wrapping by hand is only needed for custom backends
+/// let mut backend = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+/// let password = backend.get_notes("db-password", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct MeteredBackend<B> {
+    inner: B,
+    authenticated: AtomicBool,
+}
+
+impl<B: Backend> MeteredBackend<B> {
+    /// Wraps `inner`.
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            authenticated: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn record<T>(&self, operation: &'static str, started: Instant, result: &Result<T>) {
+        let backend = self.inner.name().to_string();
+        histogram!(
+            OPERATION_DURATION,
+            "backend" => backend.clone(),
+            "operation" => operation,
+        )
+        .record(started.elapsed().as_secs_f64());
+        counter!(
+            OPERATIONS,
+            "backend" => backend,
+            "operation" => operation,
+            "outcome" => outcome(result),
+        )
+        .increment(1);
+    }
+}
+
+/// Runs a call and records its outcome and latency.
+macro_rules! metered {
+    ($self:ident, $operation:literal, $call:expr) => {{
+        let started = Instant::now();
+        let result = $call.await;
+        $self.record($operation, started, &result);
+        result
+    }};
+}
+
+#[async_trait]
+impl<B: Backend> Backend for MeteredBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        metered!(self, "init", self.inner.init())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        metered!(self, "close", self.inner.close())
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        let started = Instant::now();
+        let result = self.inner.authenticate().await;
+        self.record("authenticate", started, &result);
+
+        let backend = self.inner.name().to_string();
+        counter!(
+            AUTHENTICATIONS,
+            "backend" => backend.clone(),
+            "outcome" => outcome(&result),
+        )
+        .increment(1);
+        if result.is_ok() && self.authenticated.swap(true, Ordering::SeqCst) {
+            counter!(REAUTHENTICATIONS, "backend" => backend).increment(1);
+        }
+        result
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        metered!(self, "sync", self.inner.sync(session))
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        metered!(self, "get_item", self.inner.get_item(name, session))
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        metered!(self, "get_notes", self.inner.get_notes(name, session))
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        metered!(self, "item_exists", self.inner.item_exists(name, session))
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        metered!(self, "list_items", self.inner.list_items(session))
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        self.inner.list_items_stream(options, session)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(
+            self,
+            "create_item",
+            self.inner.create_item(name, content, session)
+        )
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(
+            self,
+            "update_item",
+            self.inner.update_item(name, content, session)
+        )
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        metered!(self, "delete_item", self.inner.delete_item(name, session))
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        metered!(self, "get_many", self.inner.get_many(names, session))
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        metered!(self, "put_many", self.inner.put_many(entries, session))
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        metered!(self, "delete_many", self.inner.delete_many(names, session))
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        metered!(
+            self,
+            "create_item_full",
+            self.inner.create_item_full(item, session)
+        )
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        metered!(
+            self,
+            "update_item_full",
+            self.inner.update_item_full(item, session)
+        )
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(
+            self,
+            "update_item_if",
+            self.inner
+                .update_item_if(name, content, expected_revision, session)
+        )
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        metered!(self, "get_bytes", self.inner.get_bytes(name, session))
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        metered!(self, "put_bytes", self.inner.put_bytes(name, data, session))
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        metered!(
+            self,
+            "list_versions",
+            self.inner.list_versions(name, session)
+        )
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        metered!(
+            self,
+            "get_version",
+            self.inner.get_version(name, version_id, session)
+        )
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(
+            self,
+            "disable_version",
+            self.inner.disable_version(name, version_id, session)
+        )
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(
+            self,
+            "destroy_version",
+            self.inner.destroy_version(name, version_id, session)
+        )
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(
+            self,
+            "rollback",
+            self.inner.rollback(name, version_id, session)
+        )
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        metered!(self, "get_tags", self.inner.get_tags(name, session))
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(self, "set_tags", self.inner.set_tags(name, tags, session))
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        metered!(
+            self,
+            "list_items_by_tag",
+            self.inner.list_items_by_tag(key, value, session)
+        )
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(
+            self,
+            "rename_item",
+            self.inner.rename_item(old_name, new_name, session)
+        )
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        metered!(
+            self,
+            "move_item",
+            self.inner.move_item(name, location, session)
+        )
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        metered!(
+            self,
+            "delete_item_with",
+            self.inner.delete_item_with(name, options, session)
+        )
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        metered!(
+            self,
+            "list_deleted_items",
+            self.inner.list_deleted_items(session)
+        )
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        metered!(self, "restore_item", self.inner.restore_item(name, session))
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        self.inner.watch(options, session)
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        metered!(self, "list_locations", self.inner.list_locations(session))
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        metered!(
+            self,
+            "location_exists",
+            self.inner.location_exists(name, session)
+        )
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        metered!(
+            self,
+            "create_location",
+            self.inner.create_location(name, session)
+        )
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        metered!(
+            self,
+            "list_items_in_location",
+            self.inner
+                .list_items_in_location(loc_type, loc_value, session)
+        )
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+    use crate::VaultmuxError;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshot};
+
+    #[test]
+    fn test_outcome_labels() {
+        assert_eq!(outcome(&Ok(())), "ok");
+        assert_eq!(
+            outcome::<()>(&Err(VaultmuxError::backend_op(
+                "aws",
+                "get",
+                "api-key",
+                VaultmuxError::Throttled("Rate exceeded".to_string()),
+            ))),
+            "throttled"
+        );
+    }
+
+    type Labels = Vec<(String, String)>;
+
+    /// Returns the counters in `snapshot` as `(name, labels, value)`, sorted.
+    fn counters(snapshot: Snapshot) -> Vec<(String, Labels, u64)> {
+        let mut counters: Vec<_> = snapshot
+            .into_vec()
+            .into_iter()
+            .filter_map(|(key, _, _, value)| match value {
+                DebugValue::Counter(count) => {
+                    let key = key.key();
+                    let labels = key
+                        .labels()
+                        .map(|l| (l.key().to_string(), l.value().to_string()))
+                        .collect();
+                    Some((key.name().to_string(), labels, count))
+                }
+                _ => None,
+            })
+            .collect();
+        counters.sort();
+        counters
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_metered_backend_records_counters() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let mut inner = MockBackend::new();
+                inner.auth_error = Some(VaultmuxError::NotAuthenticated);
+                let mut backend = MeteredBackend::new(inner);
+
+                // A failed first attempt doesn't make the next one a reauthentication
+                assert!(backend.authenticate().await.is_err());
+                backend.inner.auth_error = None;
+                backend.authenticate().await.unwrap();
+                let session = backend.authenticate().await.unwrap();
+                assert!(backend.get_notes("missing", &*session).await.is_err());
+            })
+        });
+
+        let operation = |name: &str, outcome: &str| {
+            labels(&[
+                ("backend", "mock"),
+                ("operation", name),
+                ("outcome", outcome),
+            ])
+        };
+        assert_eq!(
+            counters(snapshotter.snapshot()),
+            vec![
+                (
+                    AUTHENTICATIONS.to_string(),
+                    labels(&[("backend", "mock"), ("outcome", "not_authenticated")]),
+                    1
+                ),
+                (
+                    AUTHENTICATIONS.to_string(),
+                    labels(&[("backend", "mock"), ("outcome", "ok")]),
+                    2
+                ),
+                (
+                    OPERATIONS.to_string(),
+                    operation("authenticate", "not_authenticated"),
+                    1
+                ),
+                (OPERATIONS.to_string(), operation("authenticate", "ok"), 2),
+                (
+                    OPERATIONS.to_string(),
+                    operation("get_notes", "not_found"),
+                    1
+                ),
+                (
+                    REAUTHENTICATIONS.to_string(),
+                    labels(&[("backend", "mock")]),
+                    1
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,656 @@
+//! Backend that replicates writes to several backends.
+
+use super::MultiSession;
+use crate::{
+    Backend, Capabilities, DeleteOptions, DeletedItem, Item, ItemVersion, ReplicaFailure, Result,
+    Session, VaultmuxError,
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Backend that writes to a primary and copies each write to replicas.
+///
+/// Writes go to the primary first; if it fails, the replicas are left
+/// untouched. Otherwise every replica gets the same write, and replicas that
+/// fail are reported together as [`VaultmuxError::ReplicaDivergence`]. The
+/// primary keeps the write either way.
+///
+/// Reads, version history and conditional checks use the primary only.
+/// [`update_item_if`](Backend::update_item_if) checks the revision on the
+/// primary and updates replicas unconditionally, and version operations
+/// other than [`rollback`](Backend::rollback) don't reach replicas.
+///
+/// Use [`verify()`](MirrorBackend::verify) to find replicas that drifted.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::MirrorBackend;
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let mut backend = MirrorBackend::new(factory::new_backend(Config::new(
+///     BackendType::AWSSecretsManager,
+/// ))?)
+/// .with_replica(
+///     "break-glass",
+///     factory::new_backend(Config::new(BackendType::OnePassword))?,
+/// );
+///
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// // Written to AWS, then copied to 1Password
+/// backend.create_item("db-password", "hunter2", &*session).await?;
+///
+/// for divergence in backend.verify().await? {
+///     eprintln!("{:?}", divergence);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct MirrorBackend {
+    primary: Replica,
+    replicas: Vec<Replica>,
+}
+
+/// A backend in the mirror set and its session.
+struct Replica {
+    label: String,
+    backend: Box<dyn Backend>,
+    session: Option<Arc<dyn Session>>,
+}
+
+/// How a replica differs from the primary, as found by
+/// [`MirrorBackend::verify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// The item is on the primary but not the replica
+    Missing,
+    /// The item's value differs between the primary and the replica
+    Different,
+    /// The item is on the replica but not the primary
+    Extra,
+}
+
+/// An item that differs between the primary and a replica.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Item name
+    pub name: String,
+
+    /// Label of the replica
+    pub replica: String,
+
+    /// How the replica differs
+    pub kind: DivergenceKind,
+}
+
+/// Applies a write to every replica, collecting the ones that fail.
+macro_rules! replicate {
+    ($self:ident, $item:expr, |$backend:ident, $session:ident| $call:expr) => {{
+        let mut failures = Vec::new();
+        for replica in &mut $self.replicas {
+            let outcome = match replica.session.clone() {
+                Some(session) => {
+                    let $backend = &mut *replica.backend;
+                    let $session = &*session;
+                    $call.await
+                }
+                None => Err(VaultmuxError::NotAuthenticated),
+            };
+            if let Err(error) = outcome {
+                failures.push(ReplicaFailure {
+                    replica: replica.label.clone(),
+                    error,
+                });
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(VaultmuxError::ReplicaDivergence {
+                item: $item.to_string(),
+                failures,
+            })
+        }
+    }};
+}
+
+impl MirrorBackend {
+    /// Creates a mirror with `primary` as the source of truth.
+    pub fn new(primary: Box<dyn Backend>) -> Self {
+        Self {
+            primary: Replica {
+                label: "primary".to_string(),
+                backend: primary,
+                session: None,
+            },
+            replicas: Vec::new(),
+        }
+    }
+
+    /// Adds a replica under `label`, used to report its failures.
+    pub fn with_replica(mut self, label: impl Into<String>, backend: Box<dyn Backend>) -> Self {
+        self.replicas.push(Replica {
+            label: label.into(),
+            backend,
+            session: None,
+        });
+        self
+    }
+
+    /// Compares every item's value on the primary with each replica.
+    ///
+    /// Values are compared as returned by
+    /// [`get_many`](Backend::get_many). Returns the differences ordered by
+    /// replica, then item name; an empty list means every replica matches.
+    pub async fn verify(&self) -> Result<Vec<Divergence>> {
+        let expected = values(&self.primary).await?;
+
+        let mut divergences = Vec::new();
+        for replica in &self.replicas {
+            let actual = values(replica).await?;
+            let mut names: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+            names.sort();
+            names.dedup();
+
+            for name in names {
+                let kind = match (expected.get(name), actual.get(name)) {
+                    (Some(_), None) => DivergenceKind::Missing,
+                    (None, Some(_)) => DivergenceKind::Extra,
+                    (Some(a), Some(b)) if a != b => DivergenceKind::Different,
+                    _ => continue,
+                };
+                divergences.push(Divergence {
+                    name: name.clone(),
+                    replica: replica.label.clone(),
+                    kind,
+                });
+            }
+        }
+        Ok(divergences)
+    }
+
+    /// Returns the primary backend and its session.
+    fn primary(&mut self) -> Result<(&mut dyn Backend, Arc<dyn Session>)> {
+        let session = self
+            .primary
+            .session
+            .clone()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+        Ok((self.primary.backend.as_mut(), session))
+    }
+
+    /// Returns the primary's session, for reads.
+    fn primary_session(&self) -> Result<&dyn Session> {
+        self.primary
+            .session
+            .as_deref()
+            .ok_or(VaultmuxError::NotAuthenticated)
+    }
+
+    fn members_mut(&mut self) -> impl Iterator<Item = &mut Replica> {
+        std::iter::once(&mut self.primary).chain(self.replicas.iter_mut())
+    }
+}
+
+/// Reads every item's value from one backend of the mirror set.
+///
+/// Items without a text value map to `None`.
+async fn values(replica: &Replica) -> Result<BTreeMap<String, Option<String>>> {
+    let session = replica
+        .session
+        .as_deref()
+        .ok_or(VaultmuxError::NotAuthenticated)?;
+
+    let items = replica.backend.list_items(session).await?;
+    let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+    let results = replica.backend.get_many(&names, session).await?;
+
+    let mut values = BTreeMap::new();
+    for (name, result) in names.into_iter().zip(results) {
+        let value = match result {
+            Ok(value) => Some(value),
+            Err(VaultmuxError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        values.insert(name.to_string(), value);
+    }
+    Ok(values)
+}
+
+/// Treats a missing item as already deleted.
+fn ignore_missing(result: Result<()>) -> Result<()> {
+    match result {
+        Err(VaultmuxError::NotFound(_)) => Ok(()),
+        other => other,
+    }
+}
+
+#[async_trait]
+impl Backend for MirrorBackend {
+    fn name(&self) -> &str {
+        "mirror"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let primary = self.primary.backend.capabilities();
+        let mut caps = self
+            .replicas
+            .iter()
+            .map(|replica| replica.backend.capabilities())
+            .fold(primary, Capabilities::intersection);
+
+        // Reads and version history only touch the primary
+        caps.versioning = primary.versioning;
+        caps.disable_versions = primary.disable_versions;
+        caps.destroy_versions = primary.destroy_versions;
+        caps.read_tags = primary.read_tags;
+        caps.compare_and_swap = primary.compare_and_swap;
+        caps
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        for member in self.members_mut() {
+            member.backend.init().await?;
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let mut first_error = None;
+        for member in self.members_mut() {
+            member.session = None;
+            if let Err(e) = member.backend.close().await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        if !self.primary.backend.is_authenticated().await {
+            return false;
+        }
+        for replica in &self.replicas {
+            if !replica.backend.is_authenticated().await {
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        let mut sessions = Vec::new();
+        for member in self.members_mut() {
+            let session = member.backend.authenticate().await?;
+            member.session = Some(session.clone());
+            sessions.push(session);
+        }
+        Ok(Arc::new(MultiSession::new(sessions)))
+    }
+
+    async fn sync(&mut self, _session: &dyn Session) -> Result<()> {
+        for member in self.members_mut() {
+            let session = member
+                .session
+                .clone()
+                .ok_or(VaultmuxError::NotAuthenticated)?;
+            member.backend.sync(&*session).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_item(&self, name: &str, _session: &dyn Session) -> Result<Item> {
+        self.primary
+            .backend
+            .get_item(name, self.primary_session()?)
+            .await
+    }
+
+    async fn get_notes(&self, name: &str, _session: &dyn Session) -> Result<String> {
+        self.primary
+            .backend
+            .get_notes(name, self.primary_session()?)
+            .await
+    }
+
+    async fn item_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
+        self.primary
+            .backend
+            .item_exists(name, self.primary_session()?)
+            .await
+    }
+
+    async fn list_items(&self, _session: &dyn Session) -> Result<Vec<Item>> {
+        self.primary
+            .backend
+            .list_items(self.primary_session()?)
+            .await
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.create_item(name, content, &*session).await?;
+        replicate!(self, name, |backend, session| backend
+            .create_item(name, content, session))
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.update_item(name, content, &*session).await?;
+        replicate!(self, name, |backend, session| backend
+            .update_item(name, content, session))
+    }
+
+    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.delete_item(name, &*session).await?;
+        replicate!(self, name, |backend, session| async {
+            ignore_missing(backend.delete_item(name, session).await)
+        })
+    }
+
+    async fn get_many(
+        &self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<String>>> {
+        self.primary
+            .backend
+            .get_many(names, self.primary_session()?)
+            .await
+    }
+
+    async fn create_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.create_item_full(item, &*session).await?;
+        replicate!(self, item.name, |backend, session| backend
+            .create_item_full(item, session))
+    }
+
+    async fn update_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.update_item_full(item, &*session).await?;
+        replicate!(self, item.name, |backend, session| backend
+            .update_item_full(item, session))
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        // Revisions are per backend, so only the primary can check one
+        let (primary, session) = self.primary()?;
+        primary
+            .update_item_if(name, content, expected_revision, &*session)
+            .await?;
+        replicate!(self, name, |backend, session| backend
+            .update_item(name, content, session))
+    }
+
+    async fn get_bytes(&self, name: &str, _session: &dyn Session) -> Result<Vec<u8>> {
+        self.primary
+            .backend
+            .get_bytes(name, self.primary_session()?)
+            .await
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], _session: &dyn Session) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.put_bytes(name, data, &*session).await?;
+        replicate!(self, name, |backend, session| backend
+            .put_bytes(name, data, session))
+    }
+
+    async fn list_versions(&self, name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        self.primary
+            .backend
+            .list_versions(name, self.primary_session()?)
+            .await
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<String> {
+        self.primary
+            .backend
+            .get_version(name, version_id, self.primary_session()?)
+            .await
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.disable_version(name, version_id, &*session).await
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.destroy_version(name, version_id, &*session).await
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        // Version IDs are per backend, so replicas get the restored value
+        let (primary, session) = self.primary()?;
+        primary.rollback(name, version_id, &*session).await?;
+        let value = primary.get_notes(name, &*session).await?;
+
+        replicate!(self, name, |backend, session| backend
+            .update_item(name, &value, session))
+    }
+
+    async fn get_tags(
+        &self,
+        name: &str,
+        _session: &dyn Session,
+    ) -> Result<HashMap<String, String>> {
+        self.primary
+            .backend
+            .get_tags(name, self.primary_session()?)
+            .await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.set_tags(name, tags, &*session).await?;
+        replicate!(self, name, |backend, session| backend
+            .set_tags(name, tags, session))
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        _session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.primary
+            .backend
+            .list_items_by_tag(key, value, self.primary_session()?)
+            .await
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.rename_item(old_name, new_name, &*session).await?;
+        replicate!(self, old_name, |backend, session| backend
+            .rename_item(old_name, new_name, session))
+    }
+
+    async fn move_item(
+        &mut self,
+        name: &str,
+        location: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.move_item(name, location, &*session).await?;
+        replicate!(self, name, |backend, session| backend
+            .move_item(name, location, session))
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.delete_item_with(name, options, &*session).await?;
+        replicate!(self, name, |backend, session| async {
+            ignore_missing(backend.delete_item_with(name, options, session).await)
+        })
+    }
+
+    async fn list_deleted_items(&self, _session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        self.primary
+            .backend
+            .list_deleted_items(self.primary_session()?)
+            .await
+    }
+
+    async fn restore_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.restore_item(name, &*session).await?;
+        replicate!(self, name, |backend, session| backend
+            .restore_item(name, session))
+    }
+
+    async fn list_locations(&self, _session: &dyn Session) -> Result<Vec<String>> {
+        self.primary
+            .backend
+            .list_locations(self.primary_session()?)
+            .await
+    }
+
+    async fn location_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
+        self.primary
+            .backend
+            .location_exists(name, self.primary_session()?)
+            .await
+    }
+
+    async fn create_location(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        let (primary, session) = self.primary()?;
+        primary.create_location(name, &*session).await?;
+        replicate!(self, name, |backend, session| backend
+            .create_location(name, session))
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        _session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.primary
+            .backend
+            .list_items_in_location(loc_type, loc_value, self.primary_session()?)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_mirror_reports_divergence() {
+        let mut broken = MockBackend::new();
+        broken.create_error = Some(VaultmuxError::PermissionDenied("read-only".to_string()));
+
+        let mut backend = MirrorBackend::new(Box::new(MockBackend::new()))
+            .with_replica("backup", Box::new(MockBackend::new()))
+            .with_replica("break-glass", Box::new(broken));
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        let err = backend
+            .create_item("api-key", "secret", &*session)
+            .await
+            .unwrap_err();
+        match err {
+            VaultmuxError::ReplicaDivergence { item, failures } => {
+                assert_eq!(item, "api-key");
+                assert_eq!(failures.len(), 1);
+                assert_eq!(failures[0].replica, "break-glass");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+
+        // The primary keeps the write
+        assert_eq!(
+            backend.get_notes("api-key", &*session).await.unwrap(),
+            "secret"
+        );
+
+        let divergences = backend.verify().await.unwrap();
+        assert_eq!(
+            divergences,
+            vec![Divergence {
+                name: "api-key".to_string(),
+                replica: "break-glass".to_string(),
+                kind: DivergenceKind::Missing,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mirror_verify_in_sync() {
+        let mut backend = MirrorBackend::new(Box::new(MockBackend::new()))
+            .with_replica("backup", Box::new(MockBackend::new()));
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        backend.create_item("a", "1", &*session).await.unwrap();
+        backend.update_item("a", "2", &*session).await.unwrap();
+        backend.create_item("b", "1", &*session).await.unwrap();
+        backend.delete_item("b", &*session).await.unwrap();
+
+        assert!(backend.verify().await.unwrap().is_empty());
+    }
+}
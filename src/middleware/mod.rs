@@ -0,0 +1,133 @@
+//! Backends that wrap or combine other backends.
+//!
+//! Each type here implements [`Backend`](crate::Backend) itself, so it can be
+//! used anywhere a backend from [`factory::new_backend`](crate::factory::new_backend)
+//! can.
+//!
+//! - [`AuditedBackend`]: Records every operation to an audit log
+//! - [`CachedBackend`]: Caches reads from another backend in memory
+//! - [`RouterBackend`]: Sends each item to one of several backends by name
+//! - [`DryRunBackend`]: Records writes as a plan instead of applying them
+//! - [`FallbackBackend`]: Reads from the first of several backends that answers
+//! - [`MirrorBackend`]: Copies every write from a primary to replicas
+//! - [`PolicyBackend`]: Checks each call against allow and deny rules
+//! - [`RateLimitedBackend`]: Limits the rate and concurrency of calls to a backend
+//! - [`ReadOnlyBackend`]: Rejects every write
+//! - [`RetryBackend`]: Retries operations that fail with a transient error
+//! - [`TimeoutBackend`]: Bounds how long each operation may take
+//! - `EncryptedBackend`: Encrypts values before they reach another backend
+//!   (requires the `encryption` feature)
+//! - `SnapshotBackend`: Serves reads from an encrypted snapshot while offline
+//!   (requires the `snapshot` feature)
+//! - `MeteredBackend`: Records operation metrics (requires the `metrics`
+//!   feature)
+//! - `TracedBackend`: Wraps every operation in a `tracing` span (requires the
+//!   `tracing` feature)
+
+pub mod audit;
+pub mod cached;
+pub mod dry_run;
+#[cfg(feature = "encryption")]
+pub mod encrypted;
+pub mod fallback;
+#[cfg(feature = "metrics")]
+pub mod metered;
+pub mod mirror;
+pub mod policy;
+pub mod rate_limit;
+pub mod read_only;
+pub mod retry;
+pub mod router;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+pub mod timeout;
+#[cfg(feature = "tracing")]
+pub mod traced;
+
+pub use audit::{
+    AuditContext, AuditEntry, AuditOutcome, AuditSink, AuditedBackend, JsonLinesSink, MemorySink,
+};
+pub use cached::CachedBackend;
+pub use dry_run::{DryRunBackend, PlannedChange};
+#[cfg(feature = "encryption")]
+pub use encrypted::{EncryptedBackend, KeyEncryptionKey};
+pub use fallback::{FallbackBackend, WritePolicy};
+#[cfg(feature = "metrics")]
+pub use metered::MeteredBackend;
+pub use mirror::{Divergence, DivergenceKind, MirrorBackend};
+pub use policy::{Access, Effect, Policy, PolicyBackend, Rule};
+pub use rate_limit::{RateLimit, RateLimitedBackend, RateLimiter};
+pub use read_only::ReadOnlyBackend;
+pub use retry::{RetryBackend, RetryPolicy};
+pub use router::RouterBackend;
+#[cfg(feature = "snapshot")]
+pub use snapshot::{Snapshot, SnapshotBackend, SnapshotKey};
+pub use timeout::TimeoutBackend;
+#[cfg(feature = "tracing")]
+pub use traced::TracedBackend;
+
+use crate::{Result, Session, VaultmuxError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+/// Session returned by backends that authenticate several inner backends.
+///
+/// The inner sessions are kept by the combining backend itself, which
+/// ignores the session passed to each call.
+pub(crate) struct MultiSession {
+    sessions: Vec<Arc<dyn Session>>,
+}
+
+impl MultiSession {
+    pub(crate) fn new(sessions: Vec<Arc<dyn Session>>) -> Self {
+        Self { sessions }
+    }
+}
+
+#[async_trait]
+impl Session for MultiSession {
+    fn token(&self) -> &str {
+        ""
+    }
+
+    async fn is_valid(&self) -> bool {
+        for session in &self.sessions {
+            if !session.is_valid().await {
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn refresh(&mut self) -> Result<()> {
+        // Inner sessions are shared, so expired ones need a new authenticate()
+        if self.is_valid().await {
+            Ok(())
+        } else {
+            Err(VaultmuxError::SessionExpired)
+        }
+    }
+
+    fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.sessions.iter().filter_map(|s| s.expires_at()).min()
+    }
+}
+
+/// Returns `true` for errors that say the backend couldn't answer, rather
+/// than giving an answer about the item.
+pub(crate) fn is_unavailable(err: &VaultmuxError) -> bool {
+    match err {
+        VaultmuxError::NotAuthenticated
+        | VaultmuxError::SessionExpired
+        | VaultmuxError::BackendNotInstalled(_)
+        | VaultmuxError::BackendLocked
+        | VaultmuxError::Io(_)
+        | VaultmuxError::CommandFailed(_)
+        | VaultmuxError::Throttled(_)
+        | VaultmuxError::Timeout(_)
+        | VaultmuxError::Other(_) => true,
+        VaultmuxError::BackendOperation { source, .. } => is_unavailable(source),
+        _ => false,
+    }
+}
@@ -0,0 +1,1014 @@
+//! Backend that checks each call against an access policy.
+
+use crate::pattern::glob_match;
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, VaultmuxError, WatchOptions,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Kind of access an operation needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    /// Reading values, tags and versions of named items
+    Read,
+    /// Seeing which items exist (listings and watches)
+    List,
+    /// Creating, updating, renaming, moving and restoring items
+    Write,
+    /// Deleting items (including the source of a rename) and destroying
+    /// versions
+    Delete,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::List => write!(f, "list"),
+            Self::Write => write!(f, "write"),
+            Self::Delete => write!(f, "delete"),
+        }
+    }
+}
+
+/// Whether a rule allows or denies what it matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Effect {
+    /// Permit the access
+    Allow,
+    /// Refuse the access
+    #[default]
+    Deny,
+}
+
+/// One allow or deny rule of a [`Policy`].
+///
+/// A rule matches a call when each of its lists is empty or contains the
+/// call's principal, access kind and item name respectively. Item patterns
+/// are globs (see [`glob_match`]), so a prefix is written `db/*`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+    /// Name shown in denial messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Whether matching calls are allowed or denied
+    pub effect: Effect,
+    /// Principals the rule applies to (empty: all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub principals: Vec<String>,
+    /// Access kinds the rule applies to (empty: all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operations: Vec<Access>,
+    /// Item name globs the rule applies to (empty: all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<String>,
+}
+
+impl Rule {
+    /// Creates a rule allowing everything, to be narrowed with the builder
+    /// methods.
+    pub fn allow() -> Self {
+        Self::with_effect(Effect::Allow)
+    }
+
+    /// Creates a rule denying everything, to be narrowed with the builder
+    /// methods.
+    pub fn deny() -> Self {
+        Self::with_effect(Effect::Deny)
+    }
+
+    fn with_effect(effect: Effect) -> Self {
+        Self {
+            name: None,
+            effect,
+            principals: Vec::new(),
+            operations: Vec::new(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Sets the name shown in denial messages.
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Limits the rule to the given principals.
+    pub fn for_principals<S: Into<String>>(
+        mut self,
+        principals: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.principals = principals.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Limits the rule to the given access kinds.
+    pub fn on(mut self, operations: impl IntoIterator<Item = Access>) -> Self {
+        self.operations = operations.into_iter().collect();
+        self
+    }
+
+    /// Limits the rule to items matching the given globs.
+    pub fn items<S: Into<String>>(mut self, patterns: impl IntoIterator<Item = S>) -> Self {
+        self.items = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn matches(&self, principal: Option<&str>, access: Access, item: &str) -> bool {
+        let principal_matches = self.principals.is_empty()
+            || principal.is_some_and(|p| self.principals.iter().any(|allowed| allowed == p));
+        principal_matches
+            && (self.operations.is_empty() || self.operations.contains(&access))
+            && (self.items.is_empty() || self.items.iter().any(|glob| glob_match(glob, item)))
+    }
+}
+
+/// A set of allow and deny rules.
+///
+/// A call is denied if any matching rule denies it, otherwise allowed if any
+/// matching rule allows it, and otherwise given the `default` effect (deny
+/// unless set). Rule order doesn't matter.
+///
+/// Policies can be written in JSON, or in TOML with the `toml` feature:
+///
+/// ```toml
+/// default = "deny"
+///
+/// [[rule]]
+/// name = "billing-reads-db"
+/// effect = "allow"
+/// principals = ["billing"]
+/// operations = ["read", "list"]
+/// items = ["db/*"]
+///
+/// [[rule]]
+/// name = "no-prod-deletes"
+/// effect = "deny"
+/// operations = ["delete"]
+/// items = ["prod/*"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Policy {
+    /// Effect for calls no rule matches
+    #[serde(default)]
+    pub default: Effect,
+    /// The rules
+    #[serde(default, rename = "rule", alias = "rules")]
+    pub rules: Vec<Rule>,
+}
+
+impl Policy {
+    /// Creates a policy with no rules that denies everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule.
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Sets the effect for calls no rule matches (default: deny).
+    pub fn with_default(mut self, effect: Effect) -> Self {
+        self.default = effect;
+        self
+    }
+
+    /// Parses a policy from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Parses a policy from TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml)
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("invalid policy: {}", e)))
+    }
+
+    /// Loads a policy file, choosing the format by its extension.
+    ///
+    /// `.toml` files require the `toml` feature; anything else is read as
+    /// JSON.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            #[cfg(feature = "toml")]
+            return Self::from_toml(&contents);
+            #[cfg(not(feature = "toml"))]
+            return Err(VaultmuxError::NotSupported(
+                "TOML policies require the `toml` feature".to_string(),
+            ));
+        }
+        Self::from_json(&contents)
+    }
+
+    /// Checks whether `principal` may perform `access` on `item`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VaultmuxError::PermissionDenied`] naming the rule that
+    /// denied the call, or saying that no rule allowed it.
+    pub fn check(
+        &self,
+        principal: Option<&str>,
+        access: Access,
+        operation: &str,
+        item: &str,
+    ) -> Result<()> {
+        let mut allowed = self.default == Effect::Allow;
+        for (index, rule) in self.rules.iter().enumerate() {
+            if !rule.matches(principal, access, item) {
+                continue;
+            }
+            match rule.effect {
+                Effect::Deny => {
+                    return Err(denied(
+                        principal,
+                        operation,
+                        item,
+                        &format!("denied by {}", describe_rule(rule, index)),
+                    ))
+                }
+                Effect::Allow => allowed = true,
+            }
+        }
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(denied(
+                principal,
+                operation,
+                item,
+                &format!("no rule allows {} access", access),
+            ))
+        }
+    }
+
+    fn permits(&self, principal: Option<&str>, access: Access, item: &str) -> bool {
+        self.check(principal, access, "", item).is_ok()
+    }
+}
+
+fn describe_rule(rule: &Rule, index: usize) -> String {
+    match &rule.name {
+        Some(name) => format!("rule \"{}\"", name),
+        None => format!("rule #{}", index + 1),
+    }
+}
+
+fn denied(principal: Option<&str>, operation: &str, item: &str, reason: &str) -> VaultmuxError {
+    let who = principal.map(|p| format!("{} ", p)).unwrap_or_default();
+    VaultmuxError::PermissionDenied(format!("{}{} {}: {}", who, operation, item, reason))
+}
+
+/// Backend that checks every call against a [`Policy`] before forwarding
+/// it.
+///
+/// Denied calls fail with [`VaultmuxError::PermissionDenied`] without
+/// reaching the inner backend. Listings and watches need [`Access::List`]
+/// and only include items the principal may list; listed items keep their
+/// notes and fields only if the principal may also read them. Batch calls
+/// are checked per item. Lifecycle calls (`init`, `authenticate`, `sync`, `close`) are
+/// always allowed. Location operations are checked against the location
+/// name.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::{Access, Policy, PolicyBackend, Rule};
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let policy = Policy::new().with_rule(
+///     Rule::allow()
+///         .named("billing-reads-db")
+///         .on([Access::Read, Access::List])
+///         .items(["db/*"]),
+/// );
+///
+/// let inner = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+/// let mut backend = PolicyBackend::new(inner, policy).for_principal("billing");
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// let password = backend.get_notes("db/password", &*session).await?;
+/// // PermissionDenied: billing update_item db/password: no rule allows write access
+/// assert!(backend.update_item("db/password", "x", &*session).await.is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub struct PolicyBackend<B> {
+    inner: B,
+    policy: Policy,
+    principal: Option<String>,
+}
+
+impl<B: Backend> PolicyBackend<B> {
+    /// Wraps `inner`, checking calls against `policy`.
+    pub fn new(inner: B, policy: Policy) -> Self {
+        Self {
+            inner,
+            policy,
+            principal: None,
+        }
+    }
+
+    /// Sets the principal matched against each rule's `principals`.
+    ///
+    /// Without a principal, only rules with no `principals` apply.
+    pub fn for_principal(mut self, principal: impl Into<String>) -> Self {
+        self.principal = Some(principal.into());
+        self
+    }
+
+    /// Returns the policy.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn check(&self, access: Access, operation: &str, item: &str) -> Result<()> {
+        self.policy
+            .check(self.principal.as_deref(), access, operation, item)
+    }
+
+    fn may_list(&self, item: &str) -> bool {
+        self.policy
+            .permits(self.principal.as_deref(), Access::List, item)
+    }
+
+    /// Clears the notes and fields of a listed item the principal may not
+    /// read.
+    fn redact(&self, mut item: Item) -> Item {
+        if !self
+            .policy
+            .permits(self.principal.as_deref(), Access::Read, &item.name)
+        {
+            item.notes = None;
+            item.fields = None;
+        }
+        item
+    }
+
+    fn visible(&self, items: Vec<Item>) -> Vec<Item> {
+        items
+            .into_iter()
+            .filter(|item| self.may_list(&item.name))
+            .map(|item| self.redact(item))
+            .collect()
+    }
+
+    /// Checks each name of a batch, returning the allowed names and the
+    /// denial for each position.
+    fn check_batch<'n>(
+        &self,
+        access: Access,
+        operation: &str,
+        names: impl Iterator<Item = &'n str>,
+    ) -> Vec<Result<()>> {
+        names
+            .map(|name| self.check(access, operation, name))
+            .collect()
+    }
+}
+
+/// Merges results for the allowed positions of a batch back with the
+/// denials.
+fn merge_batch<T>(
+    checks: Vec<Result<()>>,
+    results: Result<Vec<Result<T>>>,
+) -> Result<Vec<Result<T>>> {
+    let mut results = results?.into_iter();
+    Ok(checks
+        .into_iter()
+        .map(|check| match check {
+            Ok(()) => results.next().unwrap_or_else(|| {
+                Err(VaultmuxError::Other(anyhow::anyhow!(
+                    "backend returned too few batch results"
+                )))
+            }),
+            Err(e) => Err(e),
+        })
+        .collect())
+}
+
+/// Checks a call against the policy, then forwards it.
+macro_rules! checked {
+    ($self:ident, $access:expr, $operation:literal, $item:expr, $call:expr) => {{
+        $self.check($access, $operation, $item)?;
+        $call.await
+    }};
+}
+
+#[async_trait]
+impl<B: Backend> Backend for PolicyBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.inner.init().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        self.inner.authenticate().await
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        self.inner.sync(session).await
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        checked!(
+            self,
+            Access::Read,
+            "get_item",
+            name,
+            self.inner.get_item(name, session)
+        )
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        checked!(
+            self,
+            Access::Read,
+            "get_notes",
+            name,
+            self.inner.get_notes(name, session)
+        )
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        checked!(
+            self,
+            Access::Read,
+            "item_exists",
+            name,
+            self.inner.item_exists(name, session)
+        )
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        let items = self.inner.list_items(session).await?;
+        Ok(self.visible(items))
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        self.inner
+            .list_items_stream(options, session)
+            .filter(move |item| {
+                let visible = match item {
+                    Ok(item) => self.may_list(&item.name),
+                    Err(_) => true,
+                };
+                async move { visible }
+            })
+            .map(move |item| item.map(|item| self.redact(item)))
+            .boxed()
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "create_item",
+            name,
+            self.inner.create_item(name, content, session)
+        )
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "update_item",
+            name,
+            self.inner.update_item(name, content, session)
+        )
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        checked!(
+            self,
+            Access::Delete,
+            "delete_item",
+            name,
+            self.inner.delete_item(name, session)
+        )
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        let checks = self.check_batch(Access::Read, "get_many", names.iter().copied());
+        let allowed: Vec<&str> = names
+            .iter()
+            .zip(&checks)
+            .filter(|(_, check)| check.is_ok())
+            .map(|(name, _)| *name)
+            .collect();
+        merge_batch(checks, self.inner.get_many(&allowed, session).await)
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let checks = self.check_batch(
+            Access::Write,
+            "put_many",
+            entries.iter().map(|(name, _)| *name),
+        );
+        let allowed: Vec<(&str, &str)> = entries
+            .iter()
+            .zip(&checks)
+            .filter(|(_, check)| check.is_ok())
+            .map(|(entry, _)| *entry)
+            .collect();
+        merge_batch(checks, self.inner.put_many(&allowed, session).await)
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let checks = self.check_batch(Access::Delete, "delete_many", names.iter().copied());
+        let allowed: Vec<&str> = names
+            .iter()
+            .zip(&checks)
+            .filter(|(_, check)| check.is_ok())
+            .map(|(name, _)| *name)
+            .collect();
+        merge_batch(checks, self.inner.delete_many(&allowed, session).await)
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "create_item_full",
+            &item.name,
+            self.inner.create_item_full(item, session)
+        )
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "update_item_full",
+            &item.name,
+            self.inner.update_item_full(item, session)
+        )
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "update_item_if",
+            name,
+            self.inner
+                .update_item_if(name, content, expected_revision, session)
+        )
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        checked!(
+            self,
+            Access::Read,
+            "get_bytes",
+            name,
+            self.inner.get_bytes(name, session)
+        )
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "put_bytes",
+            name,
+            self.inner.put_bytes(name, data, session)
+        )
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        checked!(
+            self,
+            Access::Read,
+            "list_versions",
+            name,
+            self.inner.list_versions(name, session)
+        )
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        checked!(
+            self,
+            Access::Read,
+            "get_version",
+            name,
+            self.inner.get_version(name, version_id, session)
+        )
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "disable_version",
+            name,
+            self.inner.disable_version(name, version_id, session)
+        )
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        checked!(
+            self,
+            Access::Delete,
+            "destroy_version",
+            name,
+            self.inner.destroy_version(name, version_id, session)
+        )
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "rollback",
+            name,
+            self.inner.rollback(name, version_id, session)
+        )
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        checked!(
+            self,
+            Access::Read,
+            "get_tags",
+            name,
+            self.inner.get_tags(name, session)
+        )
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "set_tags",
+            name,
+            self.inner.set_tags(name, tags, session)
+        )
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        let items = self.inner.list_items_by_tag(key, value, session).await?;
+        Ok(self.visible(items))
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        self.check(Access::Write, "rename_item", old_name)?;
+        self.check(Access::Delete, "rename_item", old_name)?;
+        self.check(Access::Write, "rename_item", new_name)?;
+        self.inner.rename_item(old_name, new_name, session).await
+    }
+
+    /// Needs [`Access::Write`] on the item and on its name inside
+    /// `location` (`a/b/key` moved to `location` becomes `location/key`).
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        let leaf = name.rsplit('/').next().unwrap_or(name);
+        let destination = format!("{}/{}", location.trim_end_matches('/'), leaf);
+
+        self.check(Access::Write, "move_item", name)?;
+        self.check(Access::Write, "move_item", &destination)?;
+        self.inner.move_item(name, location, session).await
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        checked!(
+            self,
+            Access::Delete,
+            "delete_item_with",
+            name,
+            self.inner.delete_item_with(name, options, session)
+        )
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        let items = self.inner.list_deleted_items(session).await?;
+        Ok(items
+            .into_iter()
+            .filter(|deleted| self.may_list(&deleted.item.name))
+            .map(|deleted| DeletedItem {
+                item: self.redact(deleted.item),
+                ..deleted
+            })
+            .collect())
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "restore_item",
+            name,
+            self.inner.restore_item(name, session)
+        )
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        self.inner
+            .watch(options, session)
+            .filter(move |event| {
+                let visible = match event {
+                    Ok(event) => self.may_list(&event.name),
+                    Err(_) => true,
+                };
+                async move { visible }
+            })
+            .boxed()
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        let locations = self.inner.list_locations(session).await?;
+        Ok(locations
+            .into_iter()
+            .filter(|location| self.may_list(location))
+            .collect())
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        checked!(
+            self,
+            Access::List,
+            "location_exists",
+            name,
+            self.inner.location_exists(name, session)
+        )
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        checked!(
+            self,
+            Access::Write,
+            "create_location",
+            name,
+            self.inner.create_location(name, session)
+        )
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        let items = self
+            .inner
+            .list_items_in_location(loc_type, loc_value, session)
+            .await?;
+        Ok(self.visible(items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> Policy {
+        Policy::new()
+            .with_rule(
+                Rule::allow()
+                    .named("billing-reads-db")
+                    .for_principals(["billing"])
+                    .on([Access::Read, Access::List])
+                    .items(["db/*"]),
+            )
+            .with_rule(Rule::deny().named("no-root-password").items(["db/root"]))
+    }
+
+    #[test]
+    fn test_policy_evaluation() {
+        let policy = policy();
+
+        assert!(policy
+            .check(Some("billing"), Access::Read, "get_notes", "db/password")
+            .is_ok());
+
+        let err = policy
+            .check(Some("billing"), Access::Write, "update_item", "db/password")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "permission denied: billing update_item db/password: no rule allows write access"
+        );
+
+        let err = policy
+            .check(Some("billing"), Access::Read, "get_notes", "db/root")
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("denied by rule \"no-root-password\""));
+
+        assert!(policy
+            .check(Some("search"), Access::Read, "get_notes", "db/password")
+            .is_err());
+        assert!(policy
+            .check(None, Access::Read, "get_notes", "db/password")
+            .is_err());
+    }
+
+    #[test]
+    fn test_policy_from_json() {
+        let policy = Policy::from_json(
+            r#"{
+                "default": "allow",
+                "rules": [
+                    {"effect": "deny", "operations": ["delete"], "items": ["prod/*"]}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(policy
+            .check(None, Access::Write, "update_item", "prod/db")
+            .is_ok());
+        let err = policy
+            .check(None, Access::Delete, "delete_item", "prod/db")
+            .unwrap_err();
+        assert!(err.to_string().contains("denied by rule #1"));
+
+        assert!(Policy::from_json(r#"{"rules": [{"effect": "maybe"}]}"#).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_policy_from_toml() {
+        let policy = Policy::from_toml(
+            r#"
+            [[rule]]
+            name = "billing-reads-db"
+            effect = "allow"
+            principals = ["billing"]
+            operations = ["read", "list"]
+            items = ["db/*"]
+
+            [[rule]]
+            name = "no-root-password"
+            effect = "deny"
+            items = ["db/root"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(policy, self::policy());
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn test_policy_backend_filters_and_denies() {
+        use crate::backends::mock::MockBackend;
+
+        let inner = MockBackend::new();
+        for name in ["db/password", "db/root", "api/key"] {
+            inner.set_item(name, "secret").await;
+        }
+        let policy = policy().with_rule(
+            Rule::allow()
+                .for_principals(["billing"])
+                .on([Access::List, Access::Write])
+                .items(["api/*", "archive/*"]),
+        );
+        let mut backend = PolicyBackend::new(inner, policy).for_principal("billing");
+        let session = backend.authenticate().await.unwrap();
+
+        let mut items = backend.list_items(&*session).await.unwrap();
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        let names: Vec<_> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["api/key", "db/password"]);
+        // Listing alone doesn't reveal values
+        assert_eq!(items[0].notes, None);
+        assert_eq!(items[0].fields, None);
+        assert_eq!(items[1].notes.as_deref(), Some("secret"));
+
+        let results = backend
+            .get_many(&["db/password", "api/key"], &*session)
+            .await
+            .unwrap();
+        assert_eq!(results[0].as_deref().unwrap(), "secret");
+        assert!(matches!(
+            results[1],
+            Err(VaultmuxError::PermissionDenied(_))
+        ));
+
+        let result = backend.delete_item("db/password", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::PermissionDenied(_))));
+        assert!(backend.get_notes("db/password", &*session).await.is_ok());
+
+        // Renaming removes the source, so it needs delete access
+        let result = backend
+            .rename_item("api/key", "archive/key", &*session)
+            .await;
+        assert!(matches!(result, Err(VaultmuxError::PermissionDenied(_))));
+
+        // The destination inside the location needs write access too
+        let result = backend.move_item("api/key", "db", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::PermissionDenied(_))));
+    }
+}
@@ -0,0 +1,740 @@
+//! Backend that limits the rate and concurrency of calls to another backend.
+
+use crate::{
+    Backend, Capabilities, ChangeEvent, Config, DeleteOptions, DeletedItem, Item, ItemVersion,
+    ListOptions, Result, Session, VaultmuxError, WatchOptions,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+/// Limits applied to one kind of operation (reads or writes).
+///
+/// # Example
+///
+/// ```
+/// use vaultmux::middleware::RateLimit;
+///
+/// // 50 requests per second, bursts of up to 10, at most 4 at once
+/// let limit = RateLimit::per_second(50.0)
+///     .with_burst(10)
+///     .with_max_in_flight(4);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateLimit {
+    per_second: Option<f64>,
+    burst: Option<u32>,
+    max_in_flight: Option<usize>,
+}
+
+impl RateLimit {
+    /// Creates a limit that allows any rate and concurrency.
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Creates a limit of `rate` requests per second.
+    ///
+    /// The burst defaults to one second's worth of requests.
+    pub fn per_second(rate: f64) -> Self {
+        Self {
+            per_second: Some(rate),
+            ..Self::default()
+        }
+    }
+
+    /// Sets how many requests may start at once after an idle period.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = Some(burst.max(1));
+        self
+    }
+
+    /// Sets how many requests may be running at the same time.
+    pub fn with_max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = Some(max.max(1));
+        self
+    }
+
+    /// Reads the limit from `{kind}_rate_limit`, `{kind}_burst` and
+    /// `{kind}_max_in_flight` options.
+    fn from_options(config: &Config, kind: &str) -> Result<Self> {
+        let mut limit = Self::unlimited();
+        if let Some(rate) = parse_option::<f64>(config, &format!("{kind}_rate_limit"))? {
+            if !(rate > 0.0 && rate.is_finite()) {
+                return Err(invalid_option(&format!("{kind}_rate_limit"), &rate));
+            }
+            limit.per_second = Some(rate);
+        }
+        if let Some(burst) = parse_option::<u32>(config, &format!("{kind}_burst"))? {
+            limit = limit.with_burst(burst);
+        }
+        if let Some(max) = parse_option::<usize>(config, &format!("{kind}_max_in_flight"))? {
+            limit = limit.with_max_in_flight(max);
+        }
+        Ok(limit)
+    }
+}
+
+fn parse_option<T: FromStr>(config: &Config, key: &str) -> Result<Option<T>> {
+    match config.get_option(key) {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| invalid_option(key, value)),
+        None => Ok(None),
+    }
+}
+
+fn invalid_option(key: &str, value: &dyn std::fmt::Display) -> VaultmuxError {
+    VaultmuxError::Other(anyhow::anyhow!(
+        "invalid value for option {}: {}",
+        key,
+        value
+    ))
+}
+
+/// A token bucket and an in-flight semaphore.
+struct Limiter {
+    bucket: Option<TokenBucket>,
+    in_flight: Option<Semaphore>,
+    max_in_flight: Option<usize>,
+}
+
+impl Limiter {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            bucket: limit.per_second.map(|rate| {
+                let burst = limit.burst.unwrap_or_else(|| rate.ceil().max(1.0) as u32);
+                TokenBucket::new(rate, burst)
+            }),
+            in_flight: limit.max_in_flight.map(Semaphore::new),
+            max_in_flight: limit.max_in_flight,
+        }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.bucket.is_none() && self.in_flight.is_none()
+    }
+
+    /// Largest batch that fits in the in-flight slots.
+    fn batch_size(&self) -> usize {
+        self.max_in_flight.unwrap_or(usize::MAX)
+    }
+
+    /// Waits until `cost` requests may start, taking one token and one
+    /// in-flight slot for each.
+    ///
+    /// `cost` must not exceed [`batch_size`](Self::batch_size). The returned
+    /// permit must be held until the requests finish.
+    async fn acquire(&self, cost: usize) -> Option<SemaphorePermit<'_>> {
+        if let Some(bucket) = &self.bucket {
+            bucket.take(cost).await;
+        }
+        match &self.in_flight {
+            // The semaphore is never closed
+            Some(semaphore) => semaphore.acquire_many(cost as u32).await.ok(),
+            None => None,
+        }
+    }
+}
+
+/// Token bucket that hands out tokens in the order they were requested.
+///
+/// Tokens are taken up front, letting the balance go negative; each caller
+/// then sleeps until the refill would have covered its share.
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: u32) -> Self {
+        Self {
+            rate,
+            burst: f64::from(burst),
+            state: Mutex::new(BucketState {
+                tokens: f64::from(burst),
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    async fn take(&self, cost: usize) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let refill = now.duration_since(state.updated).as_secs_f64() * self.rate;
+            state.tokens = (state.tokens + refill).min(self.burst) - cost as f64;
+            state.updated = now;
+
+            if state.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Read and write limits that can be shared by several backends.
+///
+/// Clones share the same buckets and in-flight counts, so backends wrapped
+/// with clones of one limiter stay within a single quota together.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::RateLimiter;
+/// use vaultmux::{BackendType, Config};
+///
+/// # fn example() -> vaultmux::Result<()> {
+/// let config = Config::new(BackendType::Bitwarden)
+///     .with_option("read_rate_limit", "20")
+///     .with_option("read_max_in_flight", "4")
+///     .with_option("write_max_in_flight", "1");
+///
+/// let limiter = RateLimiter::from_config(&config)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RateLimiter {
+    read: Arc<Limiter>,
+    write: Arc<Limiter>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter with separate read and write limits.
+    pub fn new(read: RateLimit, write: RateLimit) -> Self {
+        Self {
+            read: Arc::new(Limiter::new(read)),
+            write: Arc::new(Limiter::new(write)),
+        }
+    }
+
+    /// Creates a limiter from the rate limiting options in `config`.
+    ///
+    /// Reads and writes are configured separately:
+    ///
+    /// - `read_rate_limit` / `write_rate_limit`: requests per second
+    /// - `read_burst` / `write_burst`: requests allowed at once after an
+    ///   idle period (default: one second's worth)
+    /// - `read_max_in_flight` / `write_max_in_flight`: requests running at
+    ///   the same time
+    ///
+    /// Limits that aren't set are unlimited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option is not a positive number.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self::new(
+            RateLimit::from_options(config, "read")?,
+            RateLimit::from_options(config, "write")?,
+        ))
+    }
+
+    /// Returns true if neither reads nor writes are limited.
+    pub(crate) fn is_unlimited(&self) -> bool {
+        self.read.is_unlimited() && self.write.is_unlimited()
+    }
+}
+
+/// Backend that limits how fast and how many calls reach another backend.
+///
+/// Each call first takes a token from the read or write bucket, then waits
+/// for an in-flight slot that it holds until the call returns. Methods taking
+/// `&self` count as reads and methods taking `&mut self` as writes. Batch
+/// calls cost one token and one in-flight slot per entry; batches larger
+/// than the in-flight limit are passed on in chunks that fit.
+///
+/// Lifecycle calls (`init`, `authenticate`, `close`) are not limited, nor
+/// are the streams from `list_items_stream` and `watch`.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::{RateLimitedBackend, RateLimiter};
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let config = Config::new(BackendType::AWSSecretsManager)
+///     .with_option("read_rate_limit", "50")
+///     .with_option("write_rate_limit", "5");
+///
+/// let limiter = RateLimiter::from_config(&config)?;
+/// let mut backend = RateLimitedBackend::new(factory::new_backend(config)?, limiter);
+///
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+/// let password = backend.get_notes("db-password", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RateLimitedBackend<B> {
+    inner: B,
+    limiter: RateLimiter,
+}
+
+impl<B: Backend> RateLimitedBackend<B> {
+    /// Wraps `inner`, limiting its calls with `limiter`.
+    pub fn new(inner: B, limiter: RateLimiter) -> Self {
+        Self { inner, limiter }
+    }
+
+    /// Returns the limiter, for sharing with other backends.
+    pub fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+/// Runs a call once the read or write limiter lets it start.
+macro_rules! limited {
+    ($self:ident, $kind:ident, $call:expr) => {
+        limited!($self, $kind, 1, $call)
+    };
+    ($self:ident, $kind:ident, $cost:expr, $call:expr) => {{
+        let _permit = $self.limiter.$kind.acquire($cost).await;
+        $call.await
+    }};
+}
+
+#[async_trait]
+impl<B: Backend> Backend for RateLimitedBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.inner.init().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        self.inner.authenticate().await
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        limited!(self, write, self.inner.sync(session))
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        limited!(self, read, self.inner.get_item(name, session))
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        limited!(self, read, self.inner.get_notes(name, session))
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        limited!(self, read, self.inner.item_exists(name, session))
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        limited!(self, read, self.inner.list_items(session))
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        self.inner.list_items_stream(options, session)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(self, write, self.inner.create_item(name, content, session))
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(self, write, self.inner.update_item(name, content, session))
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        limited!(self, write, self.inner.delete_item(name, session))
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        let mut results = Vec::with_capacity(names.len());
+        for chunk in names.chunks(self.limiter.read.batch_size()) {
+            results.extend(limited!(
+                self,
+                read,
+                chunk.len(),
+                self.inner.get_many(chunk, session)
+            )?);
+        }
+        Ok(results)
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(entries.len());
+        for chunk in entries.chunks(self.limiter.write.batch_size()) {
+            results.extend(limited!(
+                self,
+                write,
+                chunk.len(),
+                self.inner.put_many(chunk, session)
+            )?);
+        }
+        Ok(results)
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(names.len());
+        for chunk in names.chunks(self.limiter.write.batch_size()) {
+            results.extend(limited!(
+                self,
+                write,
+                chunk.len(),
+                self.inner.delete_many(chunk, session)
+            )?);
+        }
+        Ok(results)
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        limited!(self, write, self.inner.create_item_full(item, session))
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        limited!(self, write, self.inner.update_item_full(item, session))
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(
+            self,
+            write,
+            self.inner
+                .update_item_if(name, content, expected_revision, session)
+        )
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        limited!(self, read, self.inner.get_bytes(name, session))
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        limited!(self, write, self.inner.put_bytes(name, data, session))
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        limited!(self, read, self.inner.list_versions(name, session))
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        limited!(
+            self,
+            read,
+            self.inner.get_version(name, version_id, session)
+        )
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(
+            self,
+            write,
+            self.inner.disable_version(name, version_id, session)
+        )
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(
+            self,
+            write,
+            self.inner.destroy_version(name, version_id, session)
+        )
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(self, write, self.inner.rollback(name, version_id, session))
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        limited!(self, read, self.inner.get_tags(name, session))
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(self, write, self.inner.set_tags(name, tags, session))
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        limited!(
+            self,
+            read,
+            self.inner.list_items_by_tag(key, value, session)
+        )
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(
+            self,
+            write,
+            self.inner.rename_item(old_name, new_name, session)
+        )
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        limited!(self, write, self.inner.move_item(name, location, session))
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        limited!(
+            self,
+            write,
+            self.inner.delete_item_with(name, options, session)
+        )
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        limited!(self, read, self.inner.list_deleted_items(session))
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        limited!(self, write, self.inner.restore_item(name, session))
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        self.inner.watch(options, session)
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        limited!(self, read, self.inner.list_locations(session))
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        limited!(self, read, self.inner.location_exists(name, session))
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        limited!(self, write, self.inner.create_location(name, session))
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        limited!(
+            self,
+            read,
+            self.inner
+                .list_items_in_location(loc_type, loc_value, session)
+        )
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+    use crate::BackendType;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_spaces_requests() {
+        let limiter = RateLimiter::new(
+            RateLimit::per_second(10.0).with_burst(2),
+            RateLimit::unlimited(),
+        );
+        let inner = MockBackend::new();
+        inner.set_item("api-key", "secret").await;
+        let mut backend = RateLimitedBackend::new(inner, limiter);
+        let session = backend.authenticate().await.unwrap();
+
+        let started = Instant::now();
+        for _ in 0..4 {
+            backend.get_notes("api-key", &*session).await.unwrap();
+        }
+        // Two from the burst, then one every 100ms
+        assert_eq!(started.elapsed(), Duration::from_millis(200));
+
+        // Writes have their own, unlimited, bucket
+        let started = Instant::now();
+        for i in 0..4 {
+            let name = format!("key-{i}");
+            backend
+                .create_item(&name, "value", &*session)
+                .await
+                .unwrap();
+        }
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight_is_shared_by_clones() {
+        let limiter = RateLimiter::new(
+            RateLimit::unlimited().with_max_in_flight(2),
+            RateLimit::unlimited(),
+        );
+        let other = limiter.clone();
+
+        let first = limiter.read.acquire(1).await;
+        let _second = other.read.acquire(1).await;
+        assert_eq!(
+            limiter.read.in_flight.as_ref().unwrap().available_permits(),
+            0
+        );
+
+        drop(first);
+        assert_eq!(
+            other.read.in_flight.as_ref().unwrap().available_permits(),
+            1
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batches_take_a_slot_per_entry() {
+        let limiter = RateLimiter::new(
+            RateLimit::unlimited().with_max_in_flight(3),
+            RateLimit::unlimited(),
+        );
+        let inner = MockBackend::new();
+        for i in 0..7 {
+            inner.set_item(&format!("key-{i}"), &format!("v{i}")).await;
+        }
+        let mut backend = RateLimitedBackend::new(inner, limiter.clone());
+        let session = backend.authenticate().await.unwrap();
+
+        // Larger than the limit, so it is passed on in chunks of three
+        let names: Vec<String> = (0..7).map(|i| format!("key-{i}")).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let values: Vec<String> = backend
+            .get_many(&names, &*session)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(values, ["v0", "v1", "v2", "v3", "v4", "v5", "v6"]);
+
+        // With one slot taken, a chunk of three has to wait for it
+        let held = limiter.read.acquire(1).await;
+        let batch = backend.get_many(&names[..3], &*session);
+        tokio::pin!(batch);
+        assert!(tokio::time::timeout(Duration::from_secs(1), &mut batch)
+            .await
+            .is_err());
+        drop(held);
+        assert_eq!(batch.await.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_limits_from_config() {
+        let config = Config::new(BackendType::Bitwarden)
+            .with_option("read_rate_limit", "20")
+            .with_option("write_max_in_flight", "1");
+        let limiter = RateLimiter::from_config(&config).unwrap();
+        assert!(limiter.read.bucket.is_some());
+        assert!(limiter.read.in_flight.is_none());
+        assert!(limiter.write.bucket.is_none());
+        assert!(limiter.write.in_flight.is_some());
+        assert!(!limiter.is_unlimited());
+        assert!(
+            RateLimiter::from_config(&Config::new(BackendType::Bitwarden))
+                .unwrap()
+                .is_unlimited()
+        );
+
+        let config = Config::new(BackendType::Bitwarden).with_option("write_rate_limit", "-1");
+        assert!(RateLimiter::from_config(&config).is_err());
+        let config = Config::new(BackendType::Bitwarden).with_option("read_burst", "lots");
+        assert!(RateLimiter::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! Backend that rejects every change to another backend.
+
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, VaultmuxError, WatchOptions,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Backend that allows reads but rejects writes.
+///
+/// Every method that would change the store, from `create_item` to
+/// `create_location`, fails with [`VaultmuxError::PermissionDenied`]
+/// without calling the inner backend. Lifecycle calls (`init`,
+/// `authenticate`, `sync`, `close`) are passed through.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::ReadOnlyBackend;
+/// use vaultmux::{factory, Backend, BackendType, Config, VaultmuxError};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let inner = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+/// let mut backend = ReadOnlyBackend::new(inner);
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// let password = backend.get_notes("db-password", &*session).await?;
+/// let result = backend.update_item("db-password", "new", &*session).await;
+/// assert!(matches!(result, Err(VaultmuxError::PermissionDenied(_))));
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReadOnlyBackend<B> {
+    inner: B,
+}
+
+impl<B: Backend> ReadOnlyBackend<B> {
+    /// Wraps `inner`.
+    pub fn new(inner: B) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn denied<T>(&self, operation: &str, item: &str) -> Result<T> {
+        Err(VaultmuxError::PermissionDenied(format!(
+            "{} is read-only: {} {}",
+            self.inner.name(),
+            operation,
+            item
+        )))
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Backend for ReadOnlyBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.inner.init().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        self.inner.authenticate().await
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        self.inner.sync(session).await
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        self.inner.get_item(name, session).await
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        self.inner.get_notes(name, session).await
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        self.inner.item_exists(name, session).await
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        self.inner.list_items(session).await
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        self.inner.list_items_stream(options, session)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        _content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("create_item", name)
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        _content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("update_item", name)
+    }
+
+    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        self.denied("delete_item", name)
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        self.inner.get_many(names, session).await
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        self.denied("put_many", &format!("{} items", entries.len()))
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        self.denied("delete_many", &format!("{} items", names.len()))
+    }
+
+    async fn create_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        self.denied("create_item_full", &item.name)
+    }
+
+    async fn update_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        self.denied("update_item_full", &item.name)
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        _content: &str,
+        _expected_revision: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("update_item_if", name)
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        self.inner.get_bytes(name, session).await
+    }
+
+    async fn put_bytes(&mut self, name: &str, _data: &[u8], _session: &dyn Session) -> Result<()> {
+        self.denied("put_bytes", name)
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        self.inner.list_versions(name, session).await
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        self.inner.get_version(name, version_id, session).await
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        _version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("disable_version", name)
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        _version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("destroy_version", name)
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        _version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("rollback", name)
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        self.inner.get_tags(name, session).await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        _tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("set_tags", name)
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.inner.list_items_by_tag(key, value, session).await
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        _new_name: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("rename_item", old_name)
+    }
+
+    async fn move_item(
+        &mut self,
+        name: &str,
+        _location: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("move_item", name)
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        _options: &DeleteOptions,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        self.denied("delete_item_with", name)
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        self.inner.list_deleted_items(session).await
+    }
+
+    async fn restore_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        self.denied("restore_item", name)
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        self.inner.watch(options, session)
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        self.inner.list_locations(session).await
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        self.inner.location_exists(name, session).await
+    }
+
+    async fn create_location(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        self.denied("create_location", name)
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        self.inner
+            .list_items_in_location(loc_type, loc_value, session)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_read_only_rejects_writes() {
+        let inner = MockBackend::new();
+        inner.set_item("api-key", "secret").await;
+        let mut backend = ReadOnlyBackend::new(inner);
+        let session = backend.authenticate().await.unwrap();
+
+        assert_eq!(
+            backend.get_notes("api-key", &*session).await.unwrap(),
+            "secret"
+        );
+        for result in [
+            backend.create_item("new", "value", &*session).await,
+            backend.update_item("api-key", "value", &*session).await,
+            backend.delete_item("api-key", &*session).await,
+            backend.create_location("team", &*session).await,
+        ] {
+            assert!(matches!(result, Err(VaultmuxError::PermissionDenied(_))));
+        }
+        assert_eq!(
+            backend.get_notes("api-key", &*session).await.unwrap(),
+            "secret"
+        );
+    }
+}
@@ -0,0 +1,572 @@
+//! Backend that retries transient failures.
+
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, VaultmuxError, WatchOptions,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Default number of attempts, including the first.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first retry (100 milliseconds).
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Default upper bound on the delay between attempts (5 seconds).
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// When and how often a [`RetryBackend`] retries.
+///
+/// The delay before retry `n` is `initial_backoff * 2^(n-1)`, capped at
+/// `max_backoff`. With jitter enabled (the default), each delay is instead
+/// drawn uniformly from zero up to that value, so that clients throttled at
+/// the same moment don't retry in lockstep.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use vaultmux::middleware::RetryPolicy;
+///
+/// let policy = RetryPolicy::new()
+///     .with_max_attempts(5)
+///     .with_initial_backoff(Duration::from_millis(200))
+///     .with_deadline(Duration::from_secs(10));
+///
+/// assert_eq!(policy.max_attempts(), 5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    deadline: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// Creates the default policy: 3 attempts, 100ms initial backoff, 5s
+    /// maximum backoff, jitter on and no deadline.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            jitter: true,
+            deadline: None,
+        }
+    }
+
+    /// Sets the number of attempts, including the first (default: 3).
+    ///
+    /// A value of 1 disables retries.
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry (default: 100 milliseconds).
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the upper bound on the delay between attempts (default: 5 seconds).
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Enables or disables random jitter on delays (default: enabled).
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Bounds the total time spent on one operation, across all attempts
+    /// and delays (default: none).
+    ///
+    /// An attempt still running at the deadline is abandoned with
+    /// [`VaultmuxError::Timeout`]. No retry is started if its delay would
+    /// end past the deadline.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Returns the number of attempts, including the first.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns the per-operation deadline, if any.
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// Returns the delay after failed attempt `attempt` (starting at 1).
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            delay.mul_f64(random_fraction())
+        } else {
+            delay
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns a random number in `[0, 1]`.
+fn random_fraction() -> f64 {
+    let (bits, _) = uuid::Uuid::new_v4().as_u64_pair();
+    bits as f64 / u64::MAX as f64
+}
+
+/// Backend that retries operations failing with a transient error.
+///
+/// An operation is retried only while its error is
+/// [retryable](VaultmuxError::is_retryable), such as throttling, a dropped
+/// connection or Bitwarden lock contention. Errors about the item or the
+/// request, like [`VaultmuxError::NotFound`], are returned at once.
+///
+/// Writes are retried too. If a write reached the backend but its response
+/// was lost, the retry may report the effect of the first attempt, for
+/// example [`VaultmuxError::AlreadyExists`] from `create_item`.
+///
+/// Streams from `list_items_stream` and `watch` are passed through without
+/// retries.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use vaultmux::middleware::{RetryBackend, RetryPolicy};
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let inner = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+/// let mut backend = RetryBackend::new(inner).with_policy(
+///     RetryPolicy::new()
+///         .with_max_attempts(5)
+///         .with_deadline(Duration::from_secs(30)),
+/// );
+///
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// // Retried if AWS answers with a ThrottlingException
+/// let password = backend.get_notes("db-password", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RetryBackend<B> {
+    inner: B,
+    policy: RetryPolicy,
+}
+
+impl<B: Backend> RetryBackend<B> {
+    /// Wraps `inner` with the default [`RetryPolicy`].
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            policy: RetryPolicy::new(),
+        }
+    }
+
+    /// Sets the retry policy.
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the retry policy.
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+fn deadline_exceeded(deadline: Duration) -> VaultmuxError {
+    VaultmuxError::Timeout(format!(
+        "operation did not finish within its {:?} retry deadline",
+        deadline
+    ))
+}
+
+/// Runs a call until it succeeds, fails permanently or runs out of
+/// attempts or time.
+macro_rules! retry {
+    ($self:ident, $call:expr) => {{
+        let policy = $self.policy;
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let result = match policy.deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_sub(started.elapsed());
+                    match tokio::time::timeout(remaining, $call).await {
+                        Ok(result) => result,
+                        Err(_) => break Err(deadline_exceeded(deadline)),
+                    }
+                }
+                None => $call.await,
+            };
+
+            match result {
+                Err(ref e) if e.is_retryable() && attempt < policy.max_attempts => {
+                    let delay = policy.backoff(attempt);
+                    if let Some(deadline) = policy.deadline {
+                        if started.elapsed() + delay >= deadline {
+                            break result;
+                        }
+                    }
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                _ => break result,
+            }
+        }
+    }};
+}
+
+#[async_trait]
+impl<B: Backend> Backend for RetryBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        retry!(self, self.inner.init())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        retry!(self, self.inner.close())
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        retry!(self, self.inner.authenticate())
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        retry!(self, self.inner.sync(session))
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        retry!(self, self.inner.get_item(name, session))
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        retry!(self, self.inner.get_notes(name, session))
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        retry!(self, self.inner.item_exists(name, session))
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        retry!(self, self.inner.list_items(session))
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        self.inner.list_items_stream(options, session)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(self, self.inner.create_item(name, content, session))
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(self, self.inner.update_item(name, content, session))
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        retry!(self, self.inner.delete_item(name, session))
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        retry!(self, self.inner.get_many(names, session))
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        retry!(self, self.inner.put_many(entries, session))
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        retry!(self, self.inner.delete_many(names, session))
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        retry!(self, self.inner.create_item_full(item, session))
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        retry!(self, self.inner.update_item_full(item, session))
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(
+            self,
+            self.inner
+                .update_item_if(name, content, expected_revision, session)
+        )
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        retry!(self, self.inner.get_bytes(name, session))
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        retry!(self, self.inner.put_bytes(name, data, session))
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        retry!(self, self.inner.list_versions(name, session))
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        retry!(self, self.inner.get_version(name, version_id, session))
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(self, self.inner.disable_version(name, version_id, session))
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(self, self.inner.destroy_version(name, version_id, session))
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(self, self.inner.rollback(name, version_id, session))
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        retry!(self, self.inner.get_tags(name, session))
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(self, self.inner.set_tags(name, tags, session))
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        retry!(self, self.inner.list_items_by_tag(key, value, session))
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(self, self.inner.rename_item(old_name, new_name, session))
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        retry!(self, self.inner.move_item(name, location, session))
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        retry!(self, self.inner.delete_item_with(name, options, session))
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        retry!(self, self.inner.list_deleted_items(session))
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        retry!(self, self.inner.restore_item(name, session))
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        self.inner.watch(options, session)
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        retry!(self, self.inner.list_locations(session))
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        retry!(self, self.inner.location_exists(name, session))
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        retry!(self, self.inner.create_location(name, session))
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        retry!(
+            self,
+            self.inner
+                .list_items_in_location(loc_type, loc_value, session)
+        )
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .with_max_attempts(4)
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_jitter(false)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_backs_off_on_transient_errors() {
+        let mut inner = MockBackend::new();
+        inner.get_error = Some(VaultmuxError::CommandFailed(
+            "bw failed with exit code 1: EBUSY: resource busy or locked, open 'data.json'"
+                .to_string(),
+        ));
+        let mut backend = RetryBackend::new(inner).with_policy(policy());
+        let session = backend.authenticate().await.unwrap();
+
+        let started = Instant::now();
+        let result = backend.get_notes("api-key", &*session).await;
+
+        assert!(result.unwrap_err().is_retryable());
+        // 100ms + 200ms + 400ms between the four attempts
+        assert_eq!(started.elapsed(), Duration::from_millis(700));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_skips_permanent_errors() {
+        let mut backend = RetryBackend::new(MockBackend::new()).with_policy(policy());
+        let session = backend.authenticate().await.unwrap();
+
+        let started = Instant::now();
+        let result = backend.get_notes("missing", &*session).await;
+
+        assert!(matches!(result, Err(VaultmuxError::NotFound(_))));
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_stops_at_deadline() {
+        let mut inner = MockBackend::new();
+        inner.get_error = Some(VaultmuxError::Throttled("Rate exceeded".to_string()));
+        let mut backend = RetryBackend::new(inner)
+            .with_policy(policy().with_deadline(Duration::from_millis(250)));
+        let session = backend.authenticate().await.unwrap();
+
+        let started = Instant::now();
+        let result = backend.get_notes("api-key", &*session).await;
+
+        // The second retry would sleep until 300ms, past the deadline
+        assert!(result.is_err());
+        assert_eq!(started.elapsed(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_backoff_is_capped_and_jittered() {
+        let policy = policy().with_max_backoff(Duration::from_millis(250));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(250));
+
+        let jittered = policy.with_jitter(true);
+        for attempt in 1..10 {
+            assert!(jittered.backoff(attempt) <= Duration::from_millis(250));
+        }
+    }
+}
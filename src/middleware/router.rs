@@ -0,0 +1,717 @@
+//! Backend that routes items to several backends by name.
+
+use super::MultiSession;
+use crate::backend;
+use crate::pattern::glob_match;
+use crate::{
+    Backend, Capabilities, DeleteOptions, DeletedItem, Item, ItemVersion, Result, Session,
+    VaultmuxError,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Backend that sends each item to one of several inner backends.
+///
+/// Items are routed by name: the first rule whose glob pattern matches the
+/// name wins, and names matching no rule go to the default backend. Each
+/// inner backend is registered under a label, which listings report as the
+/// item's [`location`](Item::location).
+///
+/// [`authenticate()`](Backend::authenticate) authenticates every inner
+/// backend and keeps their sessions; the session passed to each call is
+/// ignored.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::RouterBackend;
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let mut backend = RouterBackend::new()
+///     .with_backend("aws", factory::new_backend(Config::new(BackendType::AWSSecretsManager))?)
+///     .with_backend("bitwarden", factory::new_backend(Config::new(BackendType::Bitwarden))?)
+///     .with_backend("pass", factory::new_backend(Config::new(BackendType::Pass))?)
+///     .route("db/*", "aws")
+///     .route("personal/*", "bitwarden")
+///     .with_default("pass");
+///
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// // Read from AWS Secrets Manager
+/// let password = backend.get_notes("db/password", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RouterBackend {
+    backends: Vec<Routed>,
+    routes: Vec<Route>,
+    default: Option<String>,
+}
+
+/// An inner backend and its session.
+struct Routed {
+    label: String,
+    backend: Box<dyn Backend>,
+    session: Option<Arc<dyn Session>>,
+}
+
+/// A name pattern and the label of the backend it routes to.
+struct Route {
+    pattern: String,
+    label: String,
+}
+
+impl RouterBackend {
+    /// Creates a router with no backends.
+    pub fn new() -> Self {
+        Self {
+            backends: Vec::new(),
+            routes: Vec::new(),
+            default: None,
+        }
+    }
+
+    /// Registers an inner backend under `label`.
+    pub fn with_backend(mut self, label: impl Into<String>, backend: Box<dyn Backend>) -> Self {
+        self.backends.push(Routed {
+            label: label.into(),
+            backend,
+            session: None,
+        });
+        self
+    }
+
+    /// Adds a rule sending names that match the glob `pattern` to the
+    /// backend registered as `label`.
+    ///
+    /// Rules are tried in the order they were added.
+    pub fn route(mut self, pattern: impl Into<String>, label: impl Into<String>) -> Self {
+        self.routes.push(Route {
+            pattern: pattern.into(),
+            label: label.into(),
+        });
+        self
+    }
+
+    /// Sends names that match no rule to the backend registered as `label`.
+    ///
+    /// Without a default, such names fail with an error.
+    pub fn with_default(mut self, label: impl Into<String>) -> Self {
+        self.default = Some(label.into());
+        self
+    }
+
+    /// Returns the position of the backend registered as `label`.
+    fn index_of(&self, label: &str) -> Result<usize> {
+        self.backends
+            .iter()
+            .position(|routed| routed.label == label)
+            .ok_or_else(|| {
+                VaultmuxError::Other(anyhow::anyhow!("no backend registered as {}", label))
+            })
+    }
+
+    /// Returns the position of the backend an item name routes to.
+    fn route_index(&self, name: &str) -> Result<usize> {
+        let label = self
+            .routes
+            .iter()
+            .find(|route| glob_match(&route.pattern, name))
+            .map(|route| route.label.as_str())
+            .or(self.default.as_deref())
+            .ok_or_else(|| no_route(name))?;
+
+        self.index_of(label)
+    }
+
+    /// Returns the backend and session for an item name.
+    fn target(&self, name: &str) -> Result<(&dyn Backend, &dyn Session)> {
+        let routed = &self.backends[self.route_index(name)?];
+        Ok((routed.backend.as_ref(), session(routed)?))
+    }
+
+    /// Returns the backend and session for an item name, for writes.
+    fn target_mut(&mut self, name: &str) -> Result<(&mut dyn Backend, Arc<dyn Session>)> {
+        let index = self.route_index(name)?;
+        let routed = &mut self.backends[index];
+        let session = routed
+            .session
+            .clone()
+            .ok_or(VaultmuxError::NotAuthenticated)?;
+        Ok((routed.backend.as_mut(), session))
+    }
+
+    /// Groups names by the backend they route to.
+    ///
+    /// Returns, for each backend, the positions of its names. Names that
+    /// route nowhere are left out.
+    fn partition<'a>(&self, names: impl Iterator<Item = &'a str>) -> Vec<Vec<usize>> {
+        let mut groups = vec![Vec::new(); self.backends.len()];
+        for (position, name) in names.enumerate() {
+            if let Ok(index) = self.route_index(name) {
+                groups[index].push(position);
+            }
+        }
+        groups
+    }
+
+    /// Keeps the items that route back to backend `index`, located by its label.
+    fn routed_items(&self, index: usize, items: Vec<Item>) -> Vec<Item> {
+        items
+            .into_iter()
+            .filter(|item| self.route_index(&item.name).ok() == Some(index))
+            .map(|mut item| {
+                item.location = Some(self.backends[index].label.clone());
+                item
+            })
+            .collect()
+    }
+}
+
+impl Default for RouterBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the session of an authenticated inner backend.
+fn session(routed: &Routed) -> Result<&dyn Session> {
+    routed
+        .session
+        .as_deref()
+        .ok_or(VaultmuxError::NotAuthenticated)
+}
+
+/// Error for an item name that matches no rule and has no default.
+fn no_route(name: &str) -> VaultmuxError {
+    VaultmuxError::Other(anyhow::anyhow!("no backend route matches {}", name))
+}
+
+/// Treats an unsupported operation as contributing nothing to a merged result.
+fn supported<T: Default>(result: Result<T>) -> Result<T> {
+    match result {
+        Err(VaultmuxError::NotSupported(_)) => Ok(T::default()),
+        other => other,
+    }
+}
+
+#[async_trait]
+impl Backend for RouterBackend {
+    fn name(&self) -> &str {
+        "router"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let mut caps = self
+            .backends
+            .iter()
+            .map(|routed| routed.backend.capabilities())
+            .reduce(Capabilities::intersection)
+            .unwrap_or_default();
+
+        // Locations are the inner backends, and renames can cross them
+        caps.locations = true;
+        caps.create_locations = false;
+        caps.native_rename = false;
+        caps.sync = self
+            .backends
+            .iter()
+            .any(|routed| routed.backend.capabilities().sync);
+        caps
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        for route in &self.routes {
+            self.index_of(&route.label)?;
+        }
+        if let Some(ref label) = self.default {
+            self.index_of(label)?;
+        }
+
+        for routed in &mut self.backends {
+            routed.backend.init().await?;
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let mut first_error = None;
+        for routed in &mut self.backends {
+            routed.session = None;
+            if let Err(e) = routed.backend.close().await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        for routed in &self.backends {
+            if !routed.backend.is_authenticated().await {
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        let mut sessions = Vec::with_capacity(self.backends.len());
+        for routed in &mut self.backends {
+            let session = routed.backend.authenticate().await?;
+            routed.session = Some(session.clone());
+            sessions.push(session);
+        }
+        Ok(Arc::new(MultiSession::new(sessions)))
+    }
+
+    async fn sync(&mut self, _session: &dyn Session) -> Result<()> {
+        for routed in &mut self.backends {
+            let session = routed
+                .session
+                .clone()
+                .ok_or(VaultmuxError::NotAuthenticated)?;
+            routed.backend.sync(&*session).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_item(&self, name: &str, _session: &dyn Session) -> Result<Item> {
+        let index = self.route_index(name)?;
+        let (backend, session) = self.target(name)?;
+        let mut item = backend.get_item(name, session).await?;
+        item.location = Some(self.backends[index].label.clone());
+        Ok(item)
+    }
+
+    async fn get_notes(&self, name: &str, _session: &dyn Session) -> Result<String> {
+        let (backend, session) = self.target(name)?;
+        backend.get_notes(name, session).await
+    }
+
+    async fn item_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
+        let (backend, session) = self.target(name)?;
+        backend.item_exists(name, session).await
+    }
+
+    async fn list_items(&self, _session: &dyn Session) -> Result<Vec<Item>> {
+        let mut items = Vec::new();
+        for (index, routed) in self.backends.iter().enumerate() {
+            let listed = routed.backend.list_items(session(routed)?).await?;
+            items.extend(self.routed_items(index, listed));
+        }
+        Ok(items)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.create_item(name, content, &*session).await
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.update_item(name, content, &*session).await
+    }
+
+    async fn delete_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.delete_item(name, &*session).await
+    }
+
+    async fn get_many(
+        &self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<String>>> {
+        let mut results: Vec<Option<Result<String>>> = names.iter().map(|_| None).collect();
+
+        // One batch per backend, so native batch APIs still apply
+        let groups = self.partition(names.iter().copied());
+        for (routed, positions) in self.backends.iter().zip(groups) {
+            if positions.is_empty() {
+                continue;
+            }
+            let batch: Vec<&str> = positions.iter().map(|&i| names[i]).collect();
+            let values = routed.backend.get_many(&batch, session(routed)?).await?;
+            for (position, value) in positions.into_iter().zip(values) {
+                results[position] = Some(value);
+            }
+        }
+
+        Ok(names
+            .iter()
+            .zip(results)
+            .map(|(name, result)| result.unwrap_or_else(|| Err(no_route(name))))
+            .collect())
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results: Vec<Option<Result<()>>> = entries.iter().map(|_| None).collect();
+
+        let groups = self.partition(entries.iter().map(|(name, _)| *name));
+        for (routed, positions) in self.backends.iter_mut().zip(groups) {
+            if positions.is_empty() {
+                continue;
+            }
+            let session = routed
+                .session
+                .clone()
+                .ok_or(VaultmuxError::NotAuthenticated)?;
+            let batch: Vec<(&str, &str)> = positions.iter().map(|&i| entries[i]).collect();
+            let outcomes = routed.backend.put_many(&batch, &*session).await?;
+            for (position, outcome) in positions.into_iter().zip(outcomes) {
+                results[position] = Some(outcome);
+            }
+        }
+
+        Ok(entries
+            .iter()
+            .zip(results)
+            .map(|((name, _), result)| result.unwrap_or_else(|| Err(no_route(name))))
+            .collect())
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        _session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results: Vec<Option<Result<()>>> = names.iter().map(|_| None).collect();
+
+        let groups = self.partition(names.iter().copied());
+        for (routed, positions) in self.backends.iter_mut().zip(groups) {
+            if positions.is_empty() {
+                continue;
+            }
+            let session = routed
+                .session
+                .clone()
+                .ok_or(VaultmuxError::NotAuthenticated)?;
+            let batch: Vec<&str> = positions.iter().map(|&i| names[i]).collect();
+            let outcomes = routed.backend.delete_many(&batch, &*session).await?;
+            for (position, outcome) in positions.into_iter().zip(outcomes) {
+                results[position] = Some(outcome);
+            }
+        }
+
+        Ok(names
+            .iter()
+            .zip(results)
+            .map(|(name, result)| result.unwrap_or_else(|| Err(no_route(name))))
+            .collect())
+    }
+
+    async fn create_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        let (backend, session) = self.target_mut(&item.name)?;
+        backend.create_item_full(item, &*session).await
+    }
+
+    async fn update_item_full(&mut self, item: &Item, _session: &dyn Session) -> Result<()> {
+        let (backend, session) = self.target_mut(&item.name)?;
+        backend.update_item_full(item, &*session).await
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend
+            .update_item_if(name, content, expected_revision, &*session)
+            .await
+    }
+
+    async fn get_bytes(&self, name: &str, _session: &dyn Session) -> Result<Vec<u8>> {
+        let (backend, session) = self.target(name)?;
+        backend.get_bytes(name, session).await
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], _session: &dyn Session) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.put_bytes(name, data, &*session).await
+    }
+
+    async fn list_versions(&self, name: &str, _session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        let (backend, session) = self.target(name)?;
+        backend.list_versions(name, session).await
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<String> {
+        let (backend, session) = self.target(name)?;
+        backend.get_version(name, version_id, session).await
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.disable_version(name, version_id, &*session).await
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.destroy_version(name, version_id, &*session).await
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.rollback(name, version_id, &*session).await
+    }
+
+    async fn get_tags(
+        &self,
+        name: &str,
+        _session: &dyn Session,
+    ) -> Result<HashMap<String, String>> {
+        let (backend, session) = self.target(name)?;
+        backend.get_tags(name, session).await
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.set_tags(name, tags, &*session).await
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        _session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        // Backends without tags have no tagged items
+        let mut items = Vec::new();
+        for (index, routed) in self.backends.iter().enumerate() {
+            let listed = supported(
+                routed
+                    .backend
+                    .list_items_by_tag(key, value, session(routed)?)
+                    .await,
+            )?;
+            items.extend(self.routed_items(index, listed));
+        }
+        Ok(items)
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        if self.route_index(old_name)? == self.route_index(new_name)? {
+            let (backend, inner) = self.target_mut(old_name)?;
+            return backend.rename_item(old_name, new_name, &*inner).await;
+        }
+
+        // The new name belongs to another backend: copy the item across,
+        // dropping a location that only meant something to the old one
+        backend::copy_and_delete(self, old_name, new_name, session, |item| {
+            item.location = None
+        })
+        .await
+    }
+
+    async fn move_item(
+        &mut self,
+        _name: &str,
+        _location: &str,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        Err(VaultmuxError::NotSupported(
+            "router places items by name; rename the item to route it elsewhere".to_string(),
+        ))
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        _session: &dyn Session,
+    ) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.delete_item_with(name, options, &*session).await
+    }
+
+    async fn list_deleted_items(&self, _session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        let mut deleted = Vec::new();
+        for (index, routed) in self.backends.iter().enumerate() {
+            let listed = supported(routed.backend.list_deleted_items(session(routed)?).await)?;
+            deleted.extend(
+                listed
+                    .into_iter()
+                    .filter(|d| self.route_index(&d.item.name).ok() == Some(index))
+                    .map(|mut d| {
+                        d.item.location = Some(routed.label.clone());
+                        d
+                    }),
+            );
+        }
+        Ok(deleted)
+    }
+
+    async fn restore_item(&mut self, name: &str, _session: &dyn Session) -> Result<()> {
+        let (backend, session) = self.target_mut(name)?;
+        backend.restore_item(name, &*session).await
+    }
+
+    async fn list_locations(&self, _session: &dyn Session) -> Result<Vec<String>> {
+        Ok(self
+            .backends
+            .iter()
+            .map(|routed| routed.label.clone())
+            .collect())
+    }
+
+    async fn location_exists(&self, name: &str, _session: &dyn Session) -> Result<bool> {
+        Ok(self.backends.iter().any(|routed| routed.label == name))
+    }
+
+    async fn create_location(&mut self, _name: &str, _session: &dyn Session) -> Result<()> {
+        Err(VaultmuxError::NotSupported(
+            "router locations are its inner backends; register them with with_backend".to_string(),
+        ))
+    }
+
+    async fn list_items_in_location(
+        &self,
+        _loc_type: &str,
+        loc_value: &str,
+        _session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        let index = self
+            .index_of(loc_value)
+            .map_err(|_| VaultmuxError::NotFound(loc_value.to_string()))?;
+        let routed = &self.backends[index];
+
+        let listed = routed.backend.list_items(session(routed)?).await?;
+        Ok(self.routed_items(index, listed))
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    fn router() -> RouterBackend {
+        RouterBackend::new()
+            .with_backend("aws", Box::new(MockBackend::new()))
+            .with_backend("pass", Box::new(MockBackend::new()))
+            .route("db/*", "aws")
+            .with_default("pass")
+    }
+
+    #[tokio::test]
+    async fn test_router_dispatch_and_list() {
+        let mut backend = router();
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        backend
+            .create_item("db/password", "hunter2", &*session)
+            .await
+            .unwrap();
+        backend
+            .create_item("editor-theme", "dark", &*session)
+            .await
+            .unwrap();
+
+        let item = backend.get_item("db/password", &*session).await.unwrap();
+        assert_eq!(item.location.as_deref(), Some("aws"));
+
+        let mut items = backend.list_items(&*session).await.unwrap();
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        let located: Vec<_> = items
+            .iter()
+            .map(|item| (item.name.as_str(), item.location.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            located,
+            vec![("db/password", "aws"), ("editor-theme", "pass")]
+        );
+
+        let pass_items = backend
+            .list_items_in_location("backend", "pass", &*session)
+            .await
+            .unwrap();
+        assert_eq!(pass_items.len(), 1);
+
+        let notes = backend
+            .get_many(&["editor-theme", "db/password"], &*session)
+            .await
+            .unwrap();
+        assert_eq!(notes[0].as_deref().unwrap(), "dark");
+        assert_eq!(notes[1].as_deref().unwrap(), "hunter2");
+    }
+
+    #[tokio::test]
+    async fn test_router_rename_across_backends() {
+        let mut backend = router();
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        backend
+            .create_item("staging-db", "pw", &*session)
+            .await
+            .unwrap();
+        backend
+            .rename_item("staging-db", "db/staging", &*session)
+            .await
+            .unwrap();
+
+        let item = backend.get_item("db/staging", &*session).await.unwrap();
+        assert_eq!(item.location.as_deref(), Some("aws"));
+        assert!(!backend.item_exists("staging-db", &*session).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_router_rejects_unknown_label() {
+        let mut backend = RouterBackend::new()
+            .with_backend("pass", Box::new(MockBackend::new()))
+            .route("db/*", "aws");
+
+        assert!(backend.init().await.is_err());
+    }
+}
@@ -0,0 +1,1043 @@
+//! Encrypted offline snapshots of a backend.
+
+use super::is_unavailable;
+use crate::backend::BATCH_CONCURRENCY;
+use crate::crypto::{self, KEY_LEN, SALT_LEN};
+use crate::{
+    encoding, Backend, Capabilities, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, VaultmuxError,
+};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::fs;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+/// Snapshot file format version.
+const FORMAT_VERSION: u32 = 1;
+
+/// Default time between attempts to reach the inner backend while offline.
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Key used to encrypt a snapshot file.
+#[derive(Clone)]
+pub enum SnapshotKey {
+    /// A passphrase, stretched with Argon2id and a random per-file salt
+    Passphrase(String),
+    /// A random 256-bit key, typically held in another backend
+    Key([u8; KEY_LEN]),
+}
+
+impl SnapshotKey {
+    /// Generates a random key.
+    ///
+    /// Store it with [`to_base64()`](SnapshotKey::to_base64), for example in
+    /// another backend read back with [`from_backend()`](SnapshotKey::from_backend).
+    pub fn generate() -> Self {
+        Self::Key(crypto::random_bytes())
+    }
+
+    /// Parses a base64-encoded 256-bit key.
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = BASE64
+            .decode(encoded.trim())
+            .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("invalid snapshot key: {}", e)))?;
+        let key: [u8; KEY_LEN] = bytes.try_into().map_err(|_| {
+            VaultmuxError::Other(anyhow::anyhow!(
+                "invalid snapshot key: expected {} bytes",
+                KEY_LEN
+            ))
+        })?;
+        Ok(Self::Key(key))
+    }
+
+    /// Reads a base64-encoded key stored as an item in another backend.
+    pub async fn from_backend<B>(backend: &B, name: &str, session: &dyn Session) -> Result<Self>
+    where
+        B: Backend + ?Sized,
+    {
+        Self::from_base64(&backend.get_notes(name, session).await?)
+    }
+
+    /// Returns a random key as base64, or `None` for a passphrase.
+    pub fn to_base64(&self) -> Option<String> {
+        match self {
+            Self::Key(key) => Some(BASE64.encode(key)),
+            Self::Passphrase(_) => None,
+        }
+    }
+
+    /// Returns the encryption key for a file with the given salt.
+    fn resolve(&self, salt: Option<&[u8]>) -> Result<[u8; KEY_LEN]> {
+        match (self, salt) {
+            (Self::Key(key), None) => Ok(*key),
+            (Self::Passphrase(passphrase), Some(salt)) => crypto::derive_key(passphrase, salt),
+            (Self::Key(_), Some(_)) => Err(VaultmuxError::Other(anyhow::anyhow!(
+                "snapshot was encrypted with a passphrase, not a key"
+            ))),
+            (Self::Passphrase(_), None) => Err(VaultmuxError::Other(anyhow::anyhow!(
+                "snapshot was encrypted with a key, not a passphrase"
+            ))),
+        }
+    }
+}
+
+impl fmt::Debug for SnapshotKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Passphrase(_) => f.write_str("SnapshotKey::Passphrase(..)"),
+            Self::Key(_) => f.write_str("SnapshotKey::Key(..)"),
+        }
+    }
+}
+
+/// Unencrypted part of a snapshot file, protected against changes by the
+/// encryption.
+#[derive(Serialize, Deserialize)]
+struct Header {
+    format: u32,
+    created: DateTime<Utc>,
+    prefix: String,
+    /// Base64 Argon2id salt, for passphrase keys
+    #[serde(skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile {
+    #[serde(flatten)]
+    header: Header,
+    /// Base64 nonce and ciphertext of the JSON item list
+    data: String,
+}
+
+/// Items captured from a backend at one point in time.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    created: DateTime<Utc>,
+    prefix: String,
+    items: BTreeMap<String, Item>,
+}
+
+impl Snapshot {
+    /// Reads every item whose name starts with `prefix`, with its value.
+    pub async fn capture<B>(backend: &B, prefix: &str, session: &dyn Session) -> Result<Self>
+    where
+        B: Backend + ?Sized,
+    {
+        let options = ListOptions::new().with_prefix(prefix);
+        let listed: Vec<Item> = backend
+            .list_items_stream(&options, session)
+            .try_collect()
+            .await?;
+
+        // Listings may omit values, so read each item in full
+        let lookups: Vec<_> = listed
+            .iter()
+            .map(|item| backend.get_item(&item.name, session))
+            .collect();
+        let results: Vec<Result<Item>> = futures::stream::iter(lookups)
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut items = BTreeMap::new();
+        for result in results {
+            match result {
+                Ok(item) => {
+                    items.insert(item.name.clone(), item);
+                }
+                // Deleted since it was listed
+                Err(VaultmuxError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Self {
+            created: Utc::now(),
+            prefix: prefix.to_string(),
+            items,
+        })
+    }
+
+    /// Encrypts the snapshot and writes it to `path`.
+    ///
+    /// The file is written to a new temporary file next to `path` and
+    /// renamed into place, so an interrupted save leaves the previous
+    /// snapshot intact. It is created with mode 0600 on Unix systems.
+    pub async fn save(&self, path: impl AsRef<Path>, key: &SnapshotKey) -> Result<()> {
+        let path = path.as_ref();
+
+        let salt = match key {
+            SnapshotKey::Passphrase(_) => Some(crypto::random_bytes::<SALT_LEN>()),
+            SnapshotKey::Key(_) => None,
+        };
+        let header = Header {
+            format: FORMAT_VERSION,
+            created: self.created,
+            prefix: self.prefix.clone(),
+            salt: salt.map(|salt| BASE64.encode(salt)),
+        };
+
+        let items: Vec<&Item> = self.items.values().collect();
+        let sealed = crypto::seal(
+            &key.resolve(salt.as_ref().map(|s| s.as_slice()))?,
+            &serde_json::to_vec(&header)?,
+            &serde_json::to_vec(&items)?,
+        )?;
+        let file = SnapshotFile {
+            header,
+            data: BASE64.encode(sealed),
+        };
+
+        let contents = serde_json::to_vec_pretty(&file)?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let path = path.to_path_buf();
+
+        // NamedTempFile creates the file with mode 0600 on Unix
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+            temp.write_all(&contents)?;
+            temp.as_file().sync_all()?;
+            temp.persist(path).map_err(|e| e.error)?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| VaultmuxError::Other(e.into()))?
+    }
+
+    /// Reads and decrypts a snapshot file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the key is wrong or any part of the file was modified.
+    pub async fn load(path: impl AsRef<Path>, key: &SnapshotKey) -> Result<Self> {
+        let file: SnapshotFile = serde_json::from_slice(&fs::read(path).await?)?;
+        let header = file.header;
+        if header.format != FORMAT_VERSION {
+            return Err(VaultmuxError::Other(anyhow::anyhow!(
+                "unsupported snapshot format {}",
+                header.format
+            )));
+        }
+
+        let decode = |value: &str| {
+            BASE64
+                .decode(value)
+                .map_err(|e| VaultmuxError::Other(anyhow::anyhow!("corrupt snapshot file: {}", e)))
+        };
+        let salt = header.salt.as_deref().map(decode).transpose()?;
+        let plaintext = crypto::open(
+            &key.resolve(salt.as_deref())?,
+            &serde_json::to_vec(&header)?,
+            &decode(&file.data)?,
+        )?;
+
+        let items: Vec<Item> = serde_json::from_slice(&plaintext)?;
+        Ok(Self {
+            created: header.created,
+            prefix: header.prefix,
+            items: items
+                .into_iter()
+                .map(|item| (item.name.clone(), item))
+                .collect(),
+        })
+    }
+
+    /// When the items were captured.
+    pub fn created(&self) -> DateTime<Utc> {
+        self.created
+    }
+
+    /// The name prefix the snapshot covers.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns a captured item.
+    pub fn get(&self, name: &str) -> Option<&Item> {
+        self.items.get(name)
+    }
+
+    /// Returns the captured items, ordered by name.
+    pub fn items(&self) -> impl Iterator<Item = &Item> {
+        self.items.values()
+    }
+
+    fn item(&self, name: &str) -> Result<Item> {
+        self.get(name)
+            .cloned()
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))
+    }
+
+    fn notes(&self, name: &str) -> Result<String> {
+        self.item(name)?
+            .notes
+            .ok_or_else(|| VaultmuxError::NotFound(name.to_string()))
+    }
+}
+
+/// Token of the session returned while offline.
+const OFFLINE_TOKEN: &str = "vaultmux-offline-snapshot";
+
+/// Session returned while [`SnapshotBackend`] is serving its snapshot.
+///
+/// Once the wrapper is back online, it stands for the inner session from
+/// the reconnect.
+struct OfflineSession;
+
+#[async_trait]
+impl Session for OfflineSession {
+    fn token(&self) -> &str {
+        OFFLINE_TOKEN
+    }
+
+    async fn is_valid(&self) -> bool {
+        true
+    }
+
+    async fn refresh(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn expires_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+}
+
+/// Backend that serves reads from an encrypted snapshot while the inner
+/// backend is unreachable.
+///
+/// [`take_snapshot()`](SnapshotBackend::take_snapshot) captures every item
+/// under the prefix and saves it to the snapshot file. [`init()`](Backend::init)
+/// loads the file if it exists, checking its integrity.
+///
+/// If the inner backend can't be initialized or authenticated (a connection,
+/// CLI or session failure) and a snapshot is loaded, the wrapper goes
+/// offline: [`authenticate()`](Backend::authenticate) returns an offline
+/// session, `get_item`, `get_notes`, `get_bytes`, `item_exists` and
+/// `list_items` are answered from the snapshot, and everything else fails
+/// with [`VaultmuxError::NotSupported`]. While online, those reads fall back
+/// to the snapshot when the inner backend is unavailable.
+///
+/// While offline, every `authenticate()` and, at most once per retry
+/// interval, any other call initializes and authenticates the inner backend
+/// again. The wrapper goes back online as soon as that succeeds, and offline
+/// sessions handed out earlier then stand for the new inner session.
+///
+/// Snapshots older than the max age are never served.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use vaultmux::middleware::{SnapshotBackend, SnapshotKey};
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let inner = factory::new_backend(Config::new(BackendType::AWSSecretsManager))?;
+/// let key = SnapshotKey::Passphrase(std::env::var("SNAPSHOT_PASSPHRASE").unwrap());
+///
+/// let mut backend = SnapshotBackend::new(inner, "/var/lib/app/secrets.snapshot", key)
+///     .with_prefix("site-7/")
+///     .with_max_age(Duration::from_secs(30 * 86400));
+///
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// if !backend.is_offline() {
+///     backend.take_snapshot(&*session).await?;
+/// }
+///
+/// let password = backend.get_notes("site-7/scada-admin", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SnapshotBackend<B> {
+    inner: RwLock<B>,
+    name: String,
+    capabilities: Capabilities,
+    path: PathBuf,
+    key: SnapshotKey,
+    prefix: String,
+    max_age: Option<Duration>,
+    snapshot: Option<Snapshot>,
+    offline: AtomicBool,
+    initialized: AtomicBool,
+    /// Inner session from the latest reconnect
+    inner_session: Mutex<Option<Arc<dyn Session>>>,
+    retry_interval: Duration,
+    last_attempt: Mutex<Option<Instant>>,
+}
+
+/// The session to pass to the inner backend.
+enum InnerSession<'a> {
+    /// The caller's session
+    Caller(&'a dyn Session),
+    /// The session from a reconnect, in place of an offline session
+    Reconnected(Arc<dyn Session>),
+}
+
+impl<'a> std::ops::Deref for InnerSession<'a> {
+    type Target = dyn Session + 'a;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Caller(session) => *session,
+            Self::Reconnected(session) => &**session,
+        }
+    }
+}
+
+impl<B: Backend> SnapshotBackend<B> {
+    /// Wraps `inner`, keeping its snapshot at `path`.
+    pub fn new(inner: B, path: impl Into<PathBuf>, key: SnapshotKey) -> Self {
+        Self {
+            name: inner.name().to_string(),
+            capabilities: inner.capabilities(),
+            inner: RwLock::new(inner),
+            path: path.into(),
+            key,
+            prefix: String::new(),
+            max_age: None,
+            snapshot: None,
+            offline: AtomicBool::new(false),
+            initialized: AtomicBool::new(false),
+            inner_session: Mutex::new(None),
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            last_attempt: Mutex::new(None),
+        }
+    }
+
+    /// Sets the name prefix captured by snapshots (default: every item).
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Refuses to serve snapshots older than `max_age`.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets how often calls other than `authenticate()` try the inner
+    /// backend again while offline (default: 30 seconds).
+    pub fn with_retry_interval(mut self, interval: Duration) -> Self {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Captures the items under the prefix from the inner backend and saves
+    /// them to the snapshot file.
+    pub async fn take_snapshot(&mut self, session: &dyn Session) -> Result<()> {
+        self.retry().await;
+        self.online()?;
+        let session = self.session_for(session);
+        let inner = self.inner.read().await;
+        let snapshot = Snapshot::capture(&*inner, &self.prefix, &*session).await?;
+        drop(inner);
+        snapshot.save(&self.path, &self.key).await?;
+        self.snapshot = Some(snapshot);
+        Ok(())
+    }
+
+    /// Returns the loaded snapshot, if any.
+    pub fn snapshot(&self) -> Option<&Snapshot> {
+        self.snapshot.as_ref()
+    }
+
+    /// Returns `true` if reads are being served from the snapshot because
+    /// the inner backend couldn't be reached.
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` if an offline call should try the inner backend,
+    /// starting a new retry interval if so.
+    fn should_retry(&self) -> bool {
+        let mut last_attempt = self.last_attempt.lock().unwrap();
+        match *last_attempt {
+            Some(at) if at.elapsed() < self.retry_interval => false,
+            _ => {
+                *last_attempt = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    /// Initializes the inner backend if needed and authenticates it, going
+    /// back online if both succeed.
+    async fn reconnect(&self) -> Result<Arc<dyn Session>> {
+        let mut inner = self.inner.write().await;
+        if !self.initialized.load(Ordering::SeqCst) {
+            inner.init().await?;
+            self.initialized.store(true, Ordering::SeqCst);
+        }
+        let session = inner.authenticate().await?;
+        *self.inner_session.lock().unwrap() = Some(session.clone());
+        self.offline.store(false, Ordering::SeqCst);
+        Ok(session)
+    }
+
+    /// Tries to reconnect if offline and a retry is due.
+    async fn retry(&self) {
+        if self.is_offline() && self.should_retry() {
+            // Still offline on failure; the snapshot answers instead
+            let _ = self.reconnect().await;
+        }
+    }
+
+    /// Returns the session to pass to the inner backend for `session`.
+    fn session_for<'a>(&self, session: &'a dyn Session) -> InnerSession<'a> {
+        if session.token() == OFFLINE_TOKEN {
+            if let Some(inner) = self.inner_session.lock().unwrap().clone() {
+                return InnerSession::Reconnected(inner);
+            }
+        }
+        InnerSession::Caller(session)
+    }
+
+    /// Goes offline if `err` means the inner backend is unreachable and a
+    /// snapshot is loaded; otherwise returns `err`.
+    fn go_offline(&self, err: VaultmuxError) -> Result<()> {
+        if is_unavailable(&err) && self.snapshot.is_some() {
+            self.offline.store(true, Ordering::SeqCst);
+            *self.last_attempt.lock().unwrap() = Some(Instant::now());
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Returns the snapshot if it may be served.
+    fn usable_snapshot(&self) -> Result<&Snapshot> {
+        let snapshot = self.snapshot.as_ref().ok_or_else(|| {
+            VaultmuxError::Other(anyhow::anyhow!("no snapshot at {}", self.path.display()))
+        })?;
+
+        if let Some(max_age) = self.max_age {
+            let age = (Utc::now() - snapshot.created).to_std().unwrap_or_default();
+            if age > max_age {
+                return Err(VaultmuxError::Other(anyhow::anyhow!(
+                    "snapshot taken {} is older than the maximum age of {}s",
+                    snapshot.created.to_rfc3339(),
+                    max_age.as_secs()
+                )));
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Fails while offline, for operations the snapshot can't answer.
+    fn online(&self) -> Result<()> {
+        if self.is_offline() {
+            return Err(VaultmuxError::NotSupported(format!(
+                "{} is offline and serving a read-only snapshot",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Answers a read from the snapshot when offline or when the inner
+    /// backend is unavailable.
+    async fn read<T>(
+        &self,
+        inner: impl std::future::Future<Output = Result<T>>,
+        from_snapshot: impl FnOnce(&Snapshot) -> Result<T>,
+    ) -> Result<T> {
+        if self.is_offline() {
+            return from_snapshot(self.usable_snapshot()?);
+        }
+
+        match inner.await {
+            Err(e) if is_unavailable(&e) && self.snapshot.is_some() => {
+                from_snapshot(self.usable_snapshot()?)
+            }
+            other => other,
+        }
+    }
+}
+
+/// Runs a call that needs the inner backend, after reconnecting if due.
+macro_rules! when_online {
+    ($self:ident, read, |$inner:ident, $session:ident| $call:expr) => {{
+        $self.retry().await;
+        $self.online()?;
+        let $session = $self.session_for($session);
+        let $session = &*$session;
+        let $inner = $self.inner.read().await;
+        $call.await
+    }};
+    ($self:ident, write, |$inner:ident, $session:ident| $call:expr) => {{
+        $self.retry().await;
+        $self.online()?;
+        let $session = $self.session_for($session);
+        let $session = &*$session;
+        let mut guard = $self.inner.write().await;
+        let $inner = &mut *guard;
+        $call.await
+    }};
+}
+
+/// Runs a read, answering it from the snapshot when offline or when the
+/// inner backend is unavailable.
+macro_rules! from_snapshot_or {
+    ($self:ident, |$inner:ident, $session:ident| $call:expr, $from_snapshot:expr) => {{
+        $self.retry().await;
+        let $session = $self.session_for($session);
+        let $session = &*$session;
+        let $inner = $self.inner.read().await;
+        $self.read($call, $from_snapshot).await
+    }};
+}
+
+#[async_trait]
+impl<B: Backend> Backend for SnapshotBackend<B> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        if fs::try_exists(&self.path).await? {
+            self.snapshot = Some(Snapshot::load(&self.path, &self.key).await?);
+        }
+
+        let result = self.inner.write().await.init().await;
+        match result {
+            Ok(()) => {
+                self.initialized.store(true, Ordering::SeqCst);
+                self.offline.store(false, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => self.go_offline(e),
+        }
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        *self.inner_session.lock().unwrap() = None;
+        if self.is_offline() && !self.initialized.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.inner.write().await.close().await
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.is_offline() || self.inner.read().await.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        let result = if self.is_offline() {
+            *self.last_attempt.lock().unwrap() = Some(Instant::now());
+            self.reconnect().await
+        } else {
+            self.inner.write().await.authenticate().await
+        };
+
+        match result {
+            Ok(session) => Ok(session),
+            Err(e) => {
+                self.go_offline(e)?;
+                Ok(Arc::new(OfflineSession))
+            }
+        }
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        when_online!(self, write, |inner, session| inner.sync(session))
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        from_snapshot_or!(
+            self,
+            |inner, session| inner.get_item(name, session),
+            |snapshot| { snapshot.item(name) }
+        )
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        from_snapshot_or!(
+            self,
+            |inner, session| inner.get_notes(name, session),
+            |snapshot| { snapshot.notes(name) }
+        )
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        from_snapshot_or!(
+            self,
+            |inner, session| inner.item_exists(name, session),
+            |snapshot| { Ok(snapshot.get(name).is_some()) }
+        )
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        from_snapshot_or!(
+            self,
+            |inner, session| inner.list_items(session),
+            |snapshot| { Ok(snapshot.items().cloned().collect()) }
+        )
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .create_item(name, content, session))
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .update_item(name, content, session))
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .delete_item(name, session))
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        when_online!(self, write, |inner, session| inner
+            .put_many(entries, session))
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        when_online!(self, write, |inner, session| inner
+            .delete_many(names, session))
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .create_item_full(item, session))
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .update_item_full(item, session))
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner.update_item_if(
+            name,
+            content,
+            expected_revision,
+            session
+        ))
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        from_snapshot_or!(
+            self,
+            |inner, session| inner.get_bytes(name, session),
+            |snapshot| { encoding::decode_value(&snapshot.notes(name)?) }
+        )
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .put_bytes(name, data, session))
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        when_online!(self, read, |inner, session| inner
+            .list_versions(name, session))
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        when_online!(self, read, |inner, session| inner
+            .get_version(name, version_id, session))
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .disable_version(name, version_id, session))
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .destroy_version(name, version_id, session))
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .rollback(name, version_id, session))
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        when_online!(self, read, |inner, session| inner.get_tags(name, session))
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .set_tags(name, tags, session))
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        when_online!(self, read, |inner, session| inner
+            .list_items_by_tag(key, value, session))
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .rename_item(old_name, new_name, session))
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .move_item(name, location, session))
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .delete_item_with(name, options, session))
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        when_online!(self, read, |inner, session| inner
+            .list_deleted_items(session))
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .restore_item(name, session))
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        when_online!(self, read, |inner, session| inner.list_locations(session))
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        when_online!(self, read, |inner, session| inner
+            .location_exists(name, session))
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        when_online!(self, write, |inner, session| inner
+            .create_location(name, session))
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        when_online!(self, read, |inner, session| inner
+            .list_items_in_location(loc_type, loc_value, session))
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    async fn take(path: &Path, key: SnapshotKey) {
+        let inner = MockBackend::new();
+        inner.set_item("site-db-password", "hunter2").await;
+        inner.set_item("other", "not captured").await;
+
+        let mut backend = SnapshotBackend::new(inner, path, key).with_prefix("site-");
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+        backend.take_snapshot(&*session).await.unwrap();
+    }
+
+    fn unreachable() -> MockBackend {
+        let mut inner = MockBackend::new();
+        inner.auth_error = Some(VaultmuxError::CommandFailed("no route to host".to_string()));
+        inner
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_serves_reads_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.snapshot");
+        take(&path, SnapshotKey::Passphrase("correct horse".to_string())).await;
+
+        let key = SnapshotKey::Passphrase("correct horse".to_string());
+        let mut backend = SnapshotBackend::new(unreachable(), &path, key);
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+
+        assert!(backend.is_offline());
+        assert_eq!(
+            backend
+                .get_notes("site-db-password", &*session)
+                .await
+                .unwrap(),
+            "hunter2"
+        );
+        assert!(!backend.item_exists("other", &*session).await.unwrap());
+        assert!(matches!(
+            backend.create_item("new", "value", &*session).await,
+            Err(VaultmuxError::NotSupported(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_snapshot_reconnects_when_backend_returns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.snapshot");
+        let key = SnapshotKey::generate();
+        take(&path, key.clone()).await;
+        take(&path, key.clone()).await;
+        let names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["secrets.snapshot"]);
+
+        // The mock rejects any session but its latest, like a real vault
+        let mut inner = unreachable();
+        inner.check_sessions = true;
+        inner.set_item("site-db-password", "rotated").await;
+        let mut backend = SnapshotBackend::new(inner, &path, key.clone());
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+        assert!(backend.is_offline());
+
+        // Calls reconnect once the retry interval has passed
+        backend.inner.write().await.auth_error = None;
+        let notes = backend.get_notes("site-db-password", &*session).await;
+        assert_eq!(notes.unwrap(), "hunter2");
+        tokio::time::sleep(DEFAULT_RETRY_INTERVAL).await;
+        let notes = backend.get_notes("site-db-password", &*session).await;
+        assert_eq!(notes.unwrap(), "rotated");
+        assert!(!backend.is_offline());
+
+        // The offline session now stands for the new inner session
+        backend
+            .create_item("site-api-key", "value", &*session)
+            .await
+            .unwrap();
+
+        // authenticate() reconnects straight away, repeating a failed init
+        let mut inner = MockBackend::new();
+        inner.check_sessions = true;
+        inner.init_error = Some(VaultmuxError::CommandFailed("no route to host".to_string()));
+        let mut backend = SnapshotBackend::new(inner, &path, key);
+        backend.init().await.unwrap();
+        assert!(backend.is_offline());
+        backend.authenticate().await.unwrap();
+        assert!(backend.is_offline());
+
+        backend.inner.write().await.init_error = None;
+        let session = backend.authenticate().await.unwrap();
+        assert!(!backend.is_offline());
+        backend
+            .create_item("site-api-key", "value", &*session)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_integrity_and_max_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.snapshot");
+        let key = SnapshotKey::generate();
+        take(&path, key.clone()).await;
+
+        assert!(Snapshot::load(&path, &SnapshotKey::generate())
+            .await
+            .is_err());
+
+        // Editing the unencrypted header breaks the integrity check
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let tampered = contents.replacen("\"prefix\": \"site-\"", "\"prefix\": \"\"", 1);
+        assert_ne!(tampered, contents);
+        std::fs::write(&path, tampered).unwrap();
+        assert!(Snapshot::load(&path, &key).await.is_err());
+
+        std::fs::write(&path, contents).unwrap();
+        let mut backend =
+            SnapshotBackend::new(unreachable(), &path, key).with_max_age(Duration::ZERO);
+        backend.init().await.unwrap();
+        let session = backend.authenticate().await.unwrap();
+        assert!(backend
+            .get_notes("site-db-password", &*session)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,498 @@
+//! Backend that bounds how long each operation may take.
+
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, VaultmuxError, WatchOptions,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Runs `future` until it finishes, `timeout` passes or `cancel` fires.
+///
+/// On timeout or cancellation the future is dropped, which kills any child
+/// process it spawned and aborts in-flight SDK requests.
+pub(crate) async fn bounded<F: Future>(
+    future: F,
+    timeout: Option<Duration>,
+    cancel: Option<&CancellationToken>,
+    what: &str,
+) -> Result<F::Output> {
+    let limited = async {
+        match timeout {
+            Some(limit) => tokio::time::timeout(limit, future).await.map_err(|_| {
+                VaultmuxError::Timeout(format!("{} did not finish within {:?}", what, limit))
+            }),
+            None => Ok(future.await),
+        }
+    };
+
+    match cancel {
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(VaultmuxError::Cancelled),
+            result = limited => result,
+        },
+        None => limited.await,
+    }
+}
+
+/// Backend that fails operations taking longer than a timeout, or once a
+/// cancellation token fires.
+///
+/// An expired operation returns [`VaultmuxError::Timeout`] and a cancelled
+/// one [`VaultmuxError::Cancelled`]. The inner call is dropped in both
+/// cases: CLI backends kill the child process, and SDK backends abandon the
+/// request.
+///
+/// Streams from `list_items_stream` and `watch` have no timeout, but end
+/// when the token is cancelled.
+///
+/// [`factory::new_backend`](crate::factory::new_backend) applies this
+/// wrapper when [`Config::with_timeout`](crate::Config::with_timeout) or
+/// [`Config::with_cancellation`](crate::Config::with_cancellation) is set.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tokio_util::sync::CancellationToken;
+/// use vaultmux::middleware::TimeoutBackend;
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// let cancel = CancellationToken::new();
+/// let inner = factory::new_backend(Config::new(BackendType::Bitwarden))?;
+/// let mut backend = TimeoutBackend::new(inner)
+///     .with_timeout(Duration::from_secs(30))
+///     .with_cancellation(cancel.clone());
+///
+/// // Fails with VaultmuxError::Timeout instead of waiting on a hung `bw unlock`
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TimeoutBackend<B> {
+    inner: B,
+    timeout: Option<Duration>,
+    cancel: Option<CancellationToken>,
+}
+
+impl<B: Backend> TimeoutBackend<B> {
+    /// Wraps `inner` with no timeout and no cancellation token.
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            timeout: None,
+            cancel: None,
+        }
+    }
+
+    /// Sets the time each operation may take.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Cancels in-flight and future operations when `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+/// Runs a call within the timeout and cancellation token.
+macro_rules! bounded {
+    ($self:ident, $operation:literal, $call:expr) => {
+        bounded($call, $self.timeout, $self.cancel.as_ref(), $operation)
+            .await
+            .and_then(|result| result)
+    };
+}
+
+#[async_trait]
+impl<B: Backend> Backend for TimeoutBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        bounded!(self, "init", self.inner.init())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        bounded!(self, "close", self.inner.close())
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        bounded(
+            self.inner.is_authenticated(),
+            self.timeout,
+            self.cancel.as_ref(),
+            "is_authenticated",
+        )
+        .await
+        .unwrap_or(false)
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        bounded!(self, "authenticate", self.inner.authenticate())
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        bounded!(self, "sync", self.inner.sync(session))
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        bounded!(self, "get_item", self.inner.get_item(name, session))
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        bounded!(self, "get_notes", self.inner.get_notes(name, session))
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        bounded!(self, "item_exists", self.inner.item_exists(name, session))
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        bounded!(self, "list_items", self.inner.list_items(session))
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        let stream = self.inner.list_items_stream(options, session);
+        match &self.cancel {
+            Some(token) => stream.take_until(token.cancelled()).boxed(),
+            None => stream,
+        }
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(
+            self,
+            "create_item",
+            self.inner.create_item(name, content, session)
+        )
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(
+            self,
+            "update_item",
+            self.inner.update_item(name, content, session)
+        )
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        bounded!(self, "delete_item", self.inner.delete_item(name, session))
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        bounded!(self, "get_many", self.inner.get_many(names, session))
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        bounded!(self, "put_many", self.inner.put_many(entries, session))
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        bounded!(self, "delete_many", self.inner.delete_many(names, session))
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        bounded!(
+            self,
+            "create_item_full",
+            self.inner.create_item_full(item, session)
+        )
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        bounded!(
+            self,
+            "update_item_full",
+            self.inner.update_item_full(item, session)
+        )
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(
+            self,
+            "update_item_if",
+            self.inner
+                .update_item_if(name, content, expected_revision, session)
+        )
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        bounded!(self, "get_bytes", self.inner.get_bytes(name, session))
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        bounded!(self, "put_bytes", self.inner.put_bytes(name, data, session))
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        bounded!(
+            self,
+            "list_versions",
+            self.inner.list_versions(name, session)
+        )
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        bounded!(
+            self,
+            "get_version",
+            self.inner.get_version(name, version_id, session)
+        )
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(
+            self,
+            "disable_version",
+            self.inner.disable_version(name, version_id, session)
+        )
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(
+            self,
+            "destroy_version",
+            self.inner.destroy_version(name, version_id, session)
+        )
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(
+            self,
+            "rollback",
+            self.inner.rollback(name, version_id, session)
+        )
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        bounded!(self, "get_tags", self.inner.get_tags(name, session))
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(self, "set_tags", self.inner.set_tags(name, tags, session))
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        bounded!(
+            self,
+            "list_items_by_tag",
+            self.inner.list_items_by_tag(key, value, session)
+        )
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(
+            self,
+            "rename_item",
+            self.inner.rename_item(old_name, new_name, session)
+        )
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        bounded!(
+            self,
+            "move_item",
+            self.inner.move_item(name, location, session)
+        )
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        bounded!(
+            self,
+            "delete_item_with",
+            self.inner.delete_item_with(name, options, session)
+        )
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        bounded!(
+            self,
+            "list_deleted_items",
+            self.inner.list_deleted_items(session)
+        )
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        bounded!(self, "restore_item", self.inner.restore_item(name, session))
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        let stream = self.inner.watch(options, session);
+        match &self.cancel {
+            Some(token) => stream.take_until(token.cancelled()).boxed(),
+            None => stream,
+        }
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        bounded!(self, "list_locations", self.inner.list_locations(session))
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        bounded!(
+            self,
+            "location_exists",
+            self.inner.location_exists(name, session)
+        )
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        bounded!(
+            self,
+            "create_location",
+            self.inner.create_location(name, session)
+        )
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        bounded!(
+            self,
+            "list_items_in_location",
+            self.inner
+                .list_items_in_location(loc_type, loc_value, session)
+        )
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bounded_timeout_and_cancellation() {
+        let hang = std::future::pending::<()>();
+        let result = bounded(hang, Some(Duration::from_secs(5)), None, "get_notes").await;
+        assert!(matches!(result, Err(VaultmuxError::Timeout(_))));
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = bounded(async { 1 }, None, Some(&cancel), "get_notes").await;
+        assert!(matches!(result, Err(VaultmuxError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_backend_rejects_calls() {
+        let cancel = CancellationToken::new();
+        let inner = MockBackend::new();
+        inner.set_item("api-key", "secret").await;
+        let mut backend = TimeoutBackend::new(inner)
+            .with_timeout(Duration::from_secs(1))
+            .with_cancellation(cancel.clone());
+        let session = backend.authenticate().await.unwrap();
+
+        assert_eq!(
+            backend.get_notes("api-key", &*session).await.unwrap(),
+            "secret"
+        );
+
+        cancel.cancel();
+        let result = backend.get_notes("api-key", &*session).await;
+        assert!(matches!(result, Err(VaultmuxError::Cancelled)));
+        let items: Vec<_> = backend
+            .list_items_stream(&ListOptions::default(), &*session)
+            .collect()
+            .await;
+        assert!(items.is_empty());
+    }
+}
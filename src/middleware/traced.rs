@@ -0,0 +1,619 @@
+//! Backend that wraps every operation in a `tracing` span.
+
+use crate::telemetry::redact;
+use crate::{
+    Backend, Capabilities, ChangeEvent, DeleteOptions, DeletedItem, Item, ItemVersion, ListOptions,
+    Result, Session, WatchOptions,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+
+/// Backend that records each operation as a `vaultmux.operation` span.
+///
+/// Spans carry these fields:
+///
+/// - `backend`, `operation`: which call was made
+/// - `item`: the item name, redacted to its first characters and a hash
+/// - `latency_ms`, `outcome` (`ok` or `error`) and `error`, the
+///   [kind](crate::VaultmuxError::kind) of error
+/// - `request_id`: the cloud provider's request ID, where the SDK reports one
+///
+/// CLI commands run during the operation appear as child
+/// `vaultmux.command` spans with the program, subcommand, `exit_code` and
+/// `latency_ms`. Secret values and command arguments past the subcommand are
+/// never recorded.
+///
+/// [`factory::new_backend`](crate::factory::new_backend) applies this
+/// wrapper to every backend when the `tracing` feature is enabled. Streams
+/// from `list_items_stream` and `watch` are passed through without a span.
+///
+/// # Example
+///
+/// ```no_run
+/// use vaultmux::middleware::TracedBackend;
+/// use vaultmux::{factory, Backend, BackendType, Config};
+///
+/// # async fn example() -> vaultmux::Result<()> {
+/// // Already traced; wrapping by hand is only needed for custom backends
+/// let mut backend = factory::new_backend(Config::new(BackendType::Bitwarden))?;
+/// backend.init().await?;
+/// let session = backend.authenticate().await?;
+///
+/// // A `vaultmux.operation` span with a `vaultmux.command` child for `bw get`
+/// let password = backend.get_notes("db-password", &*session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TracedBackend<B> {
+    inner: B,
+}
+
+impl<B: Backend> TracedBackend<B> {
+    /// Wraps `inner`.
+    pub fn new(inner: B) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn span(&self, operation: &str, item: Option<&str>) -> Span {
+        let span = tracing::info_span!(
+            "vaultmux.operation",
+            backend = self.inner.name(),
+            operation,
+            item = Empty,
+            latency_ms = Empty,
+            outcome = Empty,
+            error = Empty,
+            request_id = Empty,
+        );
+        if let Some(item) = item {
+            span.record("item", redact(item));
+        }
+        span
+    }
+}
+
+/// Records how an operation ended on its span.
+fn finish<T>(span: &Span, started: Instant, result: &Result<T>) {
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    match result {
+        Ok(_) => {
+            span.record("outcome", "ok");
+        }
+        Err(e) => {
+            span.record("outcome", "error");
+            // Messages can name the item, so only the kind is recorded
+            span.record("error", e.kind());
+        }
+    }
+}
+
+/// Runs a call inside an operation span.
+macro_rules! traced {
+    ($self:ident, $operation:literal, $item:expr, $call:expr) => {{
+        let span = $self.span($operation, $item);
+        let started = Instant::now();
+        let result = $call.instrument(span.clone()).await;
+        finish(&span, started, &result);
+        result
+    }};
+}
+
+#[async_trait]
+impl<B: Backend> Backend for TracedBackend<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        traced!(self, "init", None, self.inner.init())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        traced!(self, "close", None, self.inner.close())
+    }
+
+    async fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated().await
+    }
+
+    async fn authenticate(&mut self) -> Result<Arc<dyn Session>> {
+        traced!(self, "authenticate", None, self.inner.authenticate())
+    }
+
+    async fn sync(&mut self, session: &dyn Session) -> Result<()> {
+        traced!(self, "sync", None, self.inner.sync(session))
+    }
+
+    async fn get_item(&self, name: &str, session: &dyn Session) -> Result<Item> {
+        traced!(
+            self,
+            "get_item",
+            Some(name),
+            self.inner.get_item(name, session)
+        )
+    }
+
+    async fn get_notes(&self, name: &str, session: &dyn Session) -> Result<String> {
+        traced!(
+            self,
+            "get_notes",
+            Some(name),
+            self.inner.get_notes(name, session)
+        )
+    }
+
+    async fn item_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        traced!(
+            self,
+            "item_exists",
+            Some(name),
+            self.inner.item_exists(name, session)
+        )
+    }
+
+    async fn list_items(&self, session: &dyn Session) -> Result<Vec<Item>> {
+        traced!(self, "list_items", None, self.inner.list_items(session))
+    }
+
+    fn list_items_stream<'a>(
+        &'a self,
+        options: &'a ListOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<Item>> {
+        self.inner.list_items_stream(options, session)
+    }
+
+    async fn create_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "create_item",
+            Some(name),
+            self.inner.create_item(name, content, session)
+        )
+    }
+
+    async fn update_item(
+        &mut self,
+        name: &str,
+        content: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "update_item",
+            Some(name),
+            self.inner.update_item(name, content, session)
+        )
+    }
+
+    async fn delete_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        traced!(
+            self,
+            "delete_item",
+            Some(name),
+            self.inner.delete_item(name, session)
+        )
+    }
+
+    async fn get_many(&self, names: &[&str], session: &dyn Session) -> Result<Vec<Result<String>>> {
+        traced!(self, "get_many", None, self.inner.get_many(names, session))
+    }
+
+    async fn put_many(
+        &mut self,
+        entries: &[(&str, &str)],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        traced!(
+            self,
+            "put_many",
+            None,
+            self.inner.put_many(entries, session)
+        )
+    }
+
+    async fn delete_many(
+        &mut self,
+        names: &[&str],
+        session: &dyn Session,
+    ) -> Result<Vec<Result<()>>> {
+        traced!(
+            self,
+            "delete_many",
+            None,
+            self.inner.delete_many(names, session)
+        )
+    }
+
+    async fn create_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        traced!(
+            self,
+            "create_item_full",
+            Some(&item.name),
+            self.inner.create_item_full(item, session)
+        )
+    }
+
+    async fn update_item_full(&mut self, item: &Item, session: &dyn Session) -> Result<()> {
+        traced!(
+            self,
+            "update_item_full",
+            Some(&item.name),
+            self.inner.update_item_full(item, session)
+        )
+    }
+
+    async fn update_item_if(
+        &mut self,
+        name: &str,
+        content: &str,
+        expected_revision: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "update_item_if",
+            Some(name),
+            self.inner
+                .update_item_if(name, content, expected_revision, session)
+        )
+    }
+
+    async fn get_bytes(&self, name: &str, session: &dyn Session) -> Result<Vec<u8>> {
+        traced!(
+            self,
+            "get_bytes",
+            Some(name),
+            self.inner.get_bytes(name, session)
+        )
+    }
+
+    async fn put_bytes(&mut self, name: &str, data: &[u8], session: &dyn Session) -> Result<()> {
+        traced!(
+            self,
+            "put_bytes",
+            Some(name),
+            self.inner.put_bytes(name, data, session)
+        )
+    }
+
+    async fn list_versions(&self, name: &str, session: &dyn Session) -> Result<Vec<ItemVersion>> {
+        traced!(
+            self,
+            "list_versions",
+            Some(name),
+            self.inner.list_versions(name, session)
+        )
+    }
+
+    async fn get_version(
+        &self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<String> {
+        traced!(
+            self,
+            "get_version",
+            Some(name),
+            self.inner.get_version(name, version_id, session)
+        )
+    }
+
+    async fn disable_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "disable_version",
+            Some(name),
+            self.inner.disable_version(name, version_id, session)
+        )
+    }
+
+    async fn destroy_version(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "destroy_version",
+            Some(name),
+            self.inner.destroy_version(name, version_id, session)
+        )
+    }
+
+    async fn rollback(
+        &mut self,
+        name: &str,
+        version_id: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "rollback",
+            Some(name),
+            self.inner.rollback(name, version_id, session)
+        )
+    }
+
+    async fn get_tags(&self, name: &str, session: &dyn Session) -> Result<HashMap<String, String>> {
+        traced!(
+            self,
+            "get_tags",
+            Some(name),
+            self.inner.get_tags(name, session)
+        )
+    }
+
+    async fn set_tags(
+        &mut self,
+        name: &str,
+        tags: &HashMap<String, String>,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "set_tags",
+            Some(name),
+            self.inner.set_tags(name, tags, session)
+        )
+    }
+
+    async fn list_items_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        traced!(
+            self,
+            "list_items_by_tag",
+            None,
+            self.inner.list_items_by_tag(key, value, session)
+        )
+    }
+
+    async fn rename_item(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "rename_item",
+            Some(old_name),
+            self.inner.rename_item(old_name, new_name, session)
+        )
+    }
+
+    async fn move_item(&mut self, name: &str, location: &str, session: &dyn Session) -> Result<()> {
+        traced!(
+            self,
+            "move_item",
+            Some(name),
+            self.inner.move_item(name, location, session)
+        )
+    }
+
+    async fn delete_item_with(
+        &mut self,
+        name: &str,
+        options: &DeleteOptions,
+        session: &dyn Session,
+    ) -> Result<()> {
+        traced!(
+            self,
+            "delete_item_with",
+            Some(name),
+            self.inner.delete_item_with(name, options, session)
+        )
+    }
+
+    async fn list_deleted_items(&self, session: &dyn Session) -> Result<Vec<DeletedItem>> {
+        traced!(
+            self,
+            "list_deleted_items",
+            None,
+            self.inner.list_deleted_items(session)
+        )
+    }
+
+    async fn restore_item(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        traced!(
+            self,
+            "restore_item",
+            Some(name),
+            self.inner.restore_item(name, session)
+        )
+    }
+
+    fn watch<'a>(
+        &'a self,
+        options: &'a WatchOptions,
+        session: &'a dyn Session,
+    ) -> BoxStream<'a, Result<ChangeEvent>> {
+        self.inner.watch(options, session)
+    }
+
+    async fn list_locations(&self, session: &dyn Session) -> Result<Vec<String>> {
+        traced!(
+            self,
+            "list_locations",
+            None,
+            self.inner.list_locations(session)
+        )
+    }
+
+    async fn location_exists(&self, name: &str, session: &dyn Session) -> Result<bool> {
+        traced!(
+            self,
+            "location_exists",
+            Some(name),
+            self.inner.location_exists(name, session)
+        )
+    }
+
+    async fn create_location(&mut self, name: &str, session: &dyn Session) -> Result<()> {
+        traced!(
+            self,
+            "create_location",
+            Some(name),
+            self.inner.create_location(name, session)
+        )
+    }
+
+    async fn list_items_in_location(
+        &self,
+        loc_type: &str,
+        loc_value: &str,
+        session: &dyn Session,
+    ) -> Result<Vec<Item>> {
+        traced!(
+            self,
+            "list_items_in_location",
+            None,
+            self.inner
+                .list_items_in_location(loc_type, loc_value, session)
+        )
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockBackend;
+    use crate::VaultmuxError;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Mutex;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Subscriber that keeps every span field recorded as `(name, value)`.
+    #[derive(Clone, Default)]
+    struct FieldRecorder {
+        fields: Arc<Mutex<Vec<(String, String)>>>,
+        next_id: Arc<AtomicU64>,
+    }
+
+    impl FieldRecorder {
+        fn value(&self, name: &str) -> Option<String> {
+            self.fields
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.clone())
+        }
+    }
+
+    impl Visit for FieldRecorder {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.fields
+                .lock()
+                .unwrap()
+                .push((field.name().to_string(), value.to_string()));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.fields
+                .lock()
+                .unwrap()
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+
+    impl Subscriber for FieldRecorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            span.record(&mut self.clone());
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+        }
+
+        fn record(&self, _span: &Id, values: &Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[tokio::test]
+    async fn test_traced_backend_forwards_results() {
+        let inner = MockBackend::new();
+        inner.set_item("api-key", "secret").await;
+        let mut backend = TracedBackend::new(inner);
+        let session = backend.authenticate().await.unwrap();
+
+        assert_eq!(backend.name(), "mock");
+        assert_eq!(
+            backend.get_notes("api-key", &*session).await.unwrap(),
+            "secret"
+        );
+        assert!(matches!(
+            backend.get_notes("missing", &*session).await,
+            Err(VaultmuxError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_traced_backend_records_redacted_fields() {
+        let recorder = FieldRecorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let mut backend = TracedBackend::new(MockBackend::new());
+        let session = backend.authenticate().await.unwrap();
+        assert!(backend
+            .get_notes("prod/db-password", &*session)
+            .await
+            .is_err());
+
+        assert_eq!(recorder.value("backend").as_deref(), Some("mock"));
+        assert_eq!(recorder.value("operation").as_deref(), Some("get_notes"));
+        assert_eq!(recorder.value("item"), Some(redact("prod/db-password")));
+        assert_eq!(recorder.value("outcome").as_deref(), Some("error"));
+        assert_eq!(recorder.value("error").as_deref(), Some("not_found"));
+        assert!(recorder.value("latency_ms").is_some());
+        assert!(recorder
+            .fields
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|(_, value)| !value.contains("db-password")));
+    }
+}
@@ -0,0 +1,203 @@
+//! Option types for backend operations.
+
+use crate::pattern::glob_match;
+use crate::{Item, Result};
+use futures::stream::BoxStream;
+use futures::{Future, Stream, StreamExt, TryStreamExt};
+use std::time::Duration;
+
+/// Options for [`Backend::delete_item_with`](crate::Backend::delete_item_with).
+///
+/// The default moves the item to the backend's trash (or recovery window)
+/// for the backend's default retention period.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use vaultmux::DeleteOptions;
+///
+/// let soft = DeleteOptions::new().with_recovery_window(Duration::from_secs(7 * 86400));
+/// assert!(!soft.force);
+///
+/// let hard = DeleteOptions::new().with_force(true);
+/// assert!(hard.force);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeleteOptions {
+    /// How long the item stays recoverable (default: backend-specific)
+    ///
+    /// Only AWS and the mock backend honour a custom window; AWS rounds it
+    /// to whole days and clamps it to 7-30 days. Bitwarden and Azure use
+    /// their own retention settings.
+    pub recovery_window: Option<Duration>,
+
+    /// Delete permanently, bypassing the trash
+    pub force: bool,
+}
+
+impl DeleteOptions {
+    /// Creates options for a recoverable delete with the backend's default
+    /// retention.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long the item stays recoverable.
+    pub fn with_recovery_window(mut self, window: Duration) -> Self {
+        self.recovery_window = Some(window);
+        self
+    }
+
+    /// Sets whether to delete permanently.
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+}
+
+/// Options for [`Backend::list_items_stream`](crate::Backend::list_items_stream).
+///
+/// The default lists every item under the configured prefix.
+///
+/// # Example
+///
+/// ```
+/// use vaultmux::ListOptions;
+///
+/// let options = ListOptions::new()
+///     .with_prefix("prod/")
+///     .with_name_glob("*-key")
+///     .with_limit(100);
+///
+/// assert!(options.matches("prod/api-key"));
+/// assert!(!options.matches("staging/api-key"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListOptions {
+    /// Only list items whose name starts with this prefix
+    ///
+    /// Applied after the configured item prefix, and pushed to the backend
+    /// where it can filter server-side (AWS, GCP, Bitwarden).
+    pub prefix: Option<String>,
+
+    /// Only list items whose name matches this glob (`*` and `?`)
+    pub name_glob: Option<String>,
+
+    /// Items to request per page from backends that paginate (AWS, GCP)
+    pub page_size: Option<usize>,
+
+    /// Stop after this many items
+    pub limit: Option<usize>,
+}
+
+impl ListOptions {
+    /// Creates options that list every item.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name prefix.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Sets the name glob.
+    pub fn with_name_glob(mut self, glob: impl Into<String>) -> Self {
+        self.name_glob = Some(glob.into());
+        self
+    }
+
+    /// Sets the page size.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Sets the maximum number of items.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns `true` if an item name passes the prefix and glob filters.
+    pub fn matches(&self, name: &str) -> bool {
+        self.prefix
+            .as_deref()
+            .is_none_or(|prefix| name.starts_with(prefix))
+            && self
+                .name_glob
+                .as_deref()
+                .is_none_or(|glob| glob_match(glob, name))
+    }
+
+    /// Applies the name filters and the limit to a stream of items.
+    ///
+    /// Server-side filters can be coarser than ours (AWS name filters
+    /// ignore case), so every backend runs its stream through this.
+    pub(crate) fn apply<'a>(
+        &'a self,
+        items: impl Stream<Item = Result<Item>> + Send + 'a,
+    ) -> BoxStream<'a, Result<Item>> {
+        let items = items.try_filter(move |item| futures::future::ready(self.matches(&item.name)));
+
+        match self.limit {
+            Some(limit) => items.take(limit).boxed(),
+            None => items.boxed(),
+        }
+    }
+}
+
+/// Streams items from a paginated listing.
+///
+/// `fetch` is called with the previous page's continuation token (`None`
+/// for the first page) and returns a page of items plus the next token.
+/// Pages are only fetched as the stream is polled.
+#[cfg_attr(not(any(feature = "aws", feature = "gcp")), allow(dead_code))]
+pub(crate) fn paginate<'a, F, Fut>(mut fetch: F) -> impl Stream<Item = Result<Item>> + Send + 'a
+where
+    F: FnMut(Option<String>) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<(Vec<Item>, Option<String>)>> + Send + 'a,
+{
+    // `None` once the last page has been fetched
+    futures::stream::try_unfold(Some(None), move |token: Option<Option<String>>| {
+        let page = token.map(&mut fetch);
+        async move {
+            let Some(page) = page else {
+                return Ok(None);
+            };
+            let (items, next) = page.await?;
+            Result::Ok(Some((items, next.map(Some))))
+        }
+    })
+    .map_ok(|items| futures::stream::iter(items.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_paginate_stops_at_limit() {
+        let fetched = std::sync::atomic::AtomicUsize::new(0);
+        let pages = paginate(|token: Option<String>| {
+            fetched.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let page: usize = token.map_or(0, |t| t.parse().unwrap());
+            async move {
+                let items = (0..3)
+                    .map(|i| Item::new_secure_note(format!("item-{}-{}", page, i), ""))
+                    .collect();
+                Ok((items, Some((page + 1).to_string())))
+            }
+        });
+
+        let options = ListOptions::new().with_name_glob("item-*-0").with_limit(2);
+        let items: Vec<Item> = options.apply(pages).try_collect().await.unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].name, "item-1-0");
+        assert_eq!(fetched.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,66 @@
+//! Glob matching for item names.
+
+/// Returns `true` if `name` matches the glob `pattern`.
+///
+/// `*` matches any run of characters (including `/`) and `?` matches a
+/// single character; every other character matches itself.
+///
+/// # Example
+///
+/// ```
+/// use vaultmux::pattern::glob_match;
+///
+/// assert!(glob_match("prod/*-key", "prod/api-key"));
+/// assert!(glob_match("db-?", "db-1"));
+/// assert!(!glob_match("prod/*", "staging/api-key"));
+/// ```
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` absorb one more character and retry
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "anything/at/all"));
+        assert!(glob_match("api-*", "api-key"));
+        assert!(glob_match("*.password", "prod.db.password"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(glob_match("a*b", "abab"));
+        assert!(!glob_match("a*b", "abac"));
+        assert!(!glob_match("api-?", "api-12"));
+        assert!(!glob_match("", "x"));
+    }
+}
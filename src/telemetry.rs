@@ -0,0 +1,123 @@
+//! Tracing and metrics instrumentation, compiled in with the `tracing` and
+//! `metrics` features.
+//!
+//! Without the features these helpers do nothing, so call sites don't need
+//! their own `cfg` attributes.
+
+use std::future::Future;
+
+/// Runs a CLI command's future inside a `vaultmux.command` span.
+///
+/// Only the program and its first argument (the subcommand) are recorded;
+/// later arguments may carry secret values.
+pub(crate) async fn in_command_span<F: Future>(
+    program: &str,
+    args: &[&str],
+    future: F,
+) -> F::Output {
+    #[cfg(feature = "tracing")]
+    {
+        use tracing::Instrument;
+
+        let span = tracing::info_span!(
+            "vaultmux.command",
+            program,
+            command = args.first().copied().unwrap_or_default(),
+            exit_code = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+        );
+        let started = std::time::Instant::now();
+        let output = future.instrument(span.clone()).await;
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        output
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (program, args);
+        future.await
+    }
+}
+
+/// Records a subprocess exit code on the current command span.
+pub(crate) fn record_exit_code(code: Option<i32>) {
+    #[cfg(feature = "tracing")]
+    if let Some(code) = code {
+        tracing::Span::current().record("exit_code", code);
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = code;
+}
+
+/// Records a cloud SDK request ID on the current operation span.
+#[cfg_attr(not(feature = "aws"), allow(dead_code))]
+pub(crate) fn record_request_id(request_id: Option<&str>) {
+    #[cfg(feature = "tracing")]
+    if let Some(request_id) = request_id {
+        tracing::Span::current().record("request_id", request_id);
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = request_id;
+}
+
+/// Counts a cache lookup by `result` (`hit`, `stale` or `miss`).
+pub(crate) fn record_cache_lookup(backend: &str, result: &'static str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        crate::middleware::metered::CACHE_LOOKUPS,
+        "backend" => backend.to_string(),
+        "result" => result,
+    )
+    .increment(1);
+
+    #[cfg(not(feature = "metrics"))]
+    let _ = (backend, result);
+}
+
+/// Logs a change a dry run skipped.
+pub(crate) fn log_planned_change(change: &crate::middleware::PlannedChange) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(
+        target: "vaultmux::dry_run",
+        operation = %change.operation,
+        item = %change.item,
+        size = change.size,
+        fingerprint = change.fingerprint.as_deref(),
+        detail = change.detail.as_deref(),
+        "dry run: {}",
+        change
+    );
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = change;
+}
+
+/// Shortens an item name to a recognizable but non-revealing form.
+///
+/// Keeps the first two characters and appends a stable hash, so the same
+/// item can be followed across log lines without exposing its full name.
+#[cfg(feature = "tracing")]
+pub(crate) fn redact(name: &str) -> String {
+    // 32-bit FNV-1a
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    let visible: String = name.chars().take(2).collect();
+    format!("{}…{:08x}", visible, hash)
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_is_stable_and_hides_name() {
+        let redacted = redact("prod/db-password");
+        assert_eq!(redacted, redact("prod/db-password"));
+        assert_ne!(redacted, redact("prod/db-passwore"));
+        assert!(redacted.starts_with("pr…"));
+        assert!(!redacted.contains("password"));
+    }
+}
@@ -0,0 +1,320 @@
+//! Change notifications for watched items.
+//!
+//! [`Backend::watch`](crate::Backend::watch) yields a [`ChangeEvent`] whenever
+//! a watched item is created, updated or deleted. None of the supported vaults
+//! push changes to clients, so backends poll at [`WatchOptions::interval`].
+
+use crate::backend::BATCH_CONCURRENCY;
+use crate::{Backend, Item, ListOptions, Result, Session, VaultmuxError};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+/// Default time between polls (30 seconds).
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Which items to watch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchTarget {
+    /// These item names (each polled with `get_item`)
+    Names(Vec<String>),
+    /// Every item whose name starts with this prefix (polled with one listing)
+    Prefix(String),
+}
+
+/// Options for [`Backend::watch`](crate::Backend::watch).
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use vaultmux::WatchOptions;
+///
+/// let options = WatchOptions::names(["db/password"]).with_interval(Duration::from_secs(60));
+/// assert_eq!(options.interval, Duration::from_secs(60));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// Items to watch
+    pub target: WatchTarget,
+
+    /// Time between polls (default: 30 seconds)
+    pub interval: Duration,
+}
+
+impl WatchOptions {
+    /// Watches the given item names.
+    pub fn names<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            target: WatchTarget::Names(names.into_iter().map(Into::into).collect()),
+            interval: DEFAULT_WATCH_INTERVAL,
+        }
+    }
+
+    /// Watches every item under a name prefix.
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            target: WatchTarget::Prefix(prefix.into()),
+            interval: DEFAULT_WATCH_INTERVAL,
+        }
+    }
+
+    /// Sets the time between polls.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+/// What happened to a watched item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The item appeared
+    Created,
+    /// The item's revision changed
+    Updated,
+    /// The item disappeared
+    Deleted,
+}
+
+/// A change to a watched item.
+///
+/// Revisions are the item's [`revision`](crate::Item::revision) where the
+/// backend reports one, else its modification time, else a digest of its
+/// value keyed per watch. They are only meant to be compared with each
+/// other within one watch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEvent {
+    /// Item name
+    pub name: String,
+
+    /// What happened
+    pub kind: ChangeKind,
+
+    /// Revision before the change (`None` for [`ChangeKind::Created`])
+    pub old_revision: Option<String>,
+
+    /// Revision after the change (`None` for [`ChangeKind::Deleted`])
+    pub new_revision: Option<String>,
+}
+
+/// State carried between polls.
+struct PollState {
+    /// Revisions from the last successful poll, `None` before the first
+    seen: Option<HashMap<String, String>>,
+    pending: VecDeque<ChangeEvent>,
+    polled: bool,
+    /// Random key for value digests, so they can't be checked against
+    /// guessed values outside this watch
+    digest_key: RandomState,
+}
+
+/// Watches items by polling, for backends without change notifications.
+///
+/// The first poll records the current revisions without yielding events.
+/// A failed poll yields its error and polling continues at the next interval.
+pub(crate) fn poll<'a, B>(
+    backend: &'a B,
+    options: &'a WatchOptions,
+    session: &'a dyn Session,
+) -> BoxStream<'a, Result<ChangeEvent>>
+where
+    B: Backend + ?Sized,
+{
+    let state = PollState {
+        seen: None,
+        pending: VecDeque::new(),
+        polled: false,
+        digest_key: RandomState::new(),
+    };
+
+    futures::stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((Ok(event), state));
+            }
+
+            if state.polled {
+                tokio::time::sleep(options.interval).await;
+            }
+            state.polled = true;
+
+            match snapshot(backend, &options.target, &state.digest_key, session).await {
+                Ok(current) => {
+                    if let Some(ref previous) = state.seen {
+                        state.pending.extend(diff(previous, &current));
+                    }
+                    state.seen = Some(current);
+                }
+                // Keep the previous snapshot so the next poll diffs against it
+                Err(e) => return Some((Err(e), state)),
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Reads the current revision of every watched item.
+async fn snapshot<B>(
+    backend: &B,
+    target: &WatchTarget,
+    digest_key: &RandomState,
+    session: &dyn Session,
+) -> Result<HashMap<String, String>>
+where
+    B: Backend + ?Sized,
+{
+    let items: Vec<Item> = match target {
+        WatchTarget::Names(names) => {
+            let lookups: Vec<_> = names
+                .iter()
+                .map(|name| backend.get_item(name, session))
+                .collect();
+            let results: Vec<Result<Item>> = futures::stream::iter(lookups)
+                .buffered(BATCH_CONCURRENCY)
+                .collect()
+                .await;
+
+            let mut items = Vec::new();
+            for result in results {
+                match result {
+                    Ok(item) => items.push(item),
+                    Err(VaultmuxError::NotFound(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            items
+        }
+        WatchTarget::Prefix(prefix) => {
+            let options = ListOptions::new().with_prefix(prefix.clone());
+            backend
+                .list_items_stream(&options, session)
+                .try_collect()
+                .await?
+        }
+    };
+
+    let mut revisions = HashMap::with_capacity(items.len());
+    for item in items {
+        let revision = match fingerprint(&item) {
+            Some(revision) => revision,
+            // Listings without revisions or timestamps need the item itself
+            None => match backend.get_item(&item.name, session).await {
+                Ok(full) => fingerprint(&full).unwrap_or_else(|| digest(digest_key, &full)),
+                Err(VaultmuxError::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            },
+        };
+        revisions.insert(item.name, revision);
+    }
+
+    Ok(revisions)
+}
+
+/// Returns the item's revision, or its modification time.
+fn fingerprint(item: &Item) -> Option<String> {
+    item.revision
+        .clone()
+        .or_else(|| item.modified.map(|modified| modified.to_rfc3339()))
+}
+
+/// Hashes an item's value, for backends that track neither revisions nor
+/// modification times.
+fn digest(key: &RandomState, item: &Item) -> String {
+    let mut fields: Vec<_> = item.fields.iter().flatten().collect();
+    fields.sort();
+    format!("{:016x}", key.hash_one((&item.notes, fields)))
+}
+
+/// Compares two snapshots, returning events sorted by name.
+fn diff(previous: &HashMap<String, String>, current: &HashMap<String, String>) -> Vec<ChangeEvent> {
+    let mut events: Vec<ChangeEvent> = current
+        .iter()
+        .filter_map(|(name, revision)| match previous.get(name) {
+            None => Some(ChangeEvent {
+                name: name.clone(),
+                kind: ChangeKind::Created,
+                old_revision: None,
+                new_revision: Some(revision.clone()),
+            }),
+            Some(old) if old != revision => Some(ChangeEvent {
+                name: name.clone(),
+                kind: ChangeKind::Updated,
+                old_revision: Some(old.clone()),
+                new_revision: Some(revision.clone()),
+            }),
+            Some(_) => None,
+        })
+        .chain(
+            previous
+                .iter()
+                .filter(|(name, _)| !current.contains_key(*name))
+                .map(|(name, old)| ChangeEvent {
+                    name: name.clone(),
+                    kind: ChangeKind::Deleted,
+                    old_revision: Some(old.clone()),
+                    new_revision: None,
+                }),
+        )
+        .collect();
+
+    events.sort_by(|a, b| a.name.cmp(&b.name));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot_of(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, revision)| (name.to_string(), revision.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let previous = snapshot_of(&[("a", "1"), ("b", "1"), ("c", "1")]);
+        let current = snapshot_of(&[("a", "1"), ("b", "2"), ("d", "1")]);
+
+        let events = diff(&previous, &current);
+        let kinds: Vec<_> = events.iter().map(|e| (e.name.as_str(), e.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("b", ChangeKind::Updated),
+                ("c", ChangeKind::Deleted),
+                ("d", ChangeKind::Created),
+            ]
+        );
+        assert_eq!(events[0].old_revision.as_deref(), Some("1"));
+        assert_eq!(events[0].new_revision.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_digest_ignores_field_order() {
+        let mut a = Item::new_secure_note("x", "value");
+        let mut b = a.clone();
+        a.fields = Some(HashMap::from([
+            ("k1".to_string(), "v1".to_string()),
+            ("k2".to_string(), "v2".to_string()),
+        ]));
+        b.fields = Some(HashMap::from([
+            ("k2".to_string(), "v2".to_string()),
+            ("k1".to_string(), "v1".to_string()),
+        ]));
+
+        let key = RandomState::new();
+        assert_eq!(digest(&key, &a), digest(&key, &b));
+        b.notes = Some("other".to_string());
+        assert_ne!(digest(&key, &a), digest(&key, &b));
+    }
+}